@@ -7,6 +7,7 @@ pub mod error;
 pub mod identities;
 pub mod insight;
 pub mod platform_info;
+pub mod scheduler;
 pub mod state;
 pub mod strategies;
 pub mod wallet;
@@ -14,6 +15,7 @@ pub mod wallet;
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
+    path::PathBuf,
     time::Duration,
 };
 
@@ -25,14 +27,19 @@ use dpp::{
 };
 use serde::Serialize;
 pub(crate) use state::AppState;
+pub use state::AppStateError;
 use strategy_tests::Strategy;
-use tokio::sync::{MappedMutexGuard, MutexGuard};
+use tokio::sync::{broadcast, MappedMutexGuard, MutexGuard};
+use tokio_util::sync::CancellationToken;
 
 use self::state::KnownContractsMap;
 pub(crate) use self::{
     contracts::ContractTask,
-    state::StrategyContractNames,
-    strategies::StrategyTask,
+    state::{StateTask, StrategyContractNames, StrategyOperationKeys},
+    strategies::{
+        ContractUpdateEdit, DryRunReport, IdentityRegistrations, OperationKey, StrategyProgress,
+        StrategyRunReport, StrategyTask, CONTRACT_UPDATE_BLOCK_INTERVAL,
+    },
     wallet::{Wallet, WalletTask},
 };
 use crate::{
@@ -56,6 +63,38 @@ pub enum Task {
     Identity(IdentityTask),
     Contract(ContractTask),
     Document(DocumentTask),
+    State(StateTask),
+}
+
+/// Description of the task that is safe to show, unlike its debug
+/// representation it never includes keys or mnemonics.
+impl Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Task::FetchIdentityById(id, _) => write!(f, "Fetch identity {}", id),
+            Task::PlatformInfo(task) => write!(f, "{}", task),
+            Task::Strategy(task) => write!(f, "{}", task),
+            Task::Wallet(task) => write!(f, "{}", task),
+            Task::Identity(task) => write!(f, "{}", task),
+            Task::Contract(task) => write!(f, "{}", task),
+            Task::Document(task) => write!(f, "{}", task),
+            Task::State(task) => write!(f, "{}", task),
+        }
+    }
+}
+
+impl Task {
+    /// Name of the strategy if the task is a run or a dry run of it, these are
+    /// stopped gracefully rather than dropped.
+    pub fn strategy_run(&self) -> Option<&str> {
+        match self {
+            Task::Strategy(
+                StrategyTask::RunStrategy(strategy_name, ..)
+                | StrategyTask::DryRunStrategy { strategy_name, .. },
+            ) => Some(strategy_name),
+            _ => None,
+        }
+    }
 }
 
 /// A positive task execution result.
@@ -65,6 +104,7 @@ pub enum Task {
 pub enum CompletedTaskPayload {
     Documents(BTreeMap<Identifier, Option<Document>>),
     Document(Document),
+    StrategyDryRun(Box<DryRunReport>),
     String(String),
 }
 
@@ -84,6 +124,7 @@ impl Display for CompletedTaskPayload {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CompletedTaskPayload::String(s) => write!(f, "{}", s),
+            CompletedTaskPayload::StrategyDryRun(report) => write!(f, "{}", report),
             _ => write!(f, "Executed successfully"),
         }
     }
@@ -105,10 +146,20 @@ pub enum BackendEvent<'s> {
     StrategyCompleted {
         strategy_name: String,
         result: StrategyCompletionResult,
+        report: Box<StrategyRunReport>,
+        /// Where the report was saved, `None` if saving failed
+        report_file: Option<PathBuf>,
     },
     StrategyError {
         error: String,
     },
+    /// Progress of a running strategy, delivered through
+    /// [Backend::strategy_progress] rather than as a task result
+    StrategyProgress(StrategyProgress),
+    /// The task was cancelled through the [scheduler::TaskScheduler]
+    TaskCancelled {
+        task: Task,
+    },
     None,
 }
 
@@ -128,6 +179,8 @@ pub(crate) enum AppStateUpdate<'s> {
         MappedMutexGuard<'s, Strategy>,
         MappedMutexGuard<'s, StrategyContractNames>,
     ),
+    /// Keys assigned to the operations of a strategy
+    OperationKeys(String, MappedMutexGuard<'s, StrategyOperationKeys>),
     IdentityRegistrationProgressed, // TODO provide state update details
     LoadedIdentity(MappedMutexGuard<'s, Identity>),
     FailedToRefreshIdentity,
@@ -141,49 +194,77 @@ pub(crate) enum AppStateUpdate<'s> {
 /// Represents the result of completing a strategy.
 #[derive(Debug)]
 pub(crate) enum StrategyCompletionResult {
-    Success {
-        block_mode: bool,
-        final_block_height: u64,
-        start_block_height: u64,
-        success_count: u64,
-        transition_count: u64,
-        run_time: Duration,
-        init_time: Duration,
-        rate: u64,
-        success_rate: u64,
-        success_percent: u64,
-        dash_spent_identity: f64,
-        dash_spent_wallet: f64,
-    },
+    Success(StrategyRunStats),
     PartiallyCompleted {
         reached_block_height: u64,
         reason: String,
+        stats: StrategyRunStats,
     },
 }
 
+/// Statistics of a strategy run, complete or not.
+#[derive(Debug)]
+pub(crate) struct StrategyRunStats {
+    pub block_mode: bool,
+    pub final_block_height: u64,
+    pub start_block_height: u64,
+    pub success_count: u64,
+    pub transition_count: u64,
+    pub run_time: Duration,
+    pub init_time: Duration,
+    pub rate: u64,
+    pub success_rate: u64,
+    pub success_percent: u64,
+    pub dash_spent_identity: f64,
+    pub dash_spent_wallet: f64,
+    pub identity_registrations: IdentityRegistrations,
+    pub seed: u64,
+}
+
+/// Strategy progress snapshots kept for slow receivers, older ones are dropped.
+const STRATEGY_PROGRESS_CAPACITY: usize = 64;
+
 /// Application state, dependencies are task execution logic around it.
 pub struct Backend<'a> {
     pub sdk: &'a Sdk,
     app_state: AppState,
     insight: InsightAPIClient,
+    strategy_progress: broadcast::Sender<StrategyProgress>,
     pub config: Config,
 }
 
 impl<'a> Backend<'a> {
-    pub async fn new(sdk: &'a Sdk, insight: InsightAPIClient, config: Config) -> Backend<'a> {
-        Backend {
+    pub async fn new(
+        sdk: &'a Sdk,
+        insight: InsightAPIClient,
+        config: Config,
+    ) -> Result<Backend<'a>, AppStateError> {
+        Ok(Backend {
             sdk,
-            app_state: AppState::load(&insight, &config).await,
+            app_state: AppState::load(&insight, &config).await?,
             insight,
+            strategy_progress: broadcast::channel(STRATEGY_PROGRESS_CAPACITY).0,
             config,
-        }
+        })
     }
 
     pub fn state(&self) -> &AppState {
         &self.app_state
     }
 
+    /// Subscribes to progress of strategies run from now on.
+    pub fn strategy_progress(&self) -> broadcast::Receiver<StrategyProgress> {
+        self.strategy_progress.subscribe()
+    }
+
     pub async fn run_task(&self, task: Task) -> BackendEvent {
+        self.run_stoppable_task(task, CancellationToken::new())
+            .await
+    }
+
+    /// Runs the task, strategy runs wind down once `stop` is cancelled and
+    /// report what they did so far, see [Task::strategy_run].
+    pub async fn run_stoppable_task(&self, task: Task, stop: CancellationToken) -> BackendEvent {
         match task {
             Task::FetchIdentityById(ref base58_id, add_to_known_identities) => {
                 let execution_result =
@@ -209,6 +290,8 @@ impl<'a> Backend<'a> {
                     &self.app_state,
                     strategy_task,
                     &self.insight,
+                    &self.strategy_progress,
+                    stop,
                 )
                 .await
             }
@@ -218,6 +301,7 @@ impl<'a> Backend<'a> {
                     &self.app_state.loaded_wallet,
                     wallet_task,
                     &self.insight,
+                    self.app_state.network,
                 )
                 .await
             }
@@ -239,6 +323,7 @@ impl<'a> Backend<'a> {
                     .run_document_task(&self.sdk, document_task)
                     .await
             }
+            Task::State(state_task) => self.app_state.run_state_task(state_task).await,
             Task::PlatformInfo(platform_info_task) => {
                 platform_info::run_platform_task(self.sdk, platform_info_task).await
             }
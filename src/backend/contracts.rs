@@ -25,6 +25,17 @@ pub(crate) enum ContractTask {
     FetchContract(String),
 }
 
+impl std::fmt::Display for ContractTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContractTask::FetchDashpayContract => write!(f, "Fetch DashPay contract"),
+            ContractTask::FetchDPNSContract => write!(f, "Fetch DPNS contract"),
+            ContractTask::RemoveContract(name) => write!(f, "Remove contract {}", name),
+            ContractTask::FetchContract(id) => write!(f, "Fetch contract {}", id),
+        }
+    }
+}
+
 pub(super) async fn run_contract_task<'s>(
     sdk: &Sdk,
     known_contracts: &'s Mutex<KnownContractsMap>,
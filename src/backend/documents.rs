@@ -39,9 +39,29 @@ pub(crate) enum DocumentTask {
         data_contract_name: String,
         document_type_name: String,
         count: u16,
+        /// Seed of the random documents, a random one if not set
+        seed: Option<u64>,
     },
 }
 
+impl std::fmt::Display for DocumentTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentTask::QueryDocuments(_) => write!(f, "Query documents"),
+            DocumentTask::BroadcastRandomDocuments {
+                data_contract_name,
+                document_type_name,
+                count,
+                ..
+            } => write!(
+                f,
+                "Broadcast {} random {} documents of {}",
+                count, document_type_name, data_contract_name
+            ),
+        }
+    }
+}
+
 impl AppState {
     pub(super) async fn run_document_task<'s>(
         &'s self,
@@ -63,6 +83,7 @@ impl AppState {
                 data_contract_name,
                 document_type_name,
                 count,
+                seed,
             } => {
                 let broadcast_stats = {
                     let data_contracts_lock = self.known_contracts.lock().await;
@@ -96,6 +117,7 @@ impl AppState {
                         data_contract,
                         &document_type,
                         *count,
+                        *seed,
                     )
                     .await
                 };
@@ -161,8 +183,12 @@ async fn broadcast_random_documents<'s>(
     data_contract: &DataContract,
     document_type: &DocumentType,
     count: u16,
+    seed: Option<u64>,
 ) -> Result<BroadcastRandomDocumentsStats, Error> {
-    let mut std_rng = StdRng::from_entropy();
+    let mut std_rng = match seed {
+        None => StdRng::from_entropy(),
+        Some(seed_value) => StdRng::seed_from_u64(seed_value),
+    };
 
     let identity_public_key = identity
         .get_first_public_key_matching(
@@ -104,6 +104,28 @@ pub enum IdentityTask {
     RegisterDPNSName(String),
 }
 
+impl std::fmt::Display for IdentityTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentityTask::RegisterIdentity(amount) => {
+                write!(f, "Register identity with {}", amount)
+            }
+            IdentityTask::TopUpIdentity(amount) => write!(f, "Top up identity with {}", amount),
+            IdentityTask::WithdrawFromIdentity(amount) => {
+                write!(f, "Withdraw {} from identity", amount)
+            }
+            IdentityTask::Refresh => write!(f, "Refresh identity"),
+            IdentityTask::CopyIdentityId => write!(f, "Copy identity ID"),
+            IdentityTask::AddIdentityKey { .. } => write!(f, "Add identity key"),
+            IdentityTask::ClearLoadedIdentity => write!(f, "Clear loaded identity"),
+            IdentityTask::TransferCredits(recipient, amount) => {
+                write!(f, "Transfer {} to {}", amount, recipient)
+            }
+            IdentityTask::RegisterDPNSName(name) => write!(f, "Register DPNS name {}", name),
+        }
+    }
+}
+
 impl AppState {
     pub async fn run_identity_task(&self, sdk: &Sdk, task: IdentityTask) -> BackendEvent {
         match task {
@@ -23,6 +23,23 @@ pub(crate) enum PlatformInfoTask {
     FetchManyEpochInfo(u16, u32), // second is count
 }
 
+impl std::fmt::Display for PlatformInfoTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlatformInfoTask::FetchCurrentEpochInfo => write!(f, "Fetch current epoch info"),
+            PlatformInfoTask::FetchCurrentVersionVotingState => {
+                write!(f, "Fetch version voting state")
+            }
+            PlatformInfoTask::FetchSpecificEpochInfo(epoch) => {
+                write!(f, "Fetch info of epoch {}", epoch)
+            }
+            PlatformInfoTask::FetchManyEpochInfo(epoch, count) => {
+                write!(f, "Fetch info of {} epochs from {}", count, epoch)
+            }
+        }
+    }
+}
+
 fn format_extended_epoch_info(
     epoch_info: ExtendedEpochInfo,
     metadata: ResponseMetadata,
@@ -0,0 +1,154 @@
+//! Concurrent execution of backend tasks.
+//!
+//! Every started [Task] gets an ID and a [CancellationToken], results are
+//! delivered as [TaggedBackendEvent]s in the order tasks finish. Cancelled
+//! tasks are dropped, except for strategy runs that are handed their token so
+//! they can wind down and report partial results.
+
+use std::{collections::BTreeMap, time::Instant};
+
+use futures::{
+    future::LocalBoxFuture,
+    stream::{FuturesUnordered, SelectNextSome},
+    FutureExt, StreamExt,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{Backend, BackendEvent, StrategyTask, Task};
+
+pub type TaskId = u64;
+
+/// A task that was started and hasn't finished yet.
+pub struct RunningTask {
+    pub id: TaskId,
+    pub task: Task,
+    pub started: Instant,
+    cancellation: CancellationToken,
+}
+
+/// Backend event with the ID of the task it is the result of.
+pub struct TaggedBackendEvent<'s> {
+    pub task_id: TaskId,
+    pub event: BackendEvent<'s>,
+}
+
+pub struct TaskScheduler<'s> {
+    next_id: TaskId,
+    running: BTreeMap<TaskId, RunningTask>,
+    futures: FuturesUnordered<LocalBoxFuture<'s, TaggedBackendEvent<'s>>>,
+}
+
+impl<'s> TaskScheduler<'s> {
+    pub fn new() -> Self {
+        TaskScheduler {
+            next_id: 1,
+            running: BTreeMap::new(),
+            futures: FuturesUnordered::new(),
+        }
+    }
+
+    /// Starts the task alongside already running ones.
+    pub fn spawn(&mut self, backend: &'s Backend<'_>, task: Task) -> TaskId {
+        let task_id = self.next_id;
+        self.next_id += 1;
+
+        let cancellation = CancellationToken::new();
+        let cancelled = cancellation.clone();
+        let backend_task = task.clone();
+
+        let future = if let Task::Strategy(StrategyTask::StopStrategy(strategy_name)) = &task {
+            let event = self.stop_strategy_runs(strategy_name, backend_task);
+            async move { TaggedBackendEvent { task_id, event } }.boxed_local()
+        } else if task.strategy_run().is_some() {
+            async move {
+                let event = backend.run_stoppable_task(backend_task, cancelled).await;
+                TaggedBackendEvent { task_id, event }
+            }
+            .boxed_local()
+        } else {
+            async move {
+                let event = tokio::select! {
+                    event = backend.run_task(backend_task.clone()) => event,
+                    _ = cancelled.cancelled() => BackendEvent::TaskCancelled { task: backend_task },
+                };
+                TaggedBackendEvent { task_id, event }
+            }
+            .boxed_local()
+        };
+        self.futures.push(future);
+
+        self.running.insert(
+            task_id,
+            RunningTask {
+                id: task_id,
+                task,
+                started: Instant::now(),
+                cancellation,
+            },
+        );
+
+        task_id
+    }
+
+    /// Asks the runs and dry runs of the strategy to stop.
+    fn stop_strategy_runs(&self, strategy_name: &str, task: Task) -> BackendEvent<'s> {
+        let mut stopped = false;
+        for running_task in self.running.values() {
+            if running_task.task.strategy_run() == Some(strategy_name) {
+                running_task.cancellation.cancel();
+                stopped = true;
+            }
+        }
+
+        BackendEvent::TaskCompleted {
+            task,
+            execution_result: if stopped {
+                Ok("Strategy will stop after the current block".into())
+            } else {
+                Err(format!("Strategy '{}' isn't running", strategy_name))
+            },
+        }
+    }
+
+    /// Requests the task to stop, it finishes with
+    /// [BackendEvent::TaskCancelled] unless it has completed already. Strategy
+    /// runs stop after their current block and finish with their partial
+    /// results instead.
+    pub fn cancel(&self, task_id: TaskId) -> bool {
+        match self.running.get(&task_id) {
+            Some(running_task) => {
+                running_task.cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn running_tasks(&self) -> impl Iterator<Item = &RunningTask> {
+        self.running.values()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Resolves with the next finished task, to be used in `select!`: stays
+    /// pending while no tasks are running. The task shall be passed to
+    /// [TaskScheduler::finish] afterwards.
+    pub fn next_finished(
+        &mut self,
+    ) -> SelectNextSome<'_, FuturesUnordered<LocalBoxFuture<'s, TaggedBackendEvent<'s>>>> {
+        self.futures.select_next_some()
+    }
+
+    /// Removes a finished task from the running list.
+    pub fn finish(&mut self, task_id: TaskId) {
+        self.running.remove(&task_id);
+    }
+}
+
+impl Default for TaskScheduler<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
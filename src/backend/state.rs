@@ -2,7 +2,12 @@
 //! This kind of state does not include UI details and basically all about
 //! persistence required by backend.
 
+mod encryption;
+mod export;
+mod migrations;
+
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{collections::BTreeMap, fs};
@@ -22,14 +27,22 @@ use dpp::{
     tests::json_document::json_document_to_contract,
     util::deserializer::ProtocolVersion,
     version::PlatformVersion,
-    ProtocolError::{self, PlatformDeserializationError, PlatformSerializationError},
+    ProtocolError::{self, PlatformDeserializationError},
 };
 use drive::drive::Drive;
 use strategy_tests::Strategy;
 use tokio::sync::Mutex;
 use walkdir::{DirEntry, WalkDir};
 
-use super::wallet::{add_wallet_by_private_key, Wallet};
+pub use self::{
+    encryption::EncryptionError,
+    export::{StateExportError, StateTask},
+    migrations::{MigrationReport, SchemaVersion},
+};
+use super::{
+    strategies::OperationKey,
+    wallet::{add_wallet_by_private_key, Wallet},
+};
 use crate::{backend::insight::InsightAPIClient, config::Config};
 
 const CURRENT_PROTOCOL_VERSION: ProtocolVersion = 1;
@@ -41,15 +54,60 @@ pub(crate) type ContractFileName = String;
 pub(super) type StrategiesMap = BTreeMap<String, Strategy>;
 pub(crate) type StrategyContractNames =
     Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>;
+/// Keys assigned to the operations of a strategy, by operation position
+pub(crate) type StrategyOperationKeys = Vec<Option<OperationKey>>;
 pub(super) type KnownContractsMap = BTreeMap<String, DataContract>;
 pub type IdentityPrivateKeysMap = BTreeMap<(Identifier, KeyID), Vec<u8>>;
 
+#[derive(Debug, thiserror::Error)]
+pub enum AppStateError {
+    #[error(
+        "state file {path} belongs to {state_network} network, but {config_network} is \
+         configured; use another state file or change EXPLORER_NETWORK"
+    )]
+    NetworkMismatch {
+        path: PathBuf,
+        state_network: Network,
+        config_network: Network,
+    },
+    #[error("state file {0} is encrypted, a passphrase is required to unlock it")]
+    PassphraseRequired(PathBuf),
+    #[error("unable to unlock state file {0}: {1}")]
+    Decryption(PathBuf, EncryptionError),
+    #[error(
+        "state file {path} has schema version {version}, this build supports up to \
+         {supported}; please update the explorer"
+    )]
+    NewerSchema {
+        path: PathBuf,
+        version: SchemaVersion,
+        supported: SchemaVersion,
+    },
+    #[error("state file {0} not found")]
+    NotFound(PathBuf),
+    #[error("unable to access state file {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("unable to decode state file {0}: {1}")]
+    Decode(PathBuf, ProtocolError),
+    #[error("unable to encode state: {0}")]
+    Encode(ProtocolError),
+    #[error("unable to encrypt state: {0}")]
+    Encryption(EncryptionError),
+}
+
 // TODO: each state part should be in it's own mutex in case multiple backend
 // tasks are executed on different state parts,
 // moreover single mutex hold during rendering will block unrelated tasks from
 // finishing
 // #[derive(Debug)]
+/// Tasks run concurrently, so strategy related parts held together are always
+/// locked in this order: `available_strategies`, `known_contracts`,
+/// `supporting_contracts`, `available_strategies_contract_names`,
+/// `selected_strategy`, `available_strategies_operation_keys` and
+/// `available_strategies_seeds`.
 pub struct AppState {
+    /// Network the state belongs to, keys and addresses are encoded for it
+    pub network: Network,
     pub loaded_identity: Mutex<Option<Identity>>,
     pub identity_private_keys: Mutex<IdentityPrivateKeysMap>,
     pub loaded_wallet: Mutex<Option<Wallet>>,
@@ -64,6 +122,12 @@ pub struct AppState {
     /// field serves as a double of strategies' `start_contracts`,
     /// but using file names
     pub available_strategies_contract_names: Mutex<BTreeMap<String, StrategyContractNames>>,
+    /// Keys of the loaded identity assigned to strategy operations, missing
+    /// entries are operations using the default key
+    pub available_strategies_operation_keys: Mutex<BTreeMap<String, StrategyOperationKeys>>,
+    /// Seeds strategy runs use unless another one is requested, strategies
+    /// without one get a random seed every run
+    pub available_strategies_seeds: Mutex<BTreeMap<String, u64>>,
     pub selected_strategy: Mutex<Option<String>>,
     pub identity_asset_lock_private_key_in_creation: Mutex<
         Option<(
@@ -77,8 +141,8 @@ pub struct AppState {
         Mutex<Option<(Transaction, PrivateKey, Option<AssetLockProof>)>>,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
+impl AppState {
+    pub fn new(network: Network) -> Self {
         let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
 
         // Helper function to check if a file is a JSON file
@@ -119,6 +183,7 @@ impl Default for AppState {
         }
 
         AppState {
+            network,
             loaded_identity: None.into(),
             identity_private_keys: Default::default(),
             loaded_wallet: Mutex::new(None),
@@ -131,12 +196,15 @@ impl Default for AppState {
             identity_asset_lock_private_key_in_creation: None.into(),
             identity_asset_lock_private_key_in_top_up: None.into(),
             available_strategies_contract_names: BTreeMap::new().into(),
+            available_strategies_operation_keys: BTreeMap::new().into(),
+            available_strategies_seeds: BTreeMap::new().into(),
         }
     }
 }
 
 #[derive(Clone, Debug, Encode, Decode)]
 struct AppStateInSerializationFormat {
+    pub network: String,
     pub loaded_identity: Option<Identity>,
     pub identity_private_keys: IdentityPrivateKeysMap,
     pub loaded_wallet: Option<Wallet>,
@@ -155,6 +223,8 @@ struct AppStateInSerializationFormat {
     )>,
     pub identity_asset_lock_private_key_in_top_up:
         Option<(Vec<u8>, [u8; 32], Option<AssetLockProof>)>,
+    pub available_strategies_operation_keys: BTreeMap<String, StrategyOperationKeys>,
+    pub available_strategies_seeds: BTreeMap<String, u64>,
 }
 
 impl PlatformSerializableWithPlatformVersion for AppState {
@@ -172,6 +242,7 @@ impl PlatformSerializableWithPlatformVersion for AppState {
         platform_version: &PlatformVersion,
     ) -> Result<Vec<u8>, ProtocolError> {
         let AppState {
+            network,
             loaded_identity,
             identity_private_keys,
             loaded_wallet,
@@ -184,6 +255,8 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             identity_asset_lock_private_key_in_creation,
             available_strategies_contract_names,
             identity_asset_lock_private_key_in_top_up,
+            available_strategies_operation_keys,
+            available_strategies_seeds,
         } = self;
 
         let known_contracts_in_serialization_format = known_contracts
@@ -243,6 +316,7 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             });
 
         let app_state_in_serialization_format = AppStateInSerializationFormat {
+            network: network.to_string(),
             loaded_identity: loaded_identity.blocking_lock().clone(),
             identity_private_keys: identity_private_keys.blocking_lock().clone(),
             loaded_wallet: loaded_wallet.blocking_lock().clone(),
@@ -256,38 +330,36 @@ impl PlatformSerializableWithPlatformVersion for AppState {
                 .clone(),
             identity_asset_lock_private_key_in_creation,
             identity_asset_lock_private_key_in_top_up,
+            available_strategies_operation_keys: available_strategies_operation_keys
+                .blocking_lock()
+                .clone(),
+            available_strategies_seeds: available_strategies_seeds.blocking_lock().clone(),
         };
 
-        let config = bincode::config::standard()
-            .with_big_endian()
-            .with_no_limit();
-        bincode::encode_to_vec(app_state_in_serialization_format, config).map_err(|e| {
-            PlatformSerializationError(format!("unable to serialize App State: {}", e))
-        })
+        migrations::encode(app_state_in_serialization_format)
     }
 }
 
-impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for AppState {
-    fn versioned_deserialize(
+impl AppState {
+    /// Decodes a state file of any supported schema version, `network` is
+    /// assumed for files written before the network was stored.
+    fn deserialize(
         data: &[u8],
+        network: Network,
         validate: bool,
         platform_version: &PlatformVersion,
-    ) -> Result<Self, ProtocolError>
-    where
-        Self: Sized,
-    {
-        let config = bincode::config::standard()
-            .with_big_endian()
-            .with_no_limit();
-        let app_state: AppStateInSerializationFormat =
-            bincode::borrow_decode_from_slice(data, config)
-                .map_err(|e| {
-                    let msg = format!("Error decoding AppStateInSerializationFormat: {}", e);
-                    PlatformDeserializationError(msg)
-                })?
-                .0;
+    ) -> Result<Self, ProtocolError> {
+        let (app_state, schema_version) = migrations::decode(data, network)?;
+        if schema_version != migrations::CURRENT_SCHEMA_VERSION {
+            tracing::info!(
+                "Migrated state from schema version {} to {}",
+                schema_version,
+                migrations::CURRENT_SCHEMA_VERSION
+            );
+        }
 
         let AppStateInSerializationFormat {
+            network,
             loaded_identity,
             identity_private_keys,
             loaded_wallet,
@@ -299,8 +371,14 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             available_strategies_contract_names,
             identity_asset_lock_private_key_in_creation,
             identity_asset_lock_private_key_in_top_up,
+            available_strategies_operation_keys,
+            available_strategies_seeds,
         } = app_state;
 
+        let network = Network::from_str(&network).map_err(|e| {
+            PlatformDeserializationError(format!("Error parsing state network: {}", e))
+        })?;
+
         let known_contracts = known_contracts
             .into_iter()
             .map(|(key, contract)| {
@@ -361,8 +439,7 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
                     (
                         Transaction::deserialize(&transaction)
                             .expect("expected to deserialize transaction"),
-                        // TODO: Should use network from config
-                        PrivateKey::from_slice(&private_key, Network::Testnet)
+                        PrivateKey::from_slice(&private_key, network)
                             .expect("expected private key"),
                         asset_lock_proof,
                         identity_info,
@@ -375,9 +452,7 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
                 (
                     Transaction::deserialize(&transaction)
                         .expect("expected to deserialize transaction"),
-                    // TODO: Should use network from config
-                    PrivateKey::from_slice(&private_key, Network::Testnet)
-                        .expect("expected private key"),
+                    PrivateKey::from_slice(&private_key, network).expect("expected private key"),
                     asset_lock_proof,
                 )
             });
@@ -391,6 +466,7 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             .unwrap_or_else(|| Mutex::new(None));
 
         Ok(AppState {
+            network,
             loaded_identity: loaded_identity.into(),
             identity_private_keys: identity_private_keys.into(),
             loaded_wallet: deserialized_wallet_state,
@@ -401,6 +477,8 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             available_strategies: available_strategies.into(),
             selected_strategy: selected_strategy.into(),
             available_strategies_contract_names: available_strategies_contract_names.into(),
+            available_strategies_operation_keys: available_strategies_operation_keys.into(),
+            available_strategies_seeds: available_strategies_seeds.into(),
             identity_asset_lock_private_key_in_creation:
                 identity_asset_lock_private_key_in_creation.into(),
             identity_asset_lock_private_key_in_top_up: identity_asset_lock_private_key_in_top_up
@@ -410,47 +488,91 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
 }
 
 impl AppState {
-    pub async fn load(insight: &InsightAPIClient, config: &Config) -> AppState {
+    pub async fn load(
+        insight: &InsightAPIClient,
+        config: &Config,
+    ) -> Result<AppState, AppStateError> {
         let path = config.state_file_path();
+        let network = config.core_network();
 
-        let Ok(read_result) = fs::read(path.clone()) else {
-            let state = AppState::default();
+        let Some((read_result, encrypted)) = read_state_file(config)? else {
+            let state = AppState::new(network);
             if let Some(private_key) = &config.wallet_private_key {
                 let wallet_state = &state.loaded_wallet;
-                add_wallet_by_private_key(&wallet_state, private_key).await;
+                if let Err(e) = add_wallet_by_private_key(&wallet_state, private_key, network).await
+                {
+                    tracing::error!("Unable to load the configured wallet: {}", e);
+                }
             }
-            return state;
+            return Ok(state);
         };
 
-        let Ok(app_state) = AppState::versioned_deserialize(
+        // Don't back up and overwrite a state we're just too old to understand
+        if let Some(version) = migrations::header_version(&read_result) {
+            if version > migrations::CURRENT_SCHEMA_VERSION {
+                return Err(AppStateError::NewerSchema {
+                    path,
+                    version,
+                    supported: migrations::CURRENT_SCHEMA_VERSION,
+                });
+            }
+        }
+
+        let app_state = match AppState::deserialize(
             read_result.as_slice(),
+            network,
             false,
             PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap(),
-        ) else {
-            let start = SystemTime::now();
-            let since_the_epoch = start
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards");
-            let timestamp = since_the_epoch.as_secs();
-
-            let backup_path_str = format!("{}.{}.backup", path.display(), timestamp);
-            let backup_path = PathBuf::from(backup_path_str);
-
-            if let Err(e) = fs::rename(path, &backup_path) {
-                eprintln!("Failed to backup old file: {}", e);
-            }
+        ) {
+            Ok(app_state) => app_state,
+            Err(e) => {
+                let start = SystemTime::now();
+                let since_the_epoch = start
+                    .duration_since(UNIX_EPOCH)
+                    .expect("Time went backwards");
+                let timestamp = since_the_epoch.as_secs();
+
+                let backup_path_str = format!("{}.{}.backup", path.display(), timestamp);
+                let backup_path = PathBuf::from(backup_path_str);
+
+                tracing::error!(
+                    "Unable to decode state file {}, starting with an empty state and moving \
+                     the old file to {}: {}",
+                    path.display(),
+                    backup_path.display(),
+                    e
+                );
 
-            let state = AppState::default();
-            if let Some(private_key) = &config.wallet_private_key {
-                let wallet_state = &state.loaded_wallet;
-                add_wallet_by_private_key(&wallet_state, private_key).await;
+                if let Err(e) = fs::rename(path, &backup_path) {
+                    eprintln!("Failed to backup old file: {}", e);
+                }
+
+                let state = AppState::new(network);
+                if let Some(private_key) = &config.wallet_private_key {
+                    let wallet_state = &state.loaded_wallet;
+                    if let Err(e) =
+                        add_wallet_by_private_key(&wallet_state, private_key, network).await
+                    {
+                        tracing::error!("Unable to load the configured wallet: {}", e);
+                    }
+                }
+                return Ok(state);
             }
-            return state;
         };
 
+        if app_state.network != network {
+            return Err(AppStateError::NetworkMismatch {
+                path,
+                state_network: app_state.network,
+                config_network: network,
+            });
+        }
+
         if let Some(private_key) = &config.wallet_private_key {
             let wallet_state = &app_state.loaded_wallet;
-            add_wallet_by_private_key(&wallet_state, private_key).await;
+            if let Err(e) = add_wallet_by_private_key(&wallet_state, private_key, network).await {
+                tracing::error!("Unable to load the configured wallet: {}", e);
+            }
         }
 
         // Load supporting contracts
@@ -474,10 +596,76 @@ impl AppState {
             *app_state_supporting_contracts = supporting_contracts;
         }
 
-        app_state
+        // A passphrase is configured but the state file is still in plain text
+        if !encrypted && config.state_passphrase.is_some() {
+            tracing::info!(
+                "Encrypting state file {}",
+                config.state_file_path().display()
+            );
+            app_state.save(config);
+        }
+
+        Ok(app_state)
     }
 
-    /// Used in backend destructor, must not panic
+    /// Rewrites the state file in the current schema version, the original file
+    /// is kept aside if it had to be migrated.
+    pub fn migrate_file(config: &Config) -> Result<MigrationReport, AppStateError> {
+        let path = config.state_file_path();
+
+        let Some((read_result, _)) = read_state_file(config)? else {
+            return Err(AppStateError::NotFound(path));
+        };
+
+        if let Some(version) = migrations::header_version(&read_result) {
+            if version > migrations::CURRENT_SCHEMA_VERSION {
+                return Err(AppStateError::NewerSchema {
+                    path,
+                    version,
+                    supported: migrations::CURRENT_SCHEMA_VERSION,
+                });
+            }
+        }
+
+        let (app_state, schema_version) = migrations::decode(&read_result, config.core_network())
+            .map_err(|e| AppStateError::Decode(path.clone(), e))?;
+
+        let state_network = Network::from_str(&app_state.network).map_err(|e| {
+            AppStateError::Decode(
+                path.clone(),
+                PlatformDeserializationError(format!("Error parsing state network: {}", e)),
+            )
+        })?;
+        if state_network != config.core_network() {
+            return Err(AppStateError::NetworkMismatch {
+                path,
+                state_network,
+                config_network: config.core_network(),
+            });
+        }
+
+        let report = MigrationReport::new(&app_state, schema_version);
+
+        if schema_version != migrations::CURRENT_SCHEMA_VERSION {
+            let backup_path =
+                PathBuf::from(format!("{}.v{}.backup", path.display(), schema_version));
+            fs::copy(&path, &backup_path).map_err(|e| AppStateError::Io(backup_path, e))?;
+        }
+
+        let state = migrations::encode(app_state).map_err(AppStateError::Encode)?;
+        let state = match &config.state_passphrase {
+            Some(passphrase) => {
+                encryption::encrypt(&state, passphrase).map_err(AppStateError::Encryption)?
+            }
+            None => state,
+        };
+        fs::write(&path, state).map_err(|e| AppStateError::Io(path, e))?;
+
+        Ok(report)
+    }
+
+    /// Used in backend destructor, must not panic.
+    /// The state is encrypted if a passphrase is configured.
     pub fn save(&self, config: &Config) {
         let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
         let path = config.state_file_path();
@@ -485,8 +673,44 @@ impl AppState {
         let serialized_state = tokio::task::block_in_place(|| {
             self.serialize_to_bytes_with_platform_version(platform_version)
         });
-        if let Ok(state) = serialized_state {
-            let _ = fs::write(path, state);
-        }
+        let Ok(state) = serialized_state else {
+            return;
+        };
+
+        let state = match &config.state_passphrase {
+            Some(passphrase) => match encryption::encrypt(&state, passphrase) {
+                Ok(encrypted) => encrypted,
+                Err(e) => {
+                    // Never fall back to writing secrets in plain text
+                    tracing::error!("Failed to encrypt state file: {}", e);
+                    return;
+                }
+            },
+            None => state,
+        };
+        let _ = fs::write(path, state);
     }
 }
+
+/// Reads the state file, decrypting it if needed. Returns `None` if there is no
+/// state file yet, otherwise the decrypted contents and whether the file was
+/// encrypted.
+fn read_state_file(config: &Config) -> Result<Option<(Vec<u8>, bool)>, AppStateError> {
+    let path = config.state_file_path();
+
+    let read_result = match fs::read(&path) {
+        Ok(read_result) => read_result,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppStateError::Io(path, e)),
+    };
+
+    let encrypted = encryption::is_encrypted(&read_result);
+    let read_result = match (encrypted, &config.state_passphrase) {
+        (true, Some(passphrase)) => encryption::decrypt(&read_result, passphrase)
+            .map_err(|e| AppStateError::Decryption(path, e))?,
+        (true, None) => return Err(AppStateError::PassphraseRequired(path)),
+        (false, _) => read_result,
+    };
+
+    Ok(Some((read_result, encrypted)))
+}
@@ -0,0 +1,75 @@
+//! Optional encryption of the state file.
+//!
+//! An encrypted state file starts with [MAGIC] followed by a random KDF salt,
+//! a random AEAD nonce and the ChaCha20-Poly1305 ciphertext of the serialized
+//! state. The key is derived from the passphrase using Argon2id, salt and nonce
+//! are regenerated on every save.
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::{rngs::OsRng, RngCore};
+
+const MAGIC: &[u8; 8] = b"EXPLENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("key derivation failed: {0}")]
+    Kdf(String),
+    #[error("state file is truncated")]
+    Truncated,
+    #[error("wrong passphrase or corrupted state file")]
+    Decryption,
+    #[error("encryption failed")]
+    Encryption,
+}
+
+/// Checks whether the state file content was produced by [encrypt].
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, EncryptionError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| EncryptionError::Kdf(e.to_string()))?;
+    Ok(key)
+}
+
+pub(crate) fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, EncryptionError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| EncryptionError::Encryption)?;
+
+    let mut data = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+pub(crate) fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, EncryptionError> {
+    if data.len() < HEADER_LEN {
+        return Err(EncryptionError::Truncated);
+    }
+    let (salt, rest) = data[MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EncryptionError::Decryption)
+}
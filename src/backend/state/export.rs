@@ -0,0 +1,459 @@
+//! Human-readable JSON export and import of the application state.
+//!
+//! Identities and contracts are kept in their JSON representation so the
+//! document can be reviewed and edited by hand. Strategies have no such
+//! representation yet and are stored as hex encoded platform serialization,
+//! their signer keeps private keys so it's left out when secrets are redacted.
+//! UTXOs and unfinished asset locks are not exported, a wallet refresh brings
+//! the former back.
+
+use std::{collections::BTreeMap, fs, str::FromStr};
+
+use dpp::{
+    dashcore::{Network, PrivateKey},
+    data_contract::conversion::json::DataContractJsonConversionMethodsV0,
+    identity::{accessors::IdentityGettersV0, KeyID},
+    platform_value::string_encoding::Encoding,
+    prelude::{DataContract, Identifier, Identity},
+    serialization::{
+        PlatformDeserializableWithPotentialValidationFromVersionedStructure,
+        PlatformSerializableWithPlatformVersion,
+    },
+    version::PlatformVersion,
+};
+use serde::{Deserialize, Serialize};
+use strategy_tests::Strategy;
+
+use super::{AppState, StrategyContractNames, StrategyOperationKeys, CURRENT_PROTOCOL_VERSION};
+use crate::backend::{
+    wallet::{HDWallet, SingleKeyWallet, Wallet},
+    BackendEvent, CompletedTaskPayload, Task,
+};
+
+const EXPORT_FORMAT_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateTask {
+    ExportJson { path: String, redact_secrets: bool },
+    ImportJson(String),
+}
+
+impl std::fmt::Display for StateTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateTask::ExportJson { path, .. } => write!(f, "Export state to {}", path),
+            StateTask::ImportJson(path) => write!(f, "Import state from {}", path),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StateExportError {
+    #[error("unable to access {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("invalid state export: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported state export version {0}")]
+    UnsupportedVersion(u16),
+    #[error(
+        "state export belongs to {export_network} network, but the state is for {state_network}"
+    )]
+    NetworkMismatch {
+        export_network: String,
+        state_network: Network,
+    },
+    #[error("unable to convert {0}: {1}")]
+    Conversion(String, String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StateExport {
+    version: u16,
+    network: String,
+    secrets_redacted: bool,
+    loaded_identity: Option<String>,
+    known_identities: Vec<serde_json::Value>,
+    identity_private_keys: Vec<IdentityPrivateKeyExport>,
+    known_contracts: BTreeMap<String, serde_json::Value>,
+    strategies: BTreeMap<String, StrategyExport>,
+    wallet: Option<WalletExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IdentityPrivateKeyExport {
+    identity_id: String,
+    key_id: KeyID,
+    /// Hex encoded, `None` if redacted
+    private_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StrategyExport {
+    contract_names: StrategyContractNames,
+    /// Hex encoded platform serialization, without the signer if redacted
+    serialized: String,
+    #[serde(default)]
+    operation_keys: StrategyOperationKeys,
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WalletExport {
+    SingleKey {
+        address: String,
+        /// WIF encoded, `None` if redacted
+        private_key: Option<String>,
+    },
+    Hd {
+        receive_address: String,
+        mnemonic: Option<String>,
+        xprv: Option<String>,
+    },
+}
+
+impl AppState {
+    pub async fn run_state_task(&self, task: StateTask) -> BackendEvent {
+        let execution_result = match &task {
+            StateTask::ExportJson {
+                path,
+                redact_secrets,
+            } => self.export_json(path, *redact_secrets).await,
+            StateTask::ImportJson(path) => self.import_json(path).await,
+        };
+
+        BackendEvent::TaskCompleted {
+            task: Task::State(task),
+            execution_result: execution_result
+                .map(CompletedTaskPayload::String)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    async fn export_json(
+        &self,
+        path: &str,
+        redact_secrets: bool,
+    ) -> Result<String, StateExportError> {
+        let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
+
+        let loaded_identity = self.loaded_identity.lock().await.clone();
+        let mut identities = self.known_identities.lock().await.clone();
+        if let Some(identity) = &loaded_identity {
+            identities.insert(identity.id(), identity.clone());
+        }
+        let known_identities = identities
+            .values()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let identity_private_keys = self
+            .identity_private_keys
+            .lock()
+            .await
+            .iter()
+            .map(
+                |((identity_id, key_id), private_key)| IdentityPrivateKeyExport {
+                    identity_id: identity_id.to_string(Encoding::Base58),
+                    key_id: *key_id,
+                    private_key: (!redact_secrets).then(|| hex::encode(private_key)),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let known_contracts = self
+            .known_contracts
+            .lock()
+            .await
+            .iter()
+            .map(|(name, contract)| {
+                let json = contract
+                    .to_json(platform_version)
+                    .map_err(|e| StateExportError::Conversion(name.clone(), e.to_string()))?;
+                Ok((name.clone(), json))
+            })
+            .collect::<Result<BTreeMap<_, _>, StateExportError>>()?;
+
+        let strategies_contract_names = self.available_strategies_contract_names.lock().await;
+        let strategies_operation_keys = self.available_strategies_operation_keys.lock().await;
+        let strategies_seeds = self.available_strategies_seeds.lock().await;
+        let strategies = self
+            .available_strategies
+            .lock()
+            .await
+            .iter()
+            .map(|(name, strategy)| {
+                let bytes = if redact_secrets {
+                    let mut strategy = strategy.clone();
+                    strategy.signer = None;
+                    strategy.serialize_to_bytes_with_platform_version(platform_version)
+                } else {
+                    strategy.serialize_to_bytes_with_platform_version(platform_version)
+                }
+                .map_err(|e| StateExportError::Conversion(name.clone(), e.to_string()))?;
+                let serialized = hex::encode(bytes);
+                let contract_names = strategies_contract_names
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_default();
+                let operation_keys = strategies_operation_keys
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_default();
+                Ok((
+                    name.clone(),
+                    StrategyExport {
+                        contract_names,
+                        serialized,
+                        operation_keys,
+                        seed: strategies_seeds.get(name).copied(),
+                    },
+                ))
+            })
+            .collect::<Result<BTreeMap<_, _>, StateExportError>>()?;
+
+        let wallet = self
+            .loaded_wallet
+            .lock()
+            .await
+            .as_ref()
+            .map(|wallet| match wallet {
+                Wallet::SingleKeyWallet(wallet) => WalletExport::SingleKey {
+                    address: wallet.address.to_string(),
+                    private_key: (!redact_secrets).then(|| wallet.private_key.to_wif()),
+                },
+                Wallet::HDWallet(wallet) => WalletExport::Hd {
+                    receive_address: wallet.receive_address().to_string(),
+                    mnemonic: wallet.mnemonic.clone().filter(|_| !redact_secrets),
+                    xprv: (!redact_secrets && wallet.mnemonic.is_none())
+                        .then(|| wallet.master_key.to_string()),
+                },
+            });
+
+        let export = StateExport {
+            version: EXPORT_FORMAT_VERSION,
+            network: self.network.to_string(),
+            secrets_redacted: redact_secrets,
+            loaded_identity: loaded_identity
+                .map(|identity| identity.id().to_string(Encoding::Base58)),
+            known_identities,
+            identity_private_keys,
+            known_contracts,
+            strategies,
+            wallet,
+        };
+
+        let json = serde_json::to_string_pretty(&export)?;
+        fs::write(path, json).map_err(|e| StateExportError::Io(path.to_owned(), e))?;
+
+        Ok(format!(
+            "Exported {} identities, {} private keys, {} contracts and {} strategies to {}{}",
+            export.known_identities.len(),
+            export.identity_private_keys.len(),
+            export.known_contracts.len(),
+            export.strategies.len(),
+            path,
+            if redact_secrets {
+                " with secrets redacted"
+            } else {
+                ""
+            }
+        ))
+    }
+
+    /// Merges an exported state into the current one, entries with the same
+    /// keys are replaced. The wallet and the loaded identity are only taken if
+    /// none are loaded.
+    async fn import_json(&self, path: &str) -> Result<String, StateExportError> {
+        let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
+
+        let json =
+            fs::read_to_string(path).map_err(|e| StateExportError::Io(path.to_owned(), e))?;
+        let export: StateExport = serde_json::from_str(&json)?;
+
+        if export.version != EXPORT_FORMAT_VERSION {
+            return Err(StateExportError::UnsupportedVersion(export.version));
+        }
+        if Network::from_str(&export.network).ok() != Some(self.network) {
+            return Err(StateExportError::NetworkMismatch {
+                export_network: export.network,
+                state_network: self.network,
+            });
+        }
+
+        // Convert everything first to keep the state untouched on errors
+        let identities = export
+            .known_identities
+            .into_iter()
+            .map(|value| {
+                let identity: Identity = serde_json::from_value(value)?;
+                Ok((identity.id(), identity))
+            })
+            .collect::<Result<BTreeMap<_, _>, StateExportError>>()?;
+
+        let loaded_identity = export
+            .loaded_identity
+            .map(|id| {
+                identities
+                    .get(&parse_identifier(&id)?)
+                    .cloned()
+                    .ok_or_else(|| {
+                        StateExportError::Conversion(
+                            "loaded identity".to_owned(),
+                            format!("{} is not among known identities", id),
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let mut skipped = Vec::new();
+
+        let mut private_keys = BTreeMap::new();
+        for key in export.identity_private_keys {
+            let Some(private_key) = key.private_key else {
+                skipped.push(format!(
+                    "redacted key {} of {}",
+                    key.key_id, key.identity_id
+                ));
+                continue;
+            };
+            let private_key = hex::decode(&private_key).map_err(|e| {
+                StateExportError::Conversion(format!("key {}", key.key_id), e.to_string())
+            })?;
+            private_keys.insert(
+                (parse_identifier(&key.identity_id)?, key.key_id),
+                private_key,
+            );
+        }
+
+        let contracts = export
+            .known_contracts
+            .into_iter()
+            .map(|(name, json)| {
+                let contract = DataContract::from_json(json, true, platform_version)
+                    .map_err(|e| StateExportError::Conversion(name.clone(), e.to_string()))?;
+                Ok((name, contract))
+            })
+            .collect::<Result<BTreeMap<_, _>, StateExportError>>()?;
+
+        let mut strategies = BTreeMap::new();
+        for (name, strategy) in export.strategies {
+            let bytes = hex::decode(&strategy.serialized)
+                .map_err(|e| StateExportError::Conversion(name.clone(), e.to_string()))?;
+            let decoded = Strategy::versioned_deserialize(&bytes, false, platform_version)
+                .map_err(|e| StateExportError::Conversion(name.clone(), e.to_string()))?;
+            strategies.insert(
+                name,
+                (
+                    decoded,
+                    strategy.contract_names,
+                    strategy.operation_keys,
+                    strategy.seed,
+                ),
+            );
+        }
+
+        let wallet = match export.wallet {
+            Some(WalletExport::SingleKey {
+                private_key: Some(private_key),
+                ..
+            }) => {
+                let private_key = PrivateKey::from_wif(&private_key).map_err(|e| {
+                    StateExportError::Conversion("wallet".to_owned(), e.to_string())
+                })?;
+                // WIF doesn't distinguish between testnet, devnet and regtest
+                if (private_key.network == Network::Dash) != (self.network == Network::Dash) {
+                    return Err(StateExportError::Conversion(
+                        "wallet".to_owned(),
+                        format!(
+                            "WIF key is for {}, but the state is for {}",
+                            private_key.network, self.network
+                        ),
+                    ));
+                }
+                Some(Wallet::SingleKeyWallet(SingleKeyWallet::from_raw_parts(
+                    private_key.inner.secret_bytes(),
+                    self.network,
+                    Vec::new(),
+                )))
+            }
+            Some(WalletExport::Hd {
+                mnemonic: Some(mnemonic),
+                ..
+            }) => Some(Wallet::HDWallet(
+                HDWallet::from_mnemonic(&mnemonic, self.network).map_err(|e| {
+                    StateExportError::Conversion("wallet".to_owned(), e.to_string())
+                })?,
+            )),
+            Some(WalletExport::Hd {
+                xprv: Some(xprv), ..
+            }) => Some(Wallet::HDWallet(
+                HDWallet::from_xprv(&xprv, self.network).map_err(|e| {
+                    StateExportError::Conversion("wallet".to_owned(), e.to_string())
+                })?,
+            )),
+            Some(_) => {
+                skipped.push("redacted wallet".to_owned());
+                None
+            }
+            None => None,
+        };
+
+        let summary = format!(
+            "Imported {} identities, {} private keys, {} contracts and {} strategies from {}",
+            identities.len(),
+            private_keys.len(),
+            contracts.len(),
+            strategies.len(),
+            path
+        );
+
+        self.known_identities.lock().await.extend(identities);
+        self.identity_private_keys.lock().await.extend(private_keys);
+        self.known_contracts.lock().await.extend(contracts);
+        {
+            let mut available_strategies = self.available_strategies.lock().await;
+            let mut contract_names = self.available_strategies_contract_names.lock().await;
+            let mut operation_keys = self.available_strategies_operation_keys.lock().await;
+            let mut seeds = self.available_strategies_seeds.lock().await;
+            for (name, (strategy, names, keys, seed)) in strategies {
+                available_strategies.insert(name.clone(), strategy);
+                contract_names.insert(name.clone(), names);
+                operation_keys.insert(name.clone(), keys);
+                match seed {
+                    Some(seed) => seeds.insert(name, seed),
+                    None => seeds.remove(&name),
+                };
+            }
+        }
+
+        if let Some(identity) = loaded_identity {
+            let mut current = self.loaded_identity.lock().await;
+            if current.is_none() {
+                *current = Some(identity);
+            } else {
+                skipped.push("loaded identity, another one is loaded".to_owned());
+            }
+        }
+        if let Some(wallet) = wallet {
+            let mut current = self.loaded_wallet.lock().await;
+            if current.is_none() {
+                *current = Some(wallet);
+            } else {
+                skipped.push("wallet, another one is loaded".to_owned());
+            }
+        }
+
+        if skipped.is_empty() {
+            Ok(summary)
+        } else {
+            Ok(format!("{}\nSkipped: {}", summary, skipped.join(", ")))
+        }
+    }
+}
+
+fn parse_identifier(id: &str) -> Result<Identifier, StateExportError> {
+    Identifier::from_string(id, Encoding::Base58)
+        .map_err(|e| StateExportError::Conversion(id.to_owned(), e.to_string()))
+}
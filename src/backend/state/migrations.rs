@@ -0,0 +1,541 @@
+//! State file schema versioning.
+//!
+//! Every state file starts with [MAGIC] followed by a big endian `u16` schema
+//! version, the bincode encoded state goes after. Files written before the
+//! header was introduced are recognized by trying the known headerless layouts
+//! from the newest to the oldest one.
+//!
+//! To change [AppStateInSerializationFormat], keep its previous definition
+//! here under a versioned name, bump [CURRENT_SCHEMA_VERSION] and add a step
+//! to [migrate] that upgrades the previous layout to the next one.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use bincode::{config, Decode};
+use dpp::{
+    dashcore::Network,
+    identity::{accessors::IdentityGettersV0, IdentityPublicKey},
+    platform_value::string_encoding::Encoding,
+    prelude::{AssetLockProof, Identifier, Identity},
+    ProtocolError::{self, PlatformDeserializationError, PlatformSerializationError},
+};
+
+use super::{
+    AppStateInSerializationFormat, ContractFileName, IdentityPrivateKeysMap, StrategyOperationKeys,
+};
+use crate::backend::wallet::{SingleKeyWallet, Wallet};
+
+/// Marks a state file that carries a schema version header.
+const MAGIC: &[u8; 8] = b"EXPLSTAT";
+
+/// Schema version of [AppStateInSerializationFormat].
+pub(super) const CURRENT_SCHEMA_VERSION: u16 = 3;
+
+/// Known schema versions:
+/// - 0: initial layout, no network stored;
+/// - 1: network stored for the state and each wallet;
+/// - 2: keys assigned to strategy operations;
+/// - 3: seeds of strategy runs.
+pub type SchemaVersion = u16;
+
+fn bincode_config() -> impl config::Config {
+    config::standard().with_big_endian().with_no_limit()
+}
+
+/// Encodes the state in the current schema version, header included.
+pub(super) fn encode(state: AppStateInSerializationFormat) -> Result<Vec<u8>, ProtocolError> {
+    let mut bytes = Vec::from(MAGIC.as_slice());
+    bytes.extend_from_slice(&CURRENT_SCHEMA_VERSION.to_be_bytes());
+
+    let payload = bincode::encode_to_vec(state, bincode_config())
+        .map_err(|e| PlatformSerializationError(format!("unable to serialize App State: {}", e)))?;
+    bytes.extend(payload);
+
+    Ok(bytes)
+}
+
+/// Returns the schema version from the state file header, `None` is returned
+/// for files written before the header was introduced.
+pub(super) fn header_version(data: &[u8]) -> Option<SchemaVersion> {
+    let version = data.strip_prefix(MAGIC.as_slice())?.get(..2)?;
+    Some(SchemaVersion::from_be_bytes([version[0], version[1]]))
+}
+
+/// Decodes a state file of any supported schema version and migrates it to
+/// the current one, files that don't store the network are migrated to
+/// `network`. Returns the schema version the file was written with.
+pub(super) fn decode(
+    data: &[u8],
+    network: Network,
+) -> Result<(AppStateInSerializationFormat, SchemaVersion), ProtocolError> {
+    let Some(version) = header_version(data) else {
+        return decode_headerless(data, network);
+    };
+    let payload = &data[MAGIC.len() + 2..];
+
+    let state = match version {
+        1 => migrate(StateLayout::V1(decode_exact(payload)?), network)?,
+        2 => migrate(StateLayout::V2(decode_exact(payload)?), network)?,
+        3 => migrate(StateLayout::V3(decode_exact(payload)?), network)?,
+        version if version > CURRENT_SCHEMA_VERSION => {
+            return Err(PlatformDeserializationError(format!(
+                "state file schema version {} is newer than supported version {}, please \
+                 update the explorer",
+                version, CURRENT_SCHEMA_VERSION
+            )))
+        }
+        version => {
+            return Err(PlatformDeserializationError(format!(
+                "unknown state file schema version {}",
+                version
+            )))
+        }
+    };
+
+    Ok((state, version))
+}
+
+/// State files written before the version header existed.
+fn decode_headerless(
+    data: &[u8],
+    network: Network,
+) -> Result<(AppStateInSerializationFormat, SchemaVersion), ProtocolError> {
+    // Layout of version 1 starts with the network name, a version 0 file will
+    // hardly pass as it, but check the network to be sure
+    if let Ok(state) = decode_exact::<AppStateV1>(data) {
+        if Network::from_str(&state.network).is_ok() {
+            return Ok((migrate(StateLayout::V1(state), network)?, 1));
+        }
+    }
+
+    let state = decode_exact::<AppStateV0>(data).map_err(|e| {
+        PlatformDeserializationError(format!(
+            "state file doesn't match any known schema version: {}",
+            e
+        ))
+    })?;
+
+    Ok((migrate(StateLayout::V0(state), network)?, 0))
+}
+
+/// Decodes a value that must take all of the input.
+fn decode_exact<T: Decode>(data: &[u8]) -> Result<T, ProtocolError> {
+    let (value, read) = bincode::decode_from_slice(data, bincode_config()).map_err(|e| {
+        PlatformDeserializationError(format!(
+            "Error decoding AppStateInSerializationFormat: {}",
+            e
+        ))
+    })?;
+
+    if read != data.len() {
+        return Err(PlatformDeserializationError(format!(
+            "unexpected {} trailing bytes in state file",
+            data.len() - read
+        )));
+    }
+
+    Ok(value)
+}
+
+enum StateLayout {
+    V0(AppStateV0),
+    V1(AppStateV1),
+    V2(AppStateV2),
+    V3(AppStateInSerializationFormat),
+}
+
+/// Applies migration steps one by one until the current layout is reached,
+/// `network` is used by the layouts that didn't store it.
+fn migrate(
+    mut layout: StateLayout,
+    network: Network,
+) -> Result<AppStateInSerializationFormat, ProtocolError> {
+    loop {
+        layout = match layout {
+            StateLayout::V0(state) => StateLayout::V1(migrate_v0_to_v1(state, network)),
+            StateLayout::V1(state) => StateLayout::V2(migrate_v1_to_v2(state)),
+            StateLayout::V2(state) => StateLayout::V3(migrate_v2_to_v3(state)),
+            StateLayout::V3(state) => return Ok(state),
+        };
+    }
+}
+
+/// Version 0 of the state layout, the network was not stored and is taken
+/// from the configuration.
+#[derive(Decode)]
+struct AppStateV0 {
+    loaded_identity: Option<Identity>,
+    identity_private_keys: IdentityPrivateKeysMap,
+    loaded_wallet: Option<WalletV0>,
+    known_identities: BTreeMap<Identifier, Identity>,
+    known_contracts: BTreeMap<String, Vec<u8>>,
+    supporting_contracts: BTreeMap<String, Vec<u8>>,
+    available_strategies: BTreeMap<String, Vec<u8>>,
+    available_strategies_contract_names:
+        BTreeMap<String, Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>>,
+    selected_strategy: Option<String>,
+    identity_asset_lock_private_key_in_creation: Option<(
+        Vec<u8>,
+        [u8; 32],
+        Option<AssetLockProof>,
+        Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
+    )>,
+    identity_asset_lock_private_key_in_top_up: Option<(Vec<u8>, [u8; 32], Option<AssetLockProof>)>,
+}
+
+#[derive(Decode)]
+enum WalletV0 {
+    SingleKeyWallet(SingleKeyWalletV0),
+}
+
+#[derive(Decode)]
+struct SingleKeyWalletV0 {
+    private_key: [u8; 32],
+    utxos: Vec<(String, u64, String)>,
+}
+
+fn migrate_v0_to_v1(state: AppStateV0, network: Network) -> AppStateV1 {
+    let loaded_wallet = state.loaded_wallet.map(|wallet| match wallet {
+        WalletV0::SingleKeyWallet(SingleKeyWalletV0 { private_key, utxos }) => {
+            Wallet::SingleKeyWallet(SingleKeyWallet::from_raw_parts(private_key, network, utxos))
+        }
+    });
+
+    AppStateV1 {
+        network: network.to_string(),
+        loaded_identity: state.loaded_identity,
+        identity_private_keys: state.identity_private_keys,
+        loaded_wallet,
+        known_identities: state.known_identities,
+        known_contracts: state.known_contracts,
+        supporting_contracts: state.supporting_contracts,
+        available_strategies: state.available_strategies,
+        available_strategies_contract_names: state.available_strategies_contract_names,
+        selected_strategy: state.selected_strategy,
+        identity_asset_lock_private_key_in_creation: state
+            .identity_asset_lock_private_key_in_creation,
+        identity_asset_lock_private_key_in_top_up: state.identity_asset_lock_private_key_in_top_up,
+    }
+}
+
+/// Version 1 of the state layout, no keys were assigned to strategy
+/// operations.
+#[derive(Decode)]
+struct AppStateV1 {
+    network: String,
+    loaded_identity: Option<Identity>,
+    identity_private_keys: IdentityPrivateKeysMap,
+    loaded_wallet: Option<Wallet>,
+    known_identities: BTreeMap<Identifier, Identity>,
+    known_contracts: BTreeMap<String, Vec<u8>>,
+    supporting_contracts: BTreeMap<String, Vec<u8>>,
+    available_strategies: BTreeMap<String, Vec<u8>>,
+    available_strategies_contract_names:
+        BTreeMap<String, Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>>,
+    selected_strategy: Option<String>,
+    identity_asset_lock_private_key_in_creation: Option<(
+        Vec<u8>,
+        [u8; 32],
+        Option<AssetLockProof>,
+        Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
+    )>,
+    identity_asset_lock_private_key_in_top_up: Option<(Vec<u8>, [u8; 32], Option<AssetLockProof>)>,
+}
+
+fn migrate_v1_to_v2(state: AppStateV1) -> AppStateV2 {
+    AppStateV2 {
+        network: state.network,
+        loaded_identity: state.loaded_identity,
+        identity_private_keys: state.identity_private_keys,
+        loaded_wallet: state.loaded_wallet,
+        known_identities: state.known_identities,
+        known_contracts: state.known_contracts,
+        supporting_contracts: state.supporting_contracts,
+        available_strategies: state.available_strategies,
+        available_strategies_contract_names: state.available_strategies_contract_names,
+        selected_strategy: state.selected_strategy,
+        identity_asset_lock_private_key_in_creation: state
+            .identity_asset_lock_private_key_in_creation,
+        identity_asset_lock_private_key_in_top_up: state.identity_asset_lock_private_key_in_top_up,
+        available_strategies_operation_keys: BTreeMap::new(),
+    }
+}
+
+/// Version 2 of the state layout, no seeds were stored for strategies.
+#[derive(Decode)]
+struct AppStateV2 {
+    network: String,
+    loaded_identity: Option<Identity>,
+    identity_private_keys: IdentityPrivateKeysMap,
+    loaded_wallet: Option<Wallet>,
+    known_identities: BTreeMap<Identifier, Identity>,
+    known_contracts: BTreeMap<String, Vec<u8>>,
+    supporting_contracts: BTreeMap<String, Vec<u8>>,
+    available_strategies: BTreeMap<String, Vec<u8>>,
+    available_strategies_contract_names:
+        BTreeMap<String, Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>>,
+    selected_strategy: Option<String>,
+    identity_asset_lock_private_key_in_creation: Option<(
+        Vec<u8>,
+        [u8; 32],
+        Option<AssetLockProof>,
+        Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
+    )>,
+    identity_asset_lock_private_key_in_top_up: Option<(Vec<u8>, [u8; 32], Option<AssetLockProof>)>,
+    available_strategies_operation_keys: BTreeMap<String, StrategyOperationKeys>,
+}
+
+fn migrate_v2_to_v3(state: AppStateV2) -> AppStateInSerializationFormat {
+    AppStateInSerializationFormat {
+        network: state.network,
+        loaded_identity: state.loaded_identity,
+        identity_private_keys: state.identity_private_keys,
+        loaded_wallet: state.loaded_wallet,
+        known_identities: state.known_identities,
+        known_contracts: state.known_contracts,
+        supporting_contracts: state.supporting_contracts,
+        available_strategies: state.available_strategies,
+        available_strategies_contract_names: state.available_strategies_contract_names,
+        selected_strategy: state.selected_strategy,
+        identity_asset_lock_private_key_in_creation: state
+            .identity_asset_lock_private_key_in_creation,
+        identity_asset_lock_private_key_in_top_up: state.identity_asset_lock_private_key_in_top_up,
+        available_strategies_operation_keys: state.available_strategies_operation_keys,
+        available_strategies_seeds: BTreeMap::new(),
+    }
+}
+
+/// Summary of a state file migration, lists what was carried over.
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub from_version: SchemaVersion,
+    pub to_version: SchemaVersion,
+    pub network: String,
+    pub loaded_identity: Option<Identifier>,
+    pub identity_private_keys: usize,
+    pub wallet: Option<&'static str>,
+    pub known_identities: usize,
+    pub known_contracts: usize,
+    pub strategies: usize,
+    pub asset_lock_in_creation: bool,
+    pub asset_lock_in_top_up: bool,
+}
+
+impl MigrationReport {
+    pub(super) fn new(state: &AppStateInSerializationFormat, from_version: SchemaVersion) -> Self {
+        MigrationReport {
+            from_version,
+            to_version: CURRENT_SCHEMA_VERSION,
+            network: state.network.clone(),
+            loaded_identity: state.loaded_identity.as_ref().map(|identity| identity.id()),
+            identity_private_keys: state.identity_private_keys.len(),
+            wallet: state.loaded_wallet.as_ref().map(|wallet| match wallet {
+                Wallet::SingleKeyWallet(_) => "single key wallet",
+                Wallet::HDWallet(_) => "HD wallet",
+            }),
+            known_identities: state.known_identities.len(),
+            known_contracts: state.known_contracts.len(),
+            strategies: state.available_strategies.len(),
+            asset_lock_in_creation: state.identity_asset_lock_private_key_in_creation.is_some(),
+            asset_lock_in_top_up: state.identity_asset_lock_private_key_in_top_up.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.from_version == self.to_version {
+            writeln!(
+                f,
+                "State file is already at schema version {}",
+                self.to_version
+            )?;
+        } else {
+            writeln!(
+                f,
+                "Migrated state file from schema version {} to {}",
+                self.from_version, self.to_version
+            )?;
+        }
+        writeln!(f, "  network: {}", self.network)?;
+        match &self.loaded_identity {
+            Some(id) => writeln!(f, "  loaded identity: {}", id.to_string(Encoding::Base58))?,
+            None => writeln!(f, "  loaded identity: none")?,
+        }
+        writeln!(f, "  identity private keys: {}", self.identity_private_keys)?;
+        writeln!(f, "  wallet: {}", self.wallet.unwrap_or("none"))?;
+        writeln!(f, "  known identities: {}", self.known_identities)?;
+        writeln!(f, "  known contracts: {}", self.known_contracts)?;
+        writeln!(f, "  strategies: {}", self.strategies)?;
+        writeln!(
+            f,
+            "  identity registration in progress: {}",
+            self.asset_lock_in_creation
+        )?;
+        write!(
+            f,
+            "  identity top up in progress: {}",
+            self.asset_lock_in_top_up
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::backend::strategies::OperationKey;
+
+    /// State files of every supported layout, all holding the same state:
+    /// - loaded identity `[1; 32]` with 1000000 credits at revision 1 and the
+    ///   private keys 0 and 1 of it;
+    /// - known identity `[2; 32]`;
+    /// - a single key wallet with one UTXO of 100000 duffs;
+    /// - strategy `load` with contract `dashpay` updated by `dashpay-update`,
+    ///   key 1 assigned to its first operation from version 2 and seed 42
+    ///   from version 3.
+    ///
+    /// Layouts before version 1 don't store the network and are decoded with
+    /// the configured one, the others store regtest.
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/state");
+
+    fn decode_fixture(
+        name: &str,
+        network: Network,
+    ) -> (AppStateInSerializationFormat, SchemaVersion) {
+        let data = std::fs::read(Path::new(FIXTURES_DIR).join(name))
+            .unwrap_or_else(|e| panic!("unable to read fixture {}: {}", name, e));
+        decode(&data, network)
+            .unwrap_or_else(|e| panic!("unable to decode fixture {}: {}", name, e))
+    }
+
+    fn assert_migrated(state: &AppStateInSerializationFormat, network: Network) {
+        assert_eq!(state.network, network.to_string());
+
+        let loaded_identity = state.loaded_identity.as_ref().expect("loaded identity");
+        let loaded_identity_id = Identifier::new([1; 32]);
+        assert_eq!(loaded_identity.id(), loaded_identity_id);
+        assert_eq!(loaded_identity.balance(), 1_000_000);
+        assert_eq!(loaded_identity.revision(), 1);
+        assert_eq!(
+            state.identity_private_keys.keys().collect::<Vec<_>>(),
+            [&(loaded_identity_id, 0), &(loaded_identity_id, 1)]
+        );
+        assert_eq!(
+            state.identity_private_keys[&(loaded_identity_id, 0)],
+            [0x21; 32]
+        );
+
+        let known_identity_id = Identifier::new([2; 32]);
+        assert_eq!(
+            state.known_identities.keys().collect::<Vec<_>>(),
+            [&known_identity_id]
+        );
+        assert_eq!(state.known_identities[&known_identity_id].balance(), 5);
+
+        let Some(Wallet::SingleKeyWallet(wallet)) = &state.loaded_wallet else {
+            panic!("expected a single key wallet");
+        };
+        assert_eq!(wallet.private_key.network, network);
+        assert_eq!(wallet.private_key.inner.secret_bytes(), [0x11; 32]);
+        assert_eq!(wallet.balance(), 100_000);
+
+        assert_eq!(
+            state.available_strategies.keys().collect::<Vec<_>>(),
+            ["load"]
+        );
+        assert_eq!(state.available_strategies["load"], [1, 2, 3]);
+        assert_eq!(
+            state.available_strategies_contract_names["load"],
+            [(
+                "dashpay".to_owned(),
+                Some(BTreeMap::from([(1, "dashpay-update".to_owned())]))
+            )]
+        );
+        assert_eq!(state.selected_strategy.as_deref(), Some("load"));
+        assert!(state.identity_asset_lock_private_key_in_creation.is_none());
+        assert!(state.identity_asset_lock_private_key_in_top_up.is_none());
+    }
+
+    fn assert_operation_keys(state: &AppStateInSerializationFormat) {
+        assert_eq!(
+            state.available_strategies_operation_keys["load"],
+            [Some(OperationKey::Id(1)), None]
+        );
+    }
+
+    #[test]
+    fn migrates_headerless_v0() {
+        let (state, version) = decode_fixture("v0_headerless.bin", Network::Testnet);
+        assert_eq!(version, 0);
+        assert_migrated(&state, Network::Testnet);
+        assert!(state.available_strategies_operation_keys.is_empty());
+        assert!(state.available_strategies_seeds.is_empty());
+    }
+
+    #[test]
+    fn migrates_headerless_v0_to_the_configured_network() {
+        for network in [Network::Dash, Network::Devnet, Network::Regtest] {
+            let (state, version) = decode_fixture("v0_headerless.bin", network);
+            assert_eq!(version, 0);
+            assert_migrated(&state, network);
+        }
+    }
+
+    #[test]
+    fn migrates_headerless_v1() {
+        let (state, version) = decode_fixture("v1_headerless.bin", Network::Regtest);
+        assert_eq!(version, 1);
+        assert_migrated(&state, Network::Regtest);
+        assert!(state.available_strategies_operation_keys.is_empty());
+        assert!(state.available_strategies_seeds.is_empty());
+    }
+
+    #[test]
+    fn migrates_v1() {
+        // The stored network wins over the configured one
+        let (state, version) = decode_fixture("v1.bin", Network::Testnet);
+        assert_eq!(version, 1);
+        assert_migrated(&state, Network::Regtest);
+        assert!(state.available_strategies_operation_keys.is_empty());
+        assert!(state.available_strategies_seeds.is_empty());
+    }
+
+    #[test]
+    fn migrates_v2() {
+        let (state, version) = decode_fixture("v2.bin", Network::Regtest);
+        assert_eq!(version, 2);
+        assert_migrated(&state, Network::Regtest);
+        assert_operation_keys(&state);
+        assert!(state.available_strategies_seeds.is_empty());
+    }
+
+    #[test]
+    fn decodes_v3() {
+        let (state, version) = decode_fixture("v3.bin", Network::Regtest);
+        assert_eq!(version, CURRENT_SCHEMA_VERSION);
+        assert_migrated(&state, Network::Regtest);
+        assert_operation_keys(&state);
+        assert_eq!(state.available_strategies_seeds["load"], 42);
+    }
+
+    #[test]
+    fn migrated_state_is_written_in_the_current_version() {
+        let (state, _) = decode_fixture("v0_headerless.bin", Network::Dash);
+        let data = encode(state).expect("encoded state");
+        assert_eq!(header_version(&data), Some(CURRENT_SCHEMA_VERSION));
+
+        let (state, version) = decode(&data, Network::Testnet).expect("decoded state");
+        assert_eq!(version, CURRENT_SCHEMA_VERSION);
+        assert_migrated(&state, Network::Dash);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut data = std::fs::read(Path::new(FIXTURES_DIR).join("v3.bin")).expect("fixture");
+        data[MAGIC.len()..MAGIC.len() + 2]
+            .copy_from_slice(&(CURRENT_SCHEMA_VERSION + 1).to_be_bytes());
+        assert!(decode(&data, Network::Regtest).is_err());
+    }
+}
@@ -1,9 +1,19 @@
 //! Strategies management backend module.
 
+mod contract_updates;
+mod definition;
+mod dry_run;
+mod keys;
+mod local_drive;
+mod pending_identities;
+mod report;
+
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     fs::File,
+    future::Future,
     io::Write,
+    path::PathBuf,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -13,10 +23,11 @@ use std::{
 
 use dapi_grpc::platform::v0::{
     get_epochs_info_request, get_epochs_info_response,
+    get_identity_balance_request::{self, GetIdentityBalanceRequestV0},
     wait_for_state_transition_result_response::{
         self, wait_for_state_transition_result_response_v0,
     },
-    GetEpochsInfoRequest,
+    GetEpochsInfoRequest, GetIdentityBalanceRequest,
 };
 use dash_sdk::platform::transition::withdraw_from_identity::WithdrawFromIdentity;
 use dash_sdk::{
@@ -32,6 +43,7 @@ use dpp::{
         document_type::random_document::{DocumentFieldFillSize, DocumentFieldFillType},
         DataContract,
     },
+    document::Document,
     identity::{
         accessors::IdentityGettersV0, state_transition::asset_lock_proof::AssetLockProof, Identity,
         KeyType, PartialIdentity, Purpose, SecurityLevel,
@@ -44,8 +56,7 @@ use dpp::{
     state_transition::{
         documents_batch_transition::{
             document_base_transition::v0::v0_methods::DocumentBaseTransitionV0Methods,
-            document_transition::DocumentTransition, DocumentCreateTransition,
-            DocumentsBatchTransition,
+            document_transition::DocumentTransitionV0Methods, DocumentsBatchTransition,
         },
         StateTransition, StateTransitionLike,
     },
@@ -61,29 +72,57 @@ use drive::{
     query::DriveQuery,
 };
 use futures::future::join_all;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rs_dapi_client::{DapiRequest, DapiRequestExecutor, RequestSettings};
-use simple_signer::signer::SimpleSigner;
 use strategy_tests::{
     frequency::Frequency,
     operations::{DocumentAction, DocumentOp, FinalizeBlockOperation, Operation, OperationType},
     IdentityInsertInfo, LocalDocumentQuery, StartIdentities, Strategy, StrategyConfig,
 };
-use tokio::sync::{Mutex, MutexGuard};
+use tokio::sync::{broadcast, Mutex, MutexGuard};
+use tokio_util::sync::CancellationToken;
 
-use crate::backend::{wallet::SingleKeyWallet, Wallet};
+pub use self::contract_updates::{ContractUpdateEdit, CONTRACT_UPDATE_BLOCK_INTERVAL};
+pub(crate) use self::definition::{
+    random_document_type_parameters, strategy_rng, OPERATIONS_RNG_STREAM,
+};
+pub use self::definition::{
+    DefinitionError, DefinitionProblem, StrategyDefinition, DEFINITIONS_DIR,
+};
+use self::definition::{ASSET_LOCKS_RNG_STREAM, START_CONTRACTS_RNG_STREAM};
+pub use self::dry_run::{DryRunError, DryRunReport, DryRunTransitionSummary, NonceUsage};
+pub use self::keys::{KeyPreflightError, OperationKey};
+use self::local_drive::LocalDriveSync;
+use self::pending_identities::{created_identity_id, PendingIdentities};
+use self::report::{BlockRecord, RunSettings, StrategySummary, TransitionOutcome};
+pub use self::report::{IdentityRegistrations, ReportError, StrategyRunReport, REPORTS_DIR};
 
 use super::{
     insight::InsightAPIClient,
-    state::{ContractFileName, KnownContractsMap},
-    AppState, AppStateUpdate, BackendEvent, StrategyCompletionResult, StrategyContractNames, Task,
+    state::{ContractFileName, KnownContractsMap, StrategiesMap},
+    AppState, AppStateUpdate, BackendEvent, CompletedTaskPayload, StrategyCompletionResult,
+    StrategyContractNames, StrategyRunStats, Task,
 };
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum StrategyTask {
     CreateStrategy(String),
-    ImportStrategy(String),
+    /// Imports an exported strategy from an HTTP(S) URL, a `file://` URL or a
+    /// path, replacing a strategy of the same name only if `overwrite` is set
+    ImportStrategy {
+        source: String,
+        strategy_name: String,
+        overwrite: bool,
+    },
     ExportStrategy(String),
+    /// Compiles a strategy definition file (TOML or JSON) into a new strategy
+    LoadStrategyDefinition(PathBuf),
+    /// Saves a strategy as a definition file, the format is picked by the
+    /// extension
+    SaveStrategyDefinition {
+        strategy_name: String,
+        path: PathBuf,
+    },
     SelectStrategy(String),
     DeleteStrategy(String),
     CloneStrategy(String),
@@ -106,13 +145,268 @@ pub enum StrategyTask {
         operation: Operation,
     },
     RegisterDocsToAllContracts(String, u16, DocumentFieldFillSize, DocumentFieldFillType),
-    RunStrategy(String, u64, bool, bool),
+    /// Runs a strategy for a number of blocks (or seconds) with proof
+    /// verification and block mode flags, the seed overrides the one stored
+    /// for the strategy
+    RunStrategy(String, u64, bool, bool, Option<u64>),
+    /// Generates state transitions for the given number of blocks without
+    /// broadcasting them, optionally writing them to `dump_dir`
+    DryRunStrategy {
+        strategy_name: String,
+        blocks: u64,
+        dump_dir: Option<PathBuf>,
+        /// Overrides the seed stored for the strategy
+        seed: Option<u64>,
+    },
+    /// Stops the runs and dry runs of the strategy after their current block
+    /// (or second), handled by the task scheduler which owns their stop tokens
+    StopStrategy(String),
     RemoveLastContract(String),
     ClearContracts(String),
     ClearOperations(String),
     RemoveIdentityInserts(String),
     RemoveStartIdentities(String),
     RemoveLastOperation(String),
+    /// Replaces the operation at `index`
+    EditOperation {
+        strategy_name: String,
+        index: usize,
+        operation: Operation,
+    },
+    RemoveOperation {
+        strategy_name: String,
+        index: usize,
+    },
+    /// Inserts a copy of the operation at `index` right after it
+    DuplicateOperation {
+        strategy_name: String,
+        index: usize,
+    },
+    MoveOperation {
+        strategy_name: String,
+        from: usize,
+        to: usize,
+    },
+    /// Replaces the start contract at `index` with the first of
+    /// `contract_names`, the rest are its updates and take the blocks of the
+    /// replaced updates in order
+    EditContract {
+        strategy_name: String,
+        index: usize,
+        contract_names: Vec<String>,
+    },
+    RemoveContract {
+        strategy_name: String,
+        index: usize,
+    },
+    /// Inserts a copy of the start contract at `index` right after it
+    DuplicateContract {
+        strategy_name: String,
+        index: usize,
+    },
+    MoveContract {
+        strategy_name: String,
+        from: usize,
+        to: usize,
+    },
+    /// Changes the planned updates of the start contract at `index`, the
+    /// resulting timeline is checked for compatibility before it's saved
+    EditContractUpdates {
+        strategy_name: String,
+        index: usize,
+        edit: ContractUpdateEdit,
+    },
+    /// Assigns the key of the loaded identity the operation at `index` is
+    /// signed with, `None` restores the default key selection
+    SetOperationKey {
+        strategy_name: String,
+        index: usize,
+        key: Option<OperationKey>,
+    },
+    /// Stores the seed runs of the strategy use, `None` makes every run use a
+    /// random one
+    SetStrategySeed {
+        strategy_name: String,
+        seed: Option<u64>,
+    },
+}
+
+impl std::fmt::Display for StrategyTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrategyTask::CreateStrategy(name) => write!(f, "Create strategy {}", name),
+            StrategyTask::ImportStrategy { strategy_name, .. } => {
+                write!(f, "Import strategy {}", strategy_name)
+            }
+            StrategyTask::ExportStrategy(name) => write!(f, "Export strategy {}", name),
+            StrategyTask::LoadStrategyDefinition(path) => {
+                write!(f, "Load strategy definition {}", path.display())
+            }
+            StrategyTask::SaveStrategyDefinition {
+                strategy_name,
+                path,
+            } => write!(f, "Save strategy {} to {}", strategy_name, path.display()),
+            StrategyTask::SelectStrategy(name) => write!(f, "Select strategy {}", name),
+            StrategyTask::DeleteStrategy(name) => write!(f, "Delete strategy {}", name),
+            StrategyTask::CloneStrategy(name) => write!(f, "Clone strategy {}", name),
+            StrategyTask::SetStartContracts(name, _)
+            | StrategyTask::SetStartContractsRandom(name, ..) => {
+                write!(f, "Set start contracts of strategy {}", name)
+            }
+            StrategyTask::SetIdentityInserts { strategy_name, .. } => {
+                write!(f, "Set identity inserts of strategy {}", strategy_name)
+            }
+            StrategyTask::SetStartIdentities { strategy_name, .. }
+            | StrategyTask::SetStartIdentitiesBalance(strategy_name, _) => {
+                write!(f, "Set start identities of strategy {}", strategy_name)
+            }
+            StrategyTask::AddOperation { strategy_name, .. } => {
+                write!(f, "Add operation to strategy {}", strategy_name)
+            }
+            StrategyTask::RegisterDocsToAllContracts(name, ..) => {
+                write!(f, "Add document operations to strategy {}", name)
+            }
+            StrategyTask::RunStrategy(name, blocks_or_seconds, _, block_mode, _) => write!(
+                f,
+                "Run strategy {} for {} {}",
+                name,
+                blocks_or_seconds,
+                if *block_mode { "blocks" } else { "seconds" }
+            ),
+            StrategyTask::DryRunStrategy {
+                strategy_name,
+                blocks,
+                ..
+            } => write!(
+                f,
+                "Dry run strategy {} for {} blocks",
+                strategy_name, blocks
+            ),
+            StrategyTask::StopStrategy(name) => write!(f, "Stop strategy {}", name),
+            StrategyTask::RemoveLastContract(name)
+            | StrategyTask::ClearContracts(name)
+            | StrategyTask::RemoveContract {
+                strategy_name: name,
+                ..
+            } => write!(f, "Remove contracts of strategy {}", name),
+            StrategyTask::ClearOperations(name)
+            | StrategyTask::RemoveLastOperation(name)
+            | StrategyTask::RemoveOperation {
+                strategy_name: name,
+                ..
+            } => write!(f, "Remove operations of strategy {}", name),
+            StrategyTask::RemoveIdentityInserts(name) => {
+                write!(f, "Remove identity inserts of strategy {}", name)
+            }
+            StrategyTask::RemoveStartIdentities(name) => {
+                write!(f, "Remove start identities of strategy {}", name)
+            }
+            StrategyTask::EditOperation { strategy_name, .. }
+            | StrategyTask::DuplicateOperation { strategy_name, .. }
+            | StrategyTask::MoveOperation { strategy_name, .. }
+            | StrategyTask::SetOperationKey { strategy_name, .. } => {
+                write!(f, "Edit operations of strategy {}", strategy_name)
+            }
+            StrategyTask::EditContract { strategy_name, .. }
+            | StrategyTask::DuplicateContract { strategy_name, .. }
+            | StrategyTask::MoveContract { strategy_name, .. }
+            | StrategyTask::EditContractUpdates { strategy_name, .. } => {
+                write!(f, "Edit contracts of strategy {}", strategy_name)
+            }
+            StrategyTask::SetStrategySeed { strategy_name, .. } => {
+                write!(f, "Set seed of strategy {}", strategy_name)
+            }
+        }
+    }
+}
+
+/// Snapshot of a running strategy, published after every block (or second) of
+/// [StrategyTask::RunStrategy].
+#[derive(Debug, Clone)]
+pub struct StrategyProgress {
+    pub strategy_name: String,
+    pub block_mode: bool,
+    /// Index of the finished block (or second), the first two are
+    /// initialization blocks
+    pub index: u64,
+    /// Number of blocks (or seconds) requested, initialization not included
+    pub total: u64,
+    pub block_height: u64,
+    /// State transitions sent during this block (or second)
+    pub step_transitions: u64,
+    pub transition_count: u64,
+    pub success_count: u64,
+    pub error_count: u64,
+    /// Credits spent by the loaded identity, unknown until its balance is
+    /// fetched
+    pub credits_spent: Option<u64>,
+}
+
+/// The loaded identity's balance is fetched for the progress only every this
+/// many blocks (or seconds), and on the last block, as a proved fetch takes
+/// about as long as a block.
+const PROGRESS_BALANCE_INTERVAL: u64 = 10;
+
+/// Directory strategies are exported to.
+pub const EXPORTS_DIR: &str = "supporting_files/strategy_exports";
+
+/// Name of a strategy imported from `source`: its file name without the
+/// extension.
+pub fn strategy_name_from_source(source: &str) -> Option<String> {
+    source
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .map(|file_name| {
+            file_name
+                .rsplit_once('.')
+                .map_or(file_name, |(name, _)| name)
+        })
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+}
+
+/// Reads an exported strategy from an HTTP(S) URL, a `file://` URL or a path.
+async fn read_strategy_source(source: &str) -> Result<Vec<u8>, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::get(source)
+            .await
+            .map_err(|e| format!("Failed to fetch strategy: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to fetch strategy: HTTP {}",
+                response.status()
+            ));
+        }
+        response
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| format!("Failed to fetch strategy data: {}", e))
+    } else {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read strategy file {}: {}", path, e))
+    }
+}
+
+/// How long outstanding broadcasts of a stopped strategy are waited for.
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Reported for state transitions whose results didn't arrive in
+/// [STOP_TIMEOUT].
+const STOP_TIMEOUT_ERROR: &str = "no result before the stop timeout";
+
+/// Awaits a batch of broadcasts, giving it at most [STOP_TIMEOUT] more once
+/// a stop is requested. Returns `None` if the batch didn't finish in time.
+async fn await_batch<F: Future>(batch: F, stop: &CancellationToken) -> Option<F::Output> {
+    tokio::pin!(batch);
+    tokio::select! {
+        output = &mut batch => return Some(output),
+        _ = stop.cancelled() => {}
+    }
+    tokio::time::timeout(STOP_TIMEOUT, batch).await.ok()
 }
 
 pub async fn run_strategy_task<'s>(
@@ -120,8 +414,16 @@ pub async fn run_strategy_task<'s>(
     app_state: &'s AppState,
     task: StrategyTask,
     insight: &'s InsightAPIClient,
+    progress: &broadcast::Sender<StrategyProgress>,
+    stop: CancellationToken,
 ) -> BackendEvent<'s> {
     match task {
+        // Runs started through the scheduler are stopped by it, there is
+        // nothing to stop otherwise
+        StrategyTask::StopStrategy(_) => BackendEvent::TaskCompleted {
+            task: Task::Strategy(task),
+            execution_result: Err("No strategy run to stop".to_owned()),
+        },
         StrategyTask::CreateStrategy(strategy_name) => {
             let mut strategies_lock = app_state.available_strategies.lock().await;
             let mut contract_names_lock =
@@ -131,6 +433,7 @@ pub async fn run_strategy_task<'s>(
             strategies_lock.insert(strategy_name.clone(), Strategy::default());
             *selected_strategy_lock = Some(strategy_name.clone());
             contract_names_lock.insert(strategy_name.clone(), Default::default());
+            clear_strategy_settings(app_state, &strategy_name).await;
 
             BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                 strategy_name.clone(),
@@ -142,109 +445,83 @@ pub async fn run_strategy_task<'s>(
                 }),
             ))
         }
-        StrategyTask::ImportStrategy(url) => {
-            match reqwest::get(&url).await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        match response.bytes().await {
-                            Ok(bytes) => {
-                                match Strategy::versioned_deserialize(&bytes, true, &sdk.version())
-                                {
-                                    Ok(strategy) => {
-                                        let strategy_name = url.split('/').last()
-                                            .map(|s| s.rsplit_once('.').map_or(s, |(name, _)| name))
-                                            .map(|s| s.to_string())
-                                            .expect("Expected to extract the filename from the imported Strategy file");
-
-                                        let mut strategies_lock =
-                                            app_state.available_strategies.lock().await;
-                                        strategies_lock
-                                            .insert(strategy_name.clone(), strategy.clone());
-
-                                        // We need to add the contracts to available_strategies_contract_names so they can be displayed.
-                                        // In order to do so, we need to convert start_contracts into Base58-encoded IDs
-                                        let mut strategy_start_contracts_in_format: StrategyContractNames = Vec::new();
-                                        for (contract, maybe_updates) in strategy.start_contracts {
-                                            let contract_name = contract
-                                                .data_contract()
-                                                .id()
-                                                .to_string(Encoding::Base58);
-                                            if let Some(update_map) = maybe_updates {
-                                                let formatted_update_map = update_map
-                                                    .into_iter()
-                                                    .map(|(block_number, created_contract)| {
-                                                        let contract_name = created_contract
-                                                            .data_contract()
-                                                            .id()
-                                                            .to_string(Encoding::Base58);
-                                                        (block_number, contract_name)
-                                                    })
-                                                    .collect::<BTreeMap<u64, ContractFileName>>();
-
-                                                strategy_start_contracts_in_format.push((
-                                                    contract_name,
-                                                    Some(formatted_update_map),
-                                                ));
-                                            } else {
-                                                strategy_start_contracts_in_format
-                                                    .push((contract_name, None));
-                                            }
-                                        }
-
-                                        let mut contract_names_lock = app_state
-                                            .available_strategies_contract_names
-                                            .lock()
-                                            .await;
-                                        contract_names_lock.insert(
-                                            strategy_name.clone(),
-                                            strategy_start_contracts_in_format,
-                                        );
-
-                                        let mut selected_strategy =
-                                            app_state.selected_strategy.lock().await;
-                                        *selected_strategy = Some(strategy_name.clone());
-
-                                        BackendEvent::AppStateUpdated(
-                                            AppStateUpdate::SelectedStrategy(
-                                                strategy_name.clone(),
-                                                MutexGuard::map(strategies_lock, |strategies| {
-                                                    strategies.get_mut(&strategy_name).expect("Expected to find the strategy in available_strategies")
-                                                }),
-                                                MutexGuard::map(contract_names_lock, |names| {
-                                                    names.get_mut(&strategy_name).expect("Expected to find the strategy in available_strategies_contract_names")
-                                                }),
-                                            ),
-                                        )
-                                    }
-                                    Err(e) => {
-                                        tracing::error!("Failed to deserialize strategy: {}", e);
-                                        BackendEvent::StrategyError {
-                                            error: format!("Failed to deserialize strategy: {}", e),
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to fetch strategy data: {}", e);
-                                BackendEvent::StrategyError {
-                                    error: format!("Failed to fetch strategy data: {}", e),
-                                }
-                            }
-                        }
-                    } else {
-                        tracing::error!("Failed to fetch strategy: HTTP {}", response.status());
-                        BackendEvent::StrategyError {
-                            error: format!("Failed to fetch strategy: HTTP {}", response.status()),
-                        }
-                    }
+        StrategyTask::ImportStrategy {
+            ref source,
+            ref strategy_name,
+            overwrite,
+        } => {
+            let bytes = match read_strategy_source(source).await {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    tracing::error!("{}", error);
+                    return BackendEvent::StrategyError { error };
                 }
+            };
+
+            let strategy = match Strategy::versioned_deserialize(&bytes, true, &sdk.version()) {
+                Ok(strategy) => strategy,
                 Err(e) => {
-                    tracing::error!("Failed to fetch strategy: {}", e);
-                    BackendEvent::StrategyError {
-                        error: format!("Failed to fetch strategy: {}", e),
-                    }
+                    tracing::error!("Failed to deserialize strategy: {}", e);
+                    return BackendEvent::StrategyError {
+                        error: format!("Failed to deserialize strategy: {}", e),
+                    };
+                }
+            };
+
+            let mut strategies_lock = app_state.available_strategies.lock().await;
+            if !overwrite && strategies_lock.contains_key(strategy_name) {
+                return BackendEvent::StrategyError {
+                    error: format!("Strategy {} already exists", strategy_name),
+                };
+            }
+            strategies_lock.insert(strategy_name.clone(), strategy.clone());
+
+            // We need to add the contracts to available_strategies_contract_names so they can be displayed.
+            // In order to do so, we need to convert start_contracts into Base58-encoded IDs
+            let mut strategy_start_contracts_in_format: StrategyContractNames = Vec::new();
+            for (contract, maybe_updates) in strategy.start_contracts {
+                let contract_name = contract.data_contract().id().to_string(Encoding::Base58);
+                if let Some(update_map) = maybe_updates {
+                    let formatted_update_map = update_map
+                        .into_iter()
+                        .map(|(block_number, created_contract)| {
+                            let contract_name = created_contract
+                                .data_contract()
+                                .id()
+                                .to_string(Encoding::Base58);
+                            (block_number, contract_name)
+                        })
+                        .collect::<BTreeMap<u64, ContractFileName>>();
+
+                    strategy_start_contracts_in_format
+                        .push((contract_name, Some(formatted_update_map)));
+                } else {
+                    strategy_start_contracts_in_format.push((contract_name, None));
                 }
             }
+
+            let mut contract_names_lock =
+                app_state.available_strategies_contract_names.lock().await;
+            contract_names_lock.insert(strategy_name.clone(), strategy_start_contracts_in_format);
+
+            let mut selected_strategy = app_state.selected_strategy.lock().await;
+            *selected_strategy = Some(strategy_name.clone());
+            drop(selected_strategy);
+            clear_strategy_settings(app_state, strategy_name).await;
+
+            BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                strategy_name.clone(),
+                MutexGuard::map(strategies_lock, |strategies| {
+                    strategies
+                        .get_mut(strategy_name)
+                        .expect("Expected to find the strategy in available_strategies")
+                }),
+                MutexGuard::map(contract_names_lock, |names| {
+                    names.get_mut(strategy_name).expect(
+                        "Expected to find the strategy in available_strategies_contract_names",
+                    )
+                }),
+            ))
         }
         StrategyTask::ExportStrategy(ref strategy_name) => {
             let strategies_lock = app_state.available_strategies.lock().await;
@@ -255,9 +532,16 @@ pub async fn run_strategy_task<'s>(
 
             match strategy.serialize_to_bytes_with_platform_version(&platform_version) {
                 Ok(binary_data) => {
-                    let file_name = format!("supporting_files/strategy_exports/{}", strategy_name);
+                    let file_name = format!("{}/{}", EXPORTS_DIR, strategy_name);
                     let path = std::path::Path::new(&file_name);
 
+                    if let Err(e) = std::fs::create_dir_all(EXPORTS_DIR) {
+                        tracing::error!("Failed to create {}: {}", EXPORTS_DIR, e);
+                        return BackendEvent::StrategyError {
+                            error: format!("Failed to create {}: {}", EXPORTS_DIR, e),
+                        };
+                    }
+
                     match File::create(&path) {
                         Ok(mut file) => {
                             if let Err(e) = file.write_all(&binary_data) {
@@ -269,7 +553,8 @@ pub async fn run_strategy_task<'s>(
                             BackendEvent::TaskCompleted {
                                 task: Task::Strategy(task),
                                 execution_result: Ok(format!(
-                                    "Exported strategy file to supporting_files/strategy_exports"
+                                    "Exported strategy file to {}",
+                                    EXPORTS_DIR
                                 )
                                 .into()),
                             }
@@ -290,25 +575,175 @@ pub async fn run_strategy_task<'s>(
                 }
             }
         }
-        StrategyTask::SelectStrategy(ref strategy_name) => {
+        StrategyTask::LoadStrategyDefinition(ref path) => {
+            let mut strategies_lock = app_state.available_strategies.lock().await;
+            let known_contracts_lock = app_state.known_contracts.lock().await;
+            let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
+
+            let compiled = StrategyDefinition::load(
+                path,
+                |contract_name| {
+                    known_contracts_lock
+                        .get(contract_name)
+                        .or_else(|| supporting_contracts_lock.get(contract_name))
+                        .cloned()
+                },
+                sdk.version(),
+            );
+            drop(known_contracts_lock);
+            drop(supporting_contracts_lock);
+
+            let compiled = match compiled {
+                Ok(compiled) => compiled,
+                Err(e) => {
+                    tracing::error!("Failed to load strategy definition: {}", e);
+                    return BackendEvent::StrategyError {
+                        error: e.to_string(),
+                    };
+                }
+            };
+
+            let Some(strategy_name) = compiled.name.or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            }) else {
+                return BackendEvent::StrategyError {
+                    error: format!("Unable to name the strategy from {}", path.display()),
+                };
+            };
+            if strategies_lock.contains_key(&strategy_name) {
+                return BackendEvent::StrategyError {
+                    error: format!("Strategy {} already exists", strategy_name),
+                };
+            }
+
+            let mut contract_names_lock =
+                app_state.available_strategies_contract_names.lock().await;
             let mut selected_strategy_lock = app_state.selected_strategy.lock().await;
+
+            strategies_lock.insert(strategy_name.clone(), compiled.strategy);
+            contract_names_lock.insert(strategy_name.clone(), compiled.contract_names);
+            clear_strategy_settings(app_state, &strategy_name).await;
+            if let Some(seed) = compiled.seed {
+                app_state
+                    .available_strategies_seeds
+                    .lock()
+                    .await
+                    .insert(strategy_name.clone(), seed);
+            }
+            *selected_strategy_lock = Some(strategy_name.clone());
+
+            BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                strategy_name.clone(),
+                MutexGuard::map(strategies_lock, |strategies| {
+                    strategies.get_mut(&strategy_name).expect("strategy exists")
+                }),
+                MutexGuard::map(contract_names_lock, |names| {
+                    names.get_mut(&strategy_name).expect("inconsistent data")
+                }),
+            ))
+        }
+        StrategyTask::SaveStrategyDefinition {
+            ref strategy_name,
+            ref path,
+        } => {
+            let strategies_lock = app_state.available_strategies.lock().await;
+            let Some(strategy) = strategies_lock.get(strategy_name) else {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            };
+
+            // Known contracts take precedence over supporting ones with the same name
+            let known_contracts = app_state.known_contracts.lock().await.clone();
+            let mut contracts = app_state.supporting_contracts.lock().await.clone();
+            contracts.extend(known_contracts);
+
+            let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
+
+            let seed = app_state
+                .available_strategies_seeds
+                .lock()
+                .await
+                .get(strategy_name)
+                .copied();
+            let saved = StrategyDefinition::from_strategy(
+                strategy_name,
+                seed,
+                strategy,
+                contract_names_lock
+                    .get(strategy_name)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                &contracts,
+            )
+            .and_then(|definition| definition.save(path));
+
+            match saved {
+                Ok(()) => BackendEvent::TaskCompleted {
+                    execution_result: Ok(format!(
+                        "Saved strategy definition to {}",
+                        path.display()
+                    )
+                    .into()),
+                    task: Task::Strategy(task),
+                },
+                Err(e) => {
+                    tracing::error!("Failed to save strategy definition: {}", e);
+                    BackendEvent::StrategyError {
+                        error: e.to_string(),
+                    }
+                }
+            }
+        }
+        StrategyTask::DryRunStrategy {
+            ref strategy_name,
+            blocks,
+            ref dump_dir,
+            seed,
+        } => {
+            tracing::info!("-----Starting dry run of strategy '{}'-----", strategy_name);
+
+            match dry_run::dry_run_strategy(
+                sdk,
+                app_state,
+                strategy_name,
+                blocks,
+                dump_dir.as_deref(),
+                run_seed(app_state, strategy_name, seed).await,
+                &stop,
+            )
+            .await
+            {
+                Ok(report) => BackendEvent::TaskCompleted {
+                    execution_result: Ok(CompletedTaskPayload::StrategyDryRun(Box::new(report))),
+                    task: Task::Strategy(task),
+                },
+                Err(error) => {
+                    tracing::error!("Strategy dry run failed: {}", error);
+                    BackendEvent::StrategyError { error }
+                }
+            }
+        }
+        StrategyTask::SelectStrategy(ref strategy_name) => {
             let strategies_lock = app_state.available_strategies.lock().await;
 
             if strategies_lock.contains_key(strategy_name) {
-                *selected_strategy_lock = Some(strategy_name.clone());
+                let contract_names_lock =
+                    app_state.available_strategies_contract_names.lock().await;
+                *app_state.selected_strategy.lock().await = Some(strategy_name.clone());
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name.clone(),
                     MutexGuard::map(strategies_lock, |strategies| {
                         strategies.get_mut(strategy_name).expect("strategy exists")
                     }),
-                    MutexGuard::map(
-                        app_state.available_strategies_contract_names.lock().await,
-                        |names| names.get_mut(strategy_name).expect("inconsistent data"),
-                    ),
+                    MutexGuard::map(contract_names_lock, |names| {
+                        names.get_mut(strategy_name).expect("inconsistent data")
+                    }),
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -322,6 +757,7 @@ pub async fn run_strategy_task<'s>(
             if strategies_lock.contains_key(&strategy_name) {
                 strategies_lock.remove(&strategy_name);
                 contract_names_lock.remove(&strategy_name);
+                clear_strategy_settings(app_state, &strategy_name).await;
 
                 // If the deleted strategy was the selected one, unset the selected strategy
                 if let Some(selected) = selected_strategy_lock.as_ref() {
@@ -336,7 +772,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -357,6 +793,20 @@ pub async fn run_strategy_task<'s>(
                     strategies_lock.insert(new_strategy_name.clone(), cloned_strategy);
                     contract_names_lock.insert(new_strategy_name.clone(), cloned_display_data);
 
+                    clear_strategy_settings(app_state, &new_strategy_name).await;
+                    let mut operation_keys_lock =
+                        app_state.available_strategies_operation_keys.lock().await;
+                    if let Some(operation_keys) = operation_keys_lock.get(selected_strategy_name) {
+                        let operation_keys = operation_keys.clone();
+                        operation_keys_lock.insert(new_strategy_name.clone(), operation_keys);
+                    }
+                    drop(operation_keys_lock);
+                    let mut seeds_lock = app_state.available_strategies_seeds.lock().await;
+                    if let Some(seed) = seeds_lock.get(selected_strategy_name).copied() {
+                        seeds_lock.insert(new_strategy_name.clone(), seed);
+                    }
+                    drop(seeds_lock);
+
                     *selected_strategy_lock = Some(new_strategy_name.clone());
 
                     BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
@@ -374,12 +824,12 @@ pub async fn run_strategy_task<'s>(
                     ))
                 } else {
                     BackendEvent::StrategyError {
-                        error: format!("Strategy doesn't exist in app state."),
+                        error: "Strategy doesn't exist in app state.".to_owned(),
                     }
                 }
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("No selected strategy in app state."),
+                    error: "No selected strategy in app state.".to_owned(),
                 }
             }
         }
@@ -392,95 +842,35 @@ pub async fn run_strategy_task<'s>(
                 app_state.available_strategies_contract_names.lock().await;
 
             if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
-                let platform_version = sdk.version();
-
-                // Function to retrieve the contract from either known_contracts or
-                // supporting_contracts
-                let get_contract = |contract_name: &String| {
-                    known_contracts_lock
-                        .get(contract_name)
-                        .or_else(|| supporting_contracts_lock.get(contract_name))
-                        .cloned()
+                let Some(first_contract_name) = selected_contract_names.first() else {
+                    return BackendEvent::StrategyError {
+                        error: "No contract selected".to_owned(),
+                    };
                 };
 
-                // Set a fake identity nonce for now. We will set real identity nonces during strategy execution.
-                let fake_identity_nonce = 1;
-
-                if let Some(first_contract_name) = selected_contract_names.first() {
-                    if let Some(data_contract) = get_contract(first_contract_name) {
-                        match CreatedDataContract::from_contract_and_identity_nonce(
-                            data_contract,
-                            fake_identity_nonce,
-                            platform_version,
-                        ) {
-                            Ok(original_contract) => {
-                                let mut updates = BTreeMap::new();
-
-                                for (order, contract_name) in
-                                    selected_contract_names.iter().enumerate().skip(1)
-                                {
-                                    if let Some(update_contract) = get_contract(contract_name) {
-                                        match CreatedDataContract::from_contract_and_identity_nonce(
-                                            update_contract,
-                                            fake_identity_nonce,
-                                            platform_version,
-                                        ) {
-                                            Ok(created_update_contract) => {
-                                                updates
-                                                    .insert(order as u64, created_update_contract);
-                                            }
-                                            Err(e) => {
-                                                tracing::error!(
-                                                    "Error converting DataContract to \
-                                                     CreatedDataContract for update: {:?}",
-                                                    e
-                                                );
-                                                return BackendEvent::StrategyError {
-                                                    error: format!("Error converting DataContract to CreatedDataContract for update: {:?}", e)
-                                                };
-                                            }
-                                        }
-                                    }
-                                }
-
-                                strategy.start_contracts.push((
-                                    original_contract,
-                                    if updates.is_empty() {
-                                        None
-                                    } else {
-                                        Some(updates)
-                                    },
-                                ));
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    "Error converting DataContract to CreatedDataContract: {:?}",
-                                    e
-                                );
-                                return BackendEvent::StrategyError {
-                                    error: format!("Error converting DataContract to CreatedDataContract: {:?}", e)
-                                };
-                            }
-                        }
+                let slots = update_slots(None, selected_contract_names.len() - 1);
+                match start_contract_from_names(
+                    &selected_contract_names,
+                    &slots,
+                    &known_contracts_lock,
+                    &supporting_contracts_lock,
+                    sdk.version(),
+                ) {
+                    Ok(start_contract) => strategy.start_contracts.push(start_contract),
+                    Err(error) => {
+                        tracing::error!("{}", error);
+                        return BackendEvent::StrategyError { error };
                     }
                 }
 
-                let mut transformed_contract_names = Vec::new();
-                if let Some(first_contract_name) = selected_contract_names.first() {
-                    let updates: BTreeMap<u64, String> = selected_contract_names
-                        .iter()
-                        .enumerate()
-                        .skip(1)
-                        .map(|(order, name)| (order as u64, name.clone()))
-                        .collect();
-                    transformed_contract_names.push((first_contract_name.clone(), Some(updates)));
-                }
-
-                if let Some(existing_contracts) = contract_names_lock.get_mut(&strategy_name) {
-                    existing_contracts.extend(transformed_contract_names);
-                } else {
-                    contract_names_lock.insert(strategy_name.clone(), transformed_contract_names);
-                }
+                let updates: BTreeMap<u64, String> = slots
+                    .into_iter()
+                    .zip(selected_contract_names.iter().skip(1).cloned())
+                    .collect();
+                contract_names_lock
+                    .entry(strategy_name.clone())
+                    .or_default()
+                    .push((first_contract_name.clone(), Some(updates)));
 
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name.clone(),
@@ -493,7 +883,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -508,6 +898,18 @@ pub async fn run_strategy_task<'s>(
             if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
                 let platform_version = sdk.version();
 
+                // IDs of the variants follow the strategy seed
+                let seed = app_state
+                    .available_strategies_seeds
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .copied();
+                let mut rng = strategy_rng(
+                    seed,
+                    START_CONTRACTS_RNG_STREAM + strategy.start_contracts.len() as u64,
+                );
+
                 // Function to retrieve the contract from either known_contracts or
                 // supporting_contracts
                 let get_contract = |contract_name: &String| {
@@ -540,7 +942,7 @@ pub async fn run_strategy_task<'s>(
                                 let mut new_data_contract =
                                     original_contract.data_contract().clone();
                                 let new_id = DataContract::generate_data_contract_id_v0(
-                                    Identifier::random(),
+                                    Identifier::new(rng.gen()),
                                     fake_identity_nonce,
                                 );
                                 new_data_contract.set_id(new_id);
@@ -605,7 +1007,7 @@ pub async fn run_strategy_task<'s>(
                 } else {
                     tracing::error!("Contract wasn't retrieved by name in StrategyTask::SetContractsWithUpdatesRandom");
                     return BackendEvent::StrategyError {
-                        error: format!("Contract wasn't retrieved by name in StrategyTask::SetContractsWithUpdatesRandom")
+                        error: "Contract wasn't retrieved by name in StrategyTask::SetContractsWithUpdatesRandom".to_owned()
                     };
                 }
 
@@ -620,7 +1022,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -643,7 +1045,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -683,7 +1085,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -710,7 +1112,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -748,7 +1150,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -773,7 +1175,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state."),
+                    error: "Strategy doesn't exist in app state.".to_owned(),
                 }
             }
         }
@@ -782,8 +1184,10 @@ pub async fn run_strategy_task<'s>(
             num_blocks_or_seconds,
             verify_proofs,
             block_mode,
+            seed,
         ) => {
             tracing::info!("-----Starting strategy '{}'-----", strategy_name);
+
             let init_start_time = Instant::now(); // Start time of strategy initialization plus execution of first two blocks
             let mut init_time = Duration::new(0, 0); // Will set this to the time it takes for all initialization plus the first two blocks to complete
 
@@ -800,9 +1204,10 @@ pub async fn run_strategy_task<'s>(
                 }
             };
 
-            // Refresh loaded_identity and get the current balance at strategy start
-            let mut loaded_identity_lock = match app_state.refresh_identity(&sdk).await {
-                Ok(lock) => lock,
+            // Refresh loaded_identity and get the current balance at strategy start.
+            // The run works on a copy, so the rest of the app can read the identity
+            let loaded_identity = match app_state.refresh_identity(&sdk).await {
+                Ok(lock) => lock.clone(),
                 Err(e) => {
                     tracing::error!("Failed to refresh loaded identity: {:?}", e);
                     return BackendEvent::StrategyError {
@@ -810,7 +1215,7 @@ pub async fn run_strategy_task<'s>(
                     };
                 }
             };
-            let initial_balance_identity = loaded_identity_lock.balance();
+            let initial_balance_identity = loaded_identity.balance();
 
             // Refresh UTXOs for the loaded wallet and get initial wallet balance
             let mut loaded_wallet_lock = app_state.loaded_wallet.lock().await;
@@ -820,86 +1225,59 @@ pub async fn run_strategy_task<'s>(
             let initial_balance_wallet = loaded_wallet_lock.clone().unwrap().balance();
             drop(loaded_wallet_lock);
 
-            // Get a mutable strategy because we need to modify some properties of contracts on updates
-            let mut strategies_lock = app_state.available_strategies.lock().await;
-            if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
-                // Get block_info
-                // Get block info for the first block by sending a grpc request and looking at
-                // the metadata Retry up to MAX_RETRIES times
-                const MAX_RETRIES: u8 = 2;
-                let mut initial_block_info = BlockInfo::default();
-                let mut retries = 0;
-                let request = GetEpochsInfoRequest {
-                    version: Some(get_epochs_info_request::Version::V0(
-                        get_epochs_info_request::GetEpochsInfoRequestV0 {
-                            start_epoch: None,
-                            count: 1,
-                            ascending: false,
-                            prove: false,
-                        },
-                    )),
+            // Run a copy of the strategy, as contracts get modified on updates, and write it
+            // back once the run is over so the strategies aren't locked meanwhile
+            let strategy = app_state
+                .available_strategies
+                .lock()
+                .await
+                .get(&strategy_name)
+                .cloned();
+            if let Some(mut strategy) = strategy {
+                // Get block info for the first block
+                let mut initial_block_info = match current_block_info(sdk).await {
+                    Ok(block_info) => block_info,
+                    Err(error) => return BackendEvent::StrategyError { error },
                 };
-                // Use retry mechanism to fetch current block info
-                while retries <= MAX_RETRIES {
-                    match sdk
-                        .execute(request.clone(), RequestSettings::default())
-                        .await
-                    {
-                        Ok(response) => {
-                            if let Some(get_epochs_info_response::Version::V0(response_v0)) =
-                                response.version
-                            {
-                                if let Some(metadata) = response_v0.metadata {
-                                    initial_block_info = BlockInfo {
-                                        time_ms: metadata.time_ms,
-                                        height: metadata.height,
-                                        core_height: metadata.core_chain_locked_height,
-                                        epoch: Epoch::new(metadata.epoch as u16).unwrap(),
-                                    };
-                                }
-                            }
-                            break;
-                        }
-                        Err(e) if retries < MAX_RETRIES => {
-                            tracing::error!("Error executing request, retrying: {:?}", e);
-                            retries += 1;
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to execute request after retries: {:?}", e);
-                            return BackendEvent::StrategyError {
-                                error: format!("Failed to execute request after retries: {:?}", e),
-                            };
-                        }
-                    }
-                }
                 initial_block_info.height += 1; // Add one because we'll be submitting to the next block
 
-                // Get signer from loaded_identity
-                // Convert loaded_identity to SimpleSigner
+                // Get signer from the keys of loaded_identity assigned to the operations
                 let identity_private_keys_lock = app_state.identity_private_keys.lock().await;
-                let mut signer = {
-                    let strategy_signer = strategy.signer.insert({
-                        let mut new_signer = SimpleSigner::default();
-                        let Identity::V0(identity_v0) = &*loaded_identity_lock;
-                        for (key_id, public_key) in &identity_v0.public_keys {
-                            let identity_key_tuple = (identity_v0.id, *key_id);
-                            if let Some(private_key_bytes) =
-                                identity_private_keys_lock.get(&identity_key_tuple)
-                            {
-                                new_signer
-                                    .private_keys
-                                    .insert(public_key.clone(), private_key_bytes.clone());
-                            }
-                        }
-                        new_signer
-                    });
-                    strategy_signer.clone()
+                let operation_keys = app_state
+                    .available_strategies_operation_keys
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let (signer, identity_view) = match keys::preflight(
+                    &loaded_identity,
+                    &identity_private_keys_lock,
+                    &strategy.operations,
+                    &operation_keys,
+                ) {
+                    Ok(preflight) => preflight,
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        return BackendEvent::StrategyError {
+                            error: e.to_string(),
+                        };
+                    }
                 };
+                let mut signer = strategy.signer.insert(signer).clone();
                 drop(identity_private_keys_lock);
+                if let Some(stored_strategy) = app_state
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get_mut(&strategy_name)
+                {
+                    stored_strategy.signer = Some(signer.clone());
+                }
 
-                // Set initial current_identities to loaded_identity
+                // Set initial current_identities to loaded_identity, as seen by the operations
                 // During strategy execution, newly created identities will be added to current_identities
-                let mut loaded_identity_clone = loaded_identity_lock.clone();
+                let mut loaded_identity_clone = identity_view;
                 let mut current_identities: Vec<Identity> = vec![loaded_identity_clone.clone()];
 
                 // Set the nonce counters
@@ -960,113 +1338,24 @@ pub async fn run_strategy_task<'s>(
                     );
                 }
 
-                // Get a lock on the local drive for the following two callbacks
-                let drive_lock = app_state.drive.lock().await;
-
-                // Callback used to fetch documents from the local Drive instance
-                // Used for DocumentReplace and DocumentDelete transitions
-                let mut document_query_callback = |query: LocalDocumentQuery| {
-                    match query {
-                        LocalDocumentQuery::RandomDocumentQuery(random_query) => {
-                            let document_type = random_query.document_type;
-                            let data_contract = random_query.data_contract;
-
-                            // Construct a DriveQuery based on the document_type and
-                            // data_contract
-                            let drive_query =
-                                DriveQuery::any_item_query(data_contract, document_type.as_ref());
-
-                            // Query the Drive for documents
-                            match drive_lock.query_documents(drive_query, None, false, None, None) {
-                                Ok(outcome) => match outcome {
-                                    QueryDocumentsOutcome::V0(outcome_v0) => {
-                                        let documents = outcome_v0.documents_owned();
-                                        tracing::info!(
-                                            "Fetched {} documents using DriveQuery",
-                                            documents.len()
-                                        );
-                                        documents
-                                    }
-                                },
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Error fetching documents using DriveQuery: {:?}",
-                                        e
-                                    );
-                                    vec![]
-                                }
-                            }
-                        }
-                    }
-                };
+                // Confirmed documents, contracts and identities are written to the same Drive
+                let mut local_drive = LocalDriveSync::default();
 
-                // Callback used to fetch identities from the local Drive instance
-                let mut identity_fetch_callback =
-                    |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
-                        // Convert Identifier to a byte array format expected by the Drive
-                        // method
-                        let identity_id_bytes = identifier.into_buffer();
-
-                        // Fetch identity information from the Drive
-                        match drive_lock.fetch_identity_with_balance(
-                            identity_id_bytes,
-                            None,
-                            sdk.version(),
-                        ) {
-                            Ok(maybe_partial_identity) => {
-                                let partial_identity =
-                                    maybe_partial_identity.unwrap_or_else(|| PartialIdentity {
-                                        id: identifier,
-                                        loaded_public_keys: BTreeMap::new(),
-                                        balance: None,
-                                        revision: None,
-                                        not_found_public_keys: BTreeSet::new(),
-                                    });
-                                tracing::info!(
-                                    "Fetched identity info for identifier {}: {:?}",
-                                    identifier,
-                                    partial_identity
-                                );
-                                partial_identity
-                            }
-                            Err(e) => {
-                                tracing::error!("Error fetching identity: {:?}", e);
-                                PartialIdentity {
-                                    id: identifier,
-                                    loaded_public_keys: BTreeMap::new(),
-                                    balance: None,
-                                    revision: None,
-                                    not_found_public_keys: BTreeSet::new(),
-                                }
-                            }
-                        }
-                    };
+                // Everything random in the run follows the seed
+                let seed = run_seed(app_state, &strategy_name, seed).await;
+                tracing::info!("Strategy '{}' runs with seed {}", strategy_name, seed);
+                let mut asset_lock_rng = strategy_rng(Some(seed), ASSET_LOCKS_RNG_STREAM);
 
                 // Create asset lock proofs for all the identity creates and top ups
-                let num_identity_inserts = (strategy
-                    .identity_inserts
-                    .frequency
-                    .times_per_block_range
-                    .start) as u64;
-                let num_start_identities = strategy.start_identities.number_of_identities as u64;
-                let mut num_top_ups: u64 = 0;
-                for operation in &strategy.operations {
-                    if operation.op_type == OperationType::IdentityTopUp {
-                        num_top_ups += (operation.frequency.times_per_block_range.start) as u64;
-                    }
-                }
-                let num_asset_lock_proofs_needed = num_identity_inserts * num_blocks_or_seconds
-                    + num_start_identities
-                    + num_top_ups;
+                let num_asset_lock_proofs_needed =
+                    asset_lock_proofs_needed(&strategy, num_blocks_or_seconds);
                 let mut asset_lock_proofs: Vec<(AssetLockProof, PrivateKey)> = Vec::new();
                 if num_asset_lock_proofs_needed > 0 {
                     let mut wallet_lock = app_state.loaded_wallet.lock().await;
-                    let num_available_utxos = match wallet_lock
-                        .clone()
+                    let num_available_utxos = wallet_lock
+                        .as_ref()
                         .expect("No wallet loaded while getting asset lock proofs")
-                    {
-                        Wallet::SingleKeyWallet(SingleKeyWallet { utxos, .. }) => utxos.len(),
-                    };
+                        .utxos_count();
                     if num_available_utxos
                         < num_asset_lock_proofs_needed
                             .try_into()
@@ -1086,7 +1375,7 @@ pub async fn run_strategy_task<'s>(
                         if let Some(wallet) = wallet_lock.as_mut() {
                             // TO-DO: separate this into a function for start_identities, top ups, and inserts, because the balances should all be different
                             match wallet.asset_lock_transaction(
-                                None,
+                                Some(asset_lock_rng.gen()),
                                 strategy.start_identities.starting_balances,
                             ) {
                                 Ok((asset_lock_transaction, asset_lock_proof_private_key)) => {
@@ -1141,31 +1430,86 @@ pub async fn run_strategy_task<'s>(
                 }
 
                 // Some final initialization
-                let mut rng = StdRng::from_entropy(); // Will be passed to state_transitions_for_block
+                let mut rng = StdRng::seed_from_u64(seed); // Will be passed to state_transitions_for_block, as in dry runs
+                let mut report = StrategyRunReport {
+                    strategy_name: strategy_name.clone(),
+                    started_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .expect("time went backwards")
+                        .as_secs(),
+                    run: RunSettings {
+                        block_mode,
+                        blocks_or_seconds: num_blocks_or_seconds,
+                        verify_proofs,
+                        seed,
+                    },
+                    strategy: StrategySummary::from(&strategy),
+                    start_block_height: initial_block_info.height,
+                    end_block_height: initial_block_info.height,
+                    completed: false,
+                    stop_reason: None,
+                    blocks: Vec::new(),
+                    transitions_by_type: BTreeMap::new(),
+                    created_identities: Vec::new(),
+                    identity_registrations: IdentityRegistrations::default(),
+                    created_contracts: Vec::new(),
+                    identity_credits_spent: 0,
+                    wallet_duffs_spent: 0,
+                }; // Written to disk once the run is over
                 let mut current_block_info = initial_block_info.clone(); // Used for transition creation and logging
                 let mut transition_count = 0; // Used for logging how many transitions we attempted
                 let mut success_count = 0; // Used for logging how many transitions were successful
                 let mut load_start_time = Instant::now(); // Time when the load test begins (all blocks after the second block)
                 let mut index = 1; // Index of the loop iteration. Represents blocks for block mode and seconds for time mode
-                let mut new_identity_ids = Vec::new(); // Will capture the ids of identities added to current_identities
+                let mut new_identity_ids = Vec::new(); // Will capture the ids of identities the run attempted to create
+                let mut pending_identities = PendingIdentities::default(); // Created identities waiting for their registration to be confirmed
                 let mut new_contract_ids = Vec::new(); // Will capture the ids of newly created data contracts
                 let oks = Arc::new(AtomicUsize::new(0)); // Atomic counter for successful broadcasts
                 let errs = Arc::new(AtomicUsize::new(0)); // Atomic counter for failed broadcasts
                 let mempool_document_counter = BTreeMap::<(Identifier, Identifier), u64>::new(); // Map to track how many documents an identity has in the mempool per contract
+                let mut credits_spent = None; // Credits spent by the loaded identity as of the last balance fetch
+                let mut stopped = false; // Set if the run was stopped on request before completing
 
                 // Now loop through the number of blocks or seconds the user asked for, preparing and processing state transitions
                 while (block_mode && current_block_info.height < (initial_block_info.height + num_blocks_or_seconds + 2)) // +2 because we don't count the first two initialization blocks
                     || (!block_mode && load_start_time.elapsed().as_secs() < num_blocks_or_seconds) || index <= 2
                 {
+                    if stop.is_cancelled() {
+                        tracing::info!(
+                            "Stop requested, strategy '{}' stops at {} {}",
+                            strategy_name,
+                            mode_string,
+                            index
+                        );
+                        stopped = true;
+                        break;
+                    }
+
                     let oks_clone = oks.clone();
                     let errs_clone = errs.clone();
                     let loop_start_time = Instant::now();
+                    let mut block_record = BlockRecord::new(index, current_block_info.height);
 
                     // Need to pass app_state.known_contracts to state_transitions_for_block
                     let mut known_contracts_lock = app_state.known_contracts.lock().await;
 
+                    // Drive is only locked while a block is generated, so the rest of the
+                    // app can use it in between
+                    let drive_lock = app_state.drive.lock().await;
+
+                    // Callback used to fetch documents from the local Drive instance
+                    // Used for DocumentReplace and DocumentDelete transitions
+                    let mut document_query_callback =
+                        |query: LocalDocumentQuery| local_documents(&drive_lock, query);
+
+                    // Callback used to fetch identities from the local Drive instance
+                    let mut identity_fetch_callback =
+                        |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
+                            local_identity(&drive_lock, identifier, sdk.version())
+                        };
+
                     // Get the state transitions for the block (or second)
-                    let (transitions, finalize_operations, mut new_identities) = strategy
+                    let (transitions, finalize_operations, new_identities) = strategy
                         .state_transitions_for_block(
                             &mut document_query_callback,
                             &mut identity_fetch_callback,
@@ -1186,14 +1530,15 @@ pub async fn run_strategy_task<'s>(
                         )
                         .await;
 
+                    drop(drive_lock);
                     drop(known_contracts_lock);
 
-                    // Add the identities that will be created to current_identities.
-                    // TO-DO: This should be moved to execution after we confirm they were registered.
+                    // The identities that will be created are added to current_identities
+                    // once their registration is confirmed
                     for identity in &new_identities {
                         new_identity_ids.push(identity.id().to_string(Encoding::Base58))
                     }
-                    current_identities.append(&mut new_identities);
+                    pending_identities.add(new_identities);
 
                     for transition in &transitions {
                         match transition {
@@ -1211,9 +1556,6 @@ pub async fn run_strategy_task<'s>(
                         };
                     }
 
-                    // TO-DO: for DocumentDelete and DocumentReplace strategy operations, we need to
-                    // add documents from state transitions to the local Drive instance here
-
                     // Process each FinalizeBlockOperation, which so far is just adding keys to identities
                     for operation in finalize_operations {
                         match operation {
@@ -1230,15 +1572,34 @@ pub async fn run_strategy_task<'s>(
                         }
                     }
 
-                    // Update the loaded_identity_clone and loaded_identity_lock with the latest state of the identity
+                    // Update the loaded_identity_clone and app_state.loaded_identity with the latest state of the identity
                     if let Some(modified_identity) = current_identities
                         .iter()
                         .find(|identity| identity.id() == loaded_identity_clone.id())
                     {
                         loaded_identity_clone = modified_identity.clone();
-                        *loaded_identity_lock = modified_identity.clone();
+
+                        let mut loaded_identity_lock = app_state.loaded_identity.lock().await;
+                        if let Some(stored_identity) = loaded_identity_lock
+                            .as_mut()
+                            .filter(|identity| identity.id() == loaded_identity_clone.id())
+                        {
+                            // Keys not assigned to operations are kept as they were
+                            let mut updated_identity = modified_identity.clone();
+                            let Identity::V0(updated_identity_v0) = &mut updated_identity;
+                            for (key_id, key) in stored_identity.public_keys() {
+                                updated_identity_v0
+                                    .public_keys
+                                    .entry(*key_id)
+                                    .or_insert_with(|| key.clone());
+                            }
+                            *stored_identity = updated_identity;
+                        }
                     }
 
+                    // Proof results of the confirmed transitions to apply to the local Drive
+                    let mut confirmed_results = Vec::new();
+
                     // Now process the state transitions
                     if !transitions.is_empty() {
                         tracing::info!(
@@ -1254,6 +1615,7 @@ pub async fn run_strategy_task<'s>(
 
                         // We will concurrently broadcast the state transitions, so collect the futures
                         let mut broadcast_futures = Vec::new();
+                        let mut broadcast_types = Vec::new(); // Types of the broadcasted transitions for the report and identities they create, in order
 
                         for transition in st_queue.iter() {
                             transition_count += 1; // Used for logging how many transitions we attempted
@@ -1293,32 +1655,41 @@ pub async fn run_strategy_task<'s>(
                                                     Ok(wait_response) => {
                                                         if let Some(wait_for_state_transition_result_response::Version::V0(v0_response)) = &wait_response.version {
                                                             if let Some(metadata) = &v0_response.metadata {
-                                                                success_count += 1;
                                                                 if !verify_proofs {
                                                                     tracing::info!("Successfully processed state transition {} ({}) for {} {} (Actual block height: {})", st_queue_index, transition_type, mode_string, index, metadata.height);
                                                                 }
+                                                                if let Some(wait_for_state_transition_result_response_v0::Result::Error(error)) = &v0_response.result {
+                                                                    block_record.record(&transition_type, Err(format!("{:?}", error)));
+                                                                } else {
+                                                                    success_count += 1;
+                                                                    block_record.record(&transition_type, Ok(TransitionOutcome::Confirmed));
+                                                                }
                                                                 match &v0_response.result {
                                                                     Some(wait_for_state_transition_result_response_v0::Result::Error(error)) => {
                                                                         tracing::error!("WaitForStateTransitionResultResponse error: {:?}", error);
                                                                     }
                                                                     Some(wait_for_state_transition_result_response_v0::Result::Proof(proof)) => {
-                                                                        if verify_proofs {
+                                                                        if verify_proofs || local_drive::is_mirrored(&transition_clone) {
                                                                             let epoch = Epoch::new(metadata.epoch as u16).expect("Expected to get epoch from metadata in proof verification");
+                                                                            let block_info = BlockInfo {
+                                                                                time_ms: metadata.time_ms,
+                                                                                height: metadata.height,
+                                                                                core_height: metadata.core_chain_locked_height,
+                                                                                epoch,
+                                                                            };
                                                                             let verified = Drive::verify_state_transition_was_executed_with_proof(
                                                                                 &transition_clone,
-                                                                                &BlockInfo {
-                                                                                    time_ms: metadata.time_ms,
-                                                                                    height: metadata.height,
-                                                                                    core_height: metadata.core_chain_locked_height,
-                                                                                    epoch,
-                                                                                },
+                                                                                &block_info,
                                                                                 proof.grovedb_proof.as_slice(),
                                                                                 &|_| Ok(None),
                                                                                 sdk.version(),
                                                                             );
                                                                             match verified {
-                                                                                Ok(_) => {
-                                                                                    tracing::info!("Successfully processed and verified proof for state transition {} ({}), {} {} (Actual block height: {})", st_queue_index, transition_type, mode_string, index, metadata.height);
+                                                                                Ok((_, proof_result)) => {
+                                                                                    if verify_proofs {
+                                                                                        tracing::info!("Successfully processed and verified proof for state transition {} ({}), {} {} (Actual block height: {})", st_queue_index, transition_type, mode_string, index, metadata.height);
+                                                                                    }
+                                                                                    confirmed_results.push((transition_clone.clone(), block_info, proof_result));
                                                                                 }
                                                                                 Err(e) => {
                                                                                     tracing::error!("Error verifying state transition execution proof: {}", e);
@@ -1332,31 +1703,52 @@ pub async fn run_strategy_task<'s>(
                                                                 // Sleep because we need to give the chain state time to update revisions
                                                                 // It seems this is only necessary for certain STs. Like AddKeys and DisableKeys seem to need it, but Transfer does not. Not sure about Withdraw or ContractUpdate yet.
                                                                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                                                            } else {
+                                                                block_record.record(&transition_type, Err("no metadata in the state transition result".to_owned()));
                                                             }
                                                         } else {
                                                             tracing::info!("Response version other than V0 received or absent for state transition {} ({})", st_queue_index, transition_type);
+                                                            block_record.record(&transition_type, Err("unsupported state transition result version".to_owned()));
                                                         }
                                                     }
-                                                    Err(e) => tracing::error!(
-                                                        "Error waiting for state transition result: {:?}",
-                                                        e
-                                                    ),
+                                                    Err(e) => {
+                                                        tracing::error!(
+                                                            "Error waiting for state transition result: {:?}",
+                                                            e
+                                                        );
+                                                        block_record.record(
+                                                            &transition_type,
+                                                            Err(format!("{:?}", e)),
+                                                        );
+                                                    }
                                                 }
                                             } else {
                                                 tracing::error!(
                                                     "Failed to create wait request for state transition."
                                                 );
+                                                block_record.record(
+                                                    &transition_type,
+                                                    Err("failed to create wait request".to_owned()),
+                                                );
                                             }
                                         }
-                                        Err(e) => tracing::error!(
-                                            "Error broadcasting dependent state transition: {:?}",
-                                            e
-                                        ),
+                                        Err(e) => {
+                                            tracing::error!(
+                                                "Error broadcasting dependent state transition: {:?}",
+                                                e
+                                            );
+                                            block_record
+                                                .record(&transition_type, Err(format!("{:?}", e)));
+                                        }
                                     }
                                 } else {
                                     tracing::error!(
                                         "Failed to create broadcast request for state transition."
                                     );
+                                    block_record.record(
+                                        &transition_type,
+                                        Err("failed to create broadcast request".to_owned()),
+                                    );
                                 }
                             } else {
                                 let oks = oks_clone.clone();
@@ -1406,30 +1798,60 @@ pub async fn run_strategy_task<'s>(
                                     }
                                 };
                                 broadcast_futures.push(future);
+                                broadcast_types
+                                    .push((transition_type, created_identity_id(transition)));
                             }
                         }
 
                         // Concurrently execute all broadcast requests for independent transitions
-                        let broadcast_results = join_all(broadcast_futures).await;
+                        let Some(broadcast_results) =
+                            await_batch(join_all(broadcast_futures), &stop).await
+                        else {
+                            tracing::warn!(
+                                "Broadcasts of {} {} didn't finish within {:?} after the stop request",
+                                mode_string,
+                                index,
+                                STOP_TIMEOUT
+                            );
+                            for (transition_type, _) in broadcast_types {
+                                block_record
+                                    .record(transition_type, Err(STOP_TIMEOUT_ERROR.to_owned()));
+                            }
+                            report.blocks.push(block_record);
+                            stopped = true;
+                            break;
+                        };
 
                         // If we're in block mode, or index 1 or 2 of time mode, we're going to wait for state transition results and potentially verify proofs too.
                         // If we're in time mode and index 3+, we're just broadcasting.
                         if block_mode || index == 1 || index == 2 {
                             let mut wait_futures = Vec::new();
-                            for (index, result) in broadcast_results.into_iter().enumerate() {
+                            let mut wait_types = Vec::new(); // Types of the awaited transitions for the report and identities they create, in order
+                            for (index, (result, (report_type, created_identity))) in
+                                broadcast_results
+                                    .into_iter()
+                                    .zip(broadcast_types)
+                                    .enumerate()
+                            {
                                 match result {
                                     Ok((transition, broadcast_result)) => {
                                         let transition_type = transition.name().to_owned();
 
                                         if broadcast_result.is_err() {
+                                            let error = broadcast_result.err().unwrap();
                                             tracing::error!(
                                                 "Error broadcasting state transition {} ({}) for {} {}: {:?}",
                                                 index + 1,
                                                 transition_type,
                                                 mode_string,
                                                 index,
-                                                broadcast_result.err().unwrap()
+                                                error
                                             );
+                                            block_record
+                                                .record(report_type, Err(format!("{:?}", error)));
+                                            if let Some(id) = created_identity {
+                                                pending_identities.settle(id, false);
+                                            }
                                             continue;
                                         }
 
@@ -1437,25 +1859,11 @@ pub async fn run_strategy_task<'s>(
                                         let data_contract_id_option = match &transition {
                                             StateTransition::DocumentsBatch(
                                                 DocumentsBatchTransition::V0(documents_batch),
-                                            ) => {
-                                                documents_batch.transitions.get(0).and_then(
-                                                    |document_transition| {
-                                                        match document_transition {
-                                                            DocumentTransition::Create(
-                                                                DocumentCreateTransition::V0(
-                                                                    create_transition,
-                                                                ),
-                                                            ) => Some(
-                                                                create_transition
-                                                                    .base
-                                                                    .data_contract_id(),
-                                                            ),
-                                                            // Add handling for Replace and Delete transitions if necessary
-                                                            _ => None,
-                                                        }
-                                                    },
-                                                )
-                                            }
+                                            ) => documents_batch.transitions.first().map(
+                                                |document_transition| {
+                                                    document_transition.base().data_contract_id()
+                                                },
+                                            ),
                                             // Handle other state transition types that involve data contracts here
                                             _ => None,
                                         };
@@ -1466,7 +1874,14 @@ pub async fn run_strategy_task<'s>(
                                         {
                                             let data_contract_id_str =
                                                 data_contract_id.to_string(Encoding::Base58);
-                                            known_contracts_lock.get(&data_contract_id_str).cloned()
+                                            known_contracts_lock
+                                                .get(&data_contract_id_str)
+                                                .or_else(|| {
+                                                    known_contracts_lock.values().find(|contract| {
+                                                        contract.id() == data_contract_id
+                                                    })
+                                                })
+                                                .cloned()
                                         } else {
                                             None
                                         };
@@ -1492,14 +1907,26 @@ pub async fn run_strategy_task<'s>(
                                                         "Error creating wait request for state transition {} {} {}: {:?}",
                                                         index + 1, mode_string, index, e
                                                     );
-                                                    return None;
+                                                    return Err(format!(
+                                                        "failed to create wait request: {:?}",
+                                                        e
+                                                    ));
                                                 }
                                             };
 
                                             match wait_result {
                                                 Ok(wait_response) => {
-                                                    Some(if let Some(wait_for_state_transition_result_response::Version::V0(v0_response)) = &wait_response.version {
+                                                    if let Some(wait_for_state_transition_result_response::Version::V0(v0_response)) = &wait_response.version {
                                                         if let Some(metadata) = &v0_response.metadata {
+                                                            // Proof result to apply to the local Drive
+                                                            let mut mirrored = None;
+
+                                                            // Processed, but rejected by Platform
+                                                            let execution_error = match &v0_response.result {
+                                                                Some(wait_for_state_transition_result_response_v0::Result::Error(error)) => Some(format!("{:?}", error)),
+                                                                _ => None,
+                                                            };
+
                                                             if !verify_proofs {
                                                                 tracing::info!(
                                                                     "Successfully broadcasted and processed state transition {} ({}) for {} {} (Actual block height: {})",
@@ -1509,20 +1936,21 @@ pub async fn run_strategy_task<'s>(
 
                                                             // Verification of the proof
                                                             if let Some(wait_for_state_transition_result_response_v0::Result::Proof(proof)) = &v0_response.result {
-                                                                if verify_proofs {
+                                                                if verify_proofs || local_drive::is_mirrored(&transition) {
                                                                     let epoch = Epoch::new(metadata.epoch as u16).expect("Expected to get epoch from metadata in proof verification");
+                                                                    let block_info = BlockInfo {
+                                                                        time_ms: metadata.time_ms,
+                                                                        height: metadata.height,
+                                                                        core_height: metadata.core_chain_locked_height,
+                                                                        epoch,
+                                                                    };
                                                                     // For proof verification, if it's a DocumentsBatch, include the data contract, else don't
                                                                     let verified = if transition.name() == "DocumentsBatch" {
                                                                         match data_contract_clone.as_ref() {
                                                                             Some(data_contract) => {
                                                                                 Drive::verify_state_transition_was_executed_with_proof(
                                                                                     &transition,
-                                                                                    &BlockInfo {
-                                                                                        time_ms: metadata.time_ms,
-                                                                                        height: metadata.height,
-                                                                                        core_height: metadata.core_chain_locked_height,
-                                                                                        epoch,
-                                                                                    },
+                                                                                    &block_info,
                                                                                     proof.grovedb_proof.as_slice(),
                                                                                     &|_| Ok(Some(data_contract.clone().into())),
                                                                                     sdk.version(),
@@ -1533,12 +1961,7 @@ pub async fn run_strategy_task<'s>(
                                                                     } else {
                                                                         Drive::verify_state_transition_was_executed_with_proof(
                                                                             &transition,
-                                                                            &BlockInfo {
-                                                                                time_ms: metadata.time_ms,
-                                                                                height: metadata.height,
-                                                                                core_height: metadata.core_chain_locked_height,
-                                                                                epoch,
-                                                                            },
+                                                                            &block_info,
                                                                             proof.grovedb_proof.as_slice(),
                                                                             &|_| Ok(None),
                                                                             sdk.version(),
@@ -1546,8 +1969,11 @@ pub async fn run_strategy_task<'s>(
                                                                     };
 
                                                                     match verified {
-                                                                        Ok(_) => {
-                                                                            tracing::info!("Successfully processed and verified proof for state transition {} ({}), {} {} (Actual block height: {})", index + 1, transition_type, mode_string, index, metadata.height);
+                                                                        Ok((_, proof_result)) => {
+                                                                            if verify_proofs {
+                                                                                tracing::info!("Successfully processed and verified proof for state transition {} ({}), {} {} (Actual block height: {})", index + 1, transition_type, mode_string, index, metadata.height);
+                                                                            }
+                                                                            mirrored = Some((transition.clone(), block_info, proof_result));
                                                                         }
                                                                         Err(e) => tracing::error!("Error verifying state transition execution proof: {}", e),
                                                                     }
@@ -1574,16 +2000,23 @@ pub async fn run_strategy_task<'s>(
                                                                     // nothing
                                                                 }
                                                             }
+
+                                                            Ok((execution_error, mirrored))
+                                                        } else {
+                                                            Err("no metadata in the state transition result".to_owned())
                                                         }
-                                                    })
+                                                    } else {
+                                                        Err("unsupported state transition result version".to_owned())
+                                                    }
                                                 }
                                                 Err(e) => {
                                                     tracing::error!("Wait result error: {:?}", e);
-                                                    None
+                                                    Err(format!("{:?}", e))
                                                 }
                                             }
                                         };
                                         wait_futures.push(wait_future);
+                                        wait_types.push((report_type, created_identity));
                                     }
                                     Err(e) => {
                                         tracing::error!(
@@ -1593,25 +2026,89 @@ pub async fn run_strategy_task<'s>(
                                             current_block_info.height,
                                             e
                                         );
+                                        block_record.record(report_type, Err(format!("{:?}", e)));
+                                        if let Some(id) = created_identity {
+                                            pending_identities.settle(id, false);
+                                        }
                                     }
                                 }
                             }
 
                             // Wait for all state transition result futures to complete
-                            let wait_results = join_all(wait_futures).await;
+                            let Some(wait_results) =
+                                await_batch(join_all(wait_futures), &stop).await
+                            else {
+                                tracing::warn!(
+                                    "State transition results of {} {} didn't arrive within {:?} after the stop request",
+                                    mode_string,
+                                    index,
+                                    STOP_TIMEOUT
+                                );
+                                for (transition_type, _) in wait_types {
+                                    block_record.record(
+                                        transition_type,
+                                        Err(STOP_TIMEOUT_ERROR.to_owned()),
+                                    );
+                                }
+                                report.blocks.push(block_record);
+                                stopped = true;
+                                break;
+                            };
 
-                            // Log the actual block height for each state transition
-                            for (_, actual_block_height) in wait_results.into_iter().enumerate() {
-                                match actual_block_height {
-                                    Some(_) => {
-                                        success_count += 1;
+                            // Count and report the processed state transitions
+                            for ((transition_type, created_identity), wait_result) in
+                                wait_types.into_iter().zip(wait_results)
+                            {
+                                match wait_result {
+                                    Ok((execution_error, mirrored)) => {
+                                        confirmed_results.extend(mirrored);
+                                        if let Some(identity) = created_identity.and_then(|id| {
+                                            pending_identities.settle(id, execution_error.is_none())
+                                        }) {
+                                            current_identities.push(identity);
+                                        }
+                                        match execution_error {
+                                            Some(error) => {
+                                                block_record.record(transition_type, Err(error))
+                                            }
+                                            None => {
+                                                success_count += 1;
+                                                block_record.record(
+                                                    transition_type,
+                                                    Ok(TransitionOutcome::Confirmed),
+                                                );
+                                            }
+                                        }
                                     }
-                                    None => continue,
+                                    // The registration may still have succeeded, so a created identity stays pending
+                                    Err(error) => block_record.record(transition_type, Err(error)),
                                 }
                             }
                         } else {
                             // Time mode.
-                            // Don't wait.
+                            // Don't wait, registrations of created identities are awaited in the background.
+                            for (result, (transition_type, created_identity)) in
+                                broadcast_results.into_iter().zip(broadcast_types)
+                            {
+                                match result {
+                                    Ok((transition, _)) => {
+                                        block_record.record(
+                                            transition_type,
+                                            Ok(TransitionOutcome::Broadcasted),
+                                        );
+                                        if let Some(id) = created_identity {
+                                            pending_identities.watch(sdk, id, transition);
+                                        }
+                                    }
+                                    Err(e) => {
+                                        block_record
+                                            .record(transition_type, Err(format!("{:?}", e)));
+                                        if let Some(id) = created_identity {
+                                            pending_identities.settle(id, false);
+                                        }
+                                    }
+                                }
+                            }
                         }
 
                         // Reset the load_start_time
@@ -1627,10 +2124,94 @@ pub async fn run_strategy_task<'s>(
                         );
                     }
 
+                    // Identities whose registration was confirmed meanwhile in time mode
+                    current_identities.extend(pending_identities.settle_watched());
+
+                    if !confirmed_results.is_empty() {
+                        let known_contracts_lock = app_state.known_contracts.lock().await;
+                        let drive_lock = app_state.drive.lock().await;
+                        for (transition, block_info, proof_result) in confirmed_results {
+                            if let Err(e) = local_drive.apply(
+                                &drive_lock,
+                                &transition,
+                                proof_result,
+                                &known_contracts_lock,
+                                &block_info,
+                                sdk.version(),
+                            ) {
+                                tracing::error!(
+                                    "Unable to apply {} to the local Drive: {}",
+                                    transition.name(),
+                                    e
+                                );
+                            }
+                        }
+                    }
+
                     if index == 2 {
                         init_time = init_start_time.elapsed();
                     }
 
+                    report.blocks.push(block_record);
+
+                    // Publish the progress, the balance is only fetched when somebody listens
+                    if progress.receiver_count() > 0 {
+                        let last_block = block_mode
+                            && current_block_info.height + 1
+                                >= initial_block_info.height + num_blocks_or_seconds + 2;
+                        if last_block || index % PROGRESS_BALANCE_INTERVAL == 0 {
+                            match u64::fetch(
+                                sdk,
+                                GetIdentityBalanceRequest {
+                                    version: Some(get_identity_balance_request::Version::V0(
+                                        GetIdentityBalanceRequestV0 {
+                                            id: loaded_identity_clone.id().to_vec(),
+                                            prove: true,
+                                        },
+                                    )),
+                                },
+                            )
+                            .await
+                            {
+                                Ok(Some(balance)) => {
+                                    credits_spent =
+                                        Some(initial_balance_identity.saturating_sub(balance))
+                                }
+                                Ok(None) => {}
+                                Err(e) => tracing::warn!(
+                                    "Unable to fetch identity balance for progress: {:?}",
+                                    e
+                                ),
+                            }
+                        }
+
+                        // In time mode only broadcasts are counted, as in the final result
+                        let (successes, errors) = if block_mode {
+                            (
+                                success_count as u64,
+                                (transition_count as u64).saturating_sub(success_count as u64),
+                            )
+                        } else {
+                            (
+                                oks.load(Ordering::SeqCst) as u64,
+                                errs.load(Ordering::SeqCst) as u64,
+                            )
+                        };
+
+                        let _ = progress.send(StrategyProgress {
+                            strategy_name: strategy_name.clone(),
+                            block_mode,
+                            index,
+                            total: num_blocks_or_seconds,
+                            block_height: current_block_info.height,
+                            step_transitions: transitions.len() as u64,
+                            transition_count: transition_count as u64,
+                            success_count: successes,
+                            error_count: errors,
+                            credits_spent,
+                        });
+                    }
+
                     // Update current_block_info and index for next loop iteration
                     current_block_info.height += 1;
                     let current_time_ms = SystemTime::now()
@@ -1651,7 +2232,15 @@ pub async fn run_strategy_task<'s>(
                 }
 
                 // Strategy execution is finished
-                tracing::info!("-----Strategy '{}' finished running-----", strategy_name);
+                if stopped {
+                    tracing::info!(
+                        "-----Strategy '{}' stopped at block height {}-----",
+                        strategy_name,
+                        current_block_info.height
+                    );
+                } else {
+                    tracing::info!("-----Strategy '{}' finished running-----", strategy_name);
+                }
 
                 // Log oks and errs
                 tracing::info!(
@@ -1666,12 +2255,18 @@ pub async fn run_strategy_task<'s>(
                     tracing::info!("Time-based strategy execution ran for {} seconds and intended to run for {} seconds.", load_execution_run_time.as_secs(), num_blocks_or_seconds);
                 }
 
+                // Registrations still awaited in time mode get a last chance to settle, so
+                // the identities can be withdrawn from
+                current_identities.extend(pending_identities.finish_watched(STOP_TIMEOUT).await);
+
                 // Log all the newly created identities and contracts.
                 // Note these txs were not confirmed. They were just attempted at least.
                 tracing::info!(
                     "Newly created identities (attempted): {:?}",
                     new_identity_ids
                 );
+                let identity_registrations = pending_identities.registrations();
+                tracing::info!("Identity registrations: {}", identity_registrations);
                 tracing::info!(
                     "Newly created contracts (attempted): {:?}",
                     new_contract_ids
@@ -1737,8 +2332,17 @@ pub async fn run_strategy_task<'s>(
                 tracing::info!("Completed {} withdrawals.", withdrawals_count);
                 drop(wallet_lock);
 
+                // Write back the contracts the run modified
+                if let Some(stored_strategy) = app_state
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get_mut(&strategy_name)
+                {
+                    *stored_strategy = strategy.clone();
+                }
+
                 // Refresh the identity at the end
-                drop(loaded_identity_lock);
                 let refresh_result = app_state.refresh_identity(&sdk).await;
                 if let Err(ref e) = refresh_result {
                     tracing::error!("Failed to refresh identity after running strategy: {:?}", e);
@@ -1845,7 +2449,7 @@ pub async fn run_strategy_task<'s>(
                         mode_string,
                         transition_count,
                         success_count,
-                        index-3, // Minus 3 because we still incremented one at the end of the last loop, and don't count the first two blocks
+                        index.saturating_sub(3), // Minus 3 because we still incremented one at the end of the last loop, and don't count the first two blocks
                         load_run_time,
                         init_time.as_secs(),
                         tps,
@@ -1856,22 +2460,61 @@ pub async fn run_strategy_task<'s>(
                     );
                 }
 
+                // Complete and save the run report
+                report.end_block_height = current_block_info.height;
+                report.completed = !stopped;
+                report.stop_reason = stopped.then(|| "stopped on request".to_owned());
+                report.created_identities = new_identity_ids;
+                report.identity_registrations = identity_registrations;
+                report.created_contracts = new_contract_ids;
+                report.identity_credits_spent =
+                    initial_balance_identity as i64 - final_balance_identity as i64;
+                report.wallet_duffs_spent =
+                    initial_balance_wallet as i64 - final_balance_wallet as i64;
+                report.summarize();
+
+                let report_path = report.default_path();
+                let report_file = match report.write(&report_path) {
+                    Ok(()) => {
+                        tracing::info!("Strategy run report written to {}", report_path.display());
+                        Some(report_path)
+                    }
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        None
+                    }
+                };
+
+                let stats = StrategyRunStats {
+                    block_mode: block_mode,
+                    final_block_height: current_block_info.height,
+                    start_block_height: initial_block_info.height,
+                    success_count: success_count.try_into().unwrap(),
+                    transition_count: transition_count.try_into().unwrap(),
+                    rate: tps,
+                    success_rate: successful_tps,
+                    success_percent: success_percent,
+                    run_time: load_execution_run_time,
+                    init_time: init_time,
+                    dash_spent_identity,
+                    dash_spent_wallet,
+                    identity_registrations,
+                    seed,
+                };
+
                 BackendEvent::StrategyCompleted {
                     strategy_name: strategy_name.clone(),
-                    result: StrategyCompletionResult::Success {
-                        block_mode: block_mode,
-                        final_block_height: current_block_info.height,
-                        start_block_height: initial_block_info.height,
-                        success_count: success_count.try_into().unwrap(),
-                        transition_count: transition_count.try_into().unwrap(),
-                        rate: tps,
-                        success_rate: successful_tps,
-                        success_percent: success_percent,
-                        run_time: load_execution_run_time,
-                        init_time: init_time,
-                        dash_spent_identity,
-                        dash_spent_wallet,
+                    result: if stopped {
+                        StrategyCompletionResult::PartiallyCompleted {
+                            reached_block_height: current_block_info.height,
+                            reason: report.stop_reason.clone().unwrap_or_default(),
+                            stats,
+                        }
+                    } else {
+                        StrategyCompletionResult::Success(stats)
                     },
+                    report: Box::new(report),
+                    report_file,
                 }
             } else {
                 tracing::error!("No strategy loaded with name \"{}\"", strategy_name);
@@ -1907,7 +2550,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
                 }
             }
         }
@@ -1936,7 +2579,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
                 }
             }
         }
@@ -1947,6 +2590,11 @@ pub async fn run_strategy_task<'s>(
             if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
                 // Clear operations for the strategy
                 strategy.operations.clear();
+                app_state
+                    .available_strategies_operation_keys
+                    .lock()
+                    .await
+                    .remove(&strategy_name);
 
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name.clone(),
@@ -1959,7 +2607,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
                 }
             }
         }
@@ -1979,7 +2627,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
                 }
             }
         }
@@ -1999,7 +2647,7 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
                 }
             }
         }
@@ -2007,6 +2655,14 @@ pub async fn run_strategy_task<'s>(
             let mut strategies_lock = app_state.available_strategies.lock().await;
             if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
                 strategy.operations.pop();
+                if let Some(operation_keys) = app_state
+                    .available_strategies_operation_keys
+                    .lock()
+                    .await
+                    .get_mut(&strategy_name)
+                {
+                    operation_keys.truncate(strategy.operations.len());
+                }
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name.clone(),
                     MutexGuard::map(strategies_lock, |strategies| {
@@ -2019,13 +2675,587 @@ pub async fn run_strategy_task<'s>(
                 ))
             } else {
                 BackendEvent::StrategyError {
-                    error: format!("Strategy doesn't exist in app state"),
+                    error: "Strategy doesn't exist in app state".to_owned(),
+                }
+            }
+        }
+        StrategyTask::EditOperation {
+            strategy_name,
+            index,
+            operation,
+        } => {
+            let mut strategies_lock = app_state.available_strategies.lock().await;
+            let Some(strategy) = strategies_lock.get_mut(&strategy_name) else {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            };
+            let Some(existing) = strategy.operations.get_mut(index) else {
+                return BackendEvent::StrategyError {
+                    error: format!("No operation at position {}", index + 1),
+                };
+            };
+            *existing = operation;
+
+            let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
+            selected_strategy_event(strategy_name, strategies_lock, contract_names_lock)
+        }
+        StrategyTask::RemoveOperation {
+            strategy_name,
+            index,
+        } => edit_operations(app_state, strategy_name, EntryEdit::Remove(index)).await,
+        StrategyTask::DuplicateOperation {
+            strategy_name,
+            index,
+        } => edit_operations(app_state, strategy_name, EntryEdit::Duplicate(index)).await,
+        StrategyTask::MoveOperation {
+            strategy_name,
+            from,
+            to,
+        } => edit_operations(app_state, strategy_name, EntryEdit::Move { from, to }).await,
+        StrategyTask::EditContract {
+            strategy_name,
+            index,
+            contract_names,
+        } => {
+            let mut strategies_lock = app_state.available_strategies.lock().await;
+            let known_contracts_lock = app_state.known_contracts.lock().await;
+            let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
+            let mut contract_names_lock =
+                app_state.available_strategies_contract_names.lock().await;
+
+            let Some(strategy) = strategies_lock.get_mut(&strategy_name) else {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            };
+            let Some(existing) = strategy.start_contracts.get_mut(index) else {
+                return BackendEvent::StrategyError {
+                    error: format!("No start contract at position {}", index + 1),
+                };
+            };
+            let Some(first_contract_name) = contract_names.first() else {
+                return BackendEvent::StrategyError {
+                    error: "No contract selected".to_owned(),
+                };
+            };
+
+            let slots = update_slots(existing.1.as_ref(), contract_names.len() - 1);
+            match start_contract_from_names(
+                &contract_names,
+                &slots,
+                &known_contracts_lock,
+                &supporting_contracts_lock,
+                sdk.version(),
+            ) {
+                Ok(start_contract) => *existing = start_contract,
+                Err(error) => {
+                    tracing::error!("{}", error);
+                    return BackendEvent::StrategyError { error };
+                }
+            }
+
+            let updates = slots
+                .into_iter()
+                .zip(contract_names.iter().skip(1).cloned())
+                .collect::<BTreeMap<u64, String>>();
+            if let Some(names) = contract_names_lock
+                .get_mut(&strategy_name)
+                .and_then(|names| names.get_mut(index))
+            {
+                *names = (first_contract_name.clone(), Some(updates));
+            }
+
+            selected_strategy_event(strategy_name, strategies_lock, contract_names_lock)
+        }
+        StrategyTask::RemoveContract {
+            strategy_name,
+            index,
+        } => edit_start_contracts(app_state, strategy_name, EntryEdit::Remove(index)).await,
+        StrategyTask::DuplicateContract {
+            strategy_name,
+            index,
+        } => edit_start_contracts(app_state, strategy_name, EntryEdit::Duplicate(index)).await,
+        StrategyTask::MoveContract {
+            strategy_name,
+            from,
+            to,
+        } => edit_start_contracts(app_state, strategy_name, EntryEdit::Move { from, to }).await,
+        StrategyTask::EditContractUpdates {
+            strategy_name,
+            index,
+            edit,
+        } => {
+            let mut strategies_lock = app_state.available_strategies.lock().await;
+            let known_contracts_lock = app_state.known_contracts.lock().await;
+            let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
+            let mut contract_names_lock =
+                app_state.available_strategies_contract_names.lock().await;
+
+            let Some(strategy) = strategies_lock.get_mut(&strategy_name) else {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            };
+            let Some((original, updates)) = strategy.start_contracts.get_mut(index) else {
+                return BackendEvent::StrategyError {
+                    error: format!("No start contract at position {}", index + 1),
+                };
+            };
+            let update_names = contract_names_lock
+                .get_mut(&strategy_name)
+                .and_then(|names| names.get_mut(index))
+                .map(|(_, update_names)| update_names);
+
+            let mut new_updates = updates.clone().unwrap_or_default();
+            let mut new_update_names = update_names
+                .as_deref()
+                .cloned()
+                .flatten()
+                .unwrap_or_default();
+            let edited = edit
+                .apply(&mut new_updates, &mut new_update_names, |contract_name| {
+                    created_contract(
+                        contract_name,
+                        &known_contracts_lock,
+                        &supporting_contracts_lock,
+                        sdk.version(),
+                    )
+                })
+                .and_then(|()| contract_updates::check_timeline(original, &new_updates));
+            if let Err(error) = edited {
+                tracing::error!("{}", error);
+                return BackendEvent::StrategyError { error };
+            }
+
+            *updates = (!new_updates.is_empty()).then_some(new_updates);
+            if let Some(update_names) = update_names {
+                *update_names = (!new_update_names.is_empty()).then_some(new_update_names);
+            }
+
+            selected_strategy_event(strategy_name, strategies_lock, contract_names_lock)
+        }
+        StrategyTask::SetStrategySeed {
+            ref strategy_name,
+            seed,
+        } => {
+            if !app_state
+                .available_strategies
+                .lock()
+                .await
+                .contains_key(strategy_name)
+            {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            }
+
+            let mut seeds_lock = app_state.available_strategies_seeds.lock().await;
+            let message = match seed {
+                Some(seed) => {
+                    seeds_lock.insert(strategy_name.clone(), seed);
+                    format!("Strategy {} runs with seed {}", strategy_name, seed)
+                }
+                None => {
+                    seeds_lock.remove(strategy_name);
+                    format!("Strategy {} runs with a random seed", strategy_name)
+                }
+            };
+            drop(seeds_lock);
+
+            BackendEvent::TaskCompleted {
+                execution_result: Ok(message.into()),
+                task: Task::Strategy(task),
+            }
+        }
+        StrategyTask::SetOperationKey {
+            strategy_name,
+            index,
+            key,
+        } => {
+            let strategies_lock = app_state.available_strategies.lock().await;
+            let Some(strategy) = strategies_lock.get(&strategy_name) else {
+                return BackendEvent::StrategyError {
+                    error: "Strategy doesn't exist in app state.".to_owned(),
+                };
+            };
+            if index >= strategy.operations.len() {
+                return BackendEvent::StrategyError {
+                    error: format!("No operation at position {}", index + 1),
+                };
+            }
+
+            let operations_count = strategy.operations.len();
+            drop(strategies_lock);
+
+            let operation_keys_lock = app_state.available_strategies_operation_keys.lock().await;
+            BackendEvent::AppStateUpdated(AppStateUpdate::OperationKeys(
+                strategy_name.clone(),
+                MutexGuard::map(operation_keys_lock, |operation_keys| {
+                    let operation_keys = operation_keys.entry(strategy_name).or_default();
+                    operation_keys.resize(operations_count, None);
+                    operation_keys[index] = key;
+                    operation_keys
+                }),
+            ))
+        }
+    }
+}
+
+/// Number of asset lock proofs to create before running `blocks` blocks of the
+/// strategy: one per start identity, identity insert and top up.
+fn asset_lock_proofs_needed(strategy: &Strategy, blocks: u64) -> u64 {
+    let identity_inserts_per_block = strategy
+        .identity_inserts
+        .frequency
+        .times_per_block_range
+        .start as u64;
+    let top_ups_per_block: u64 = strategy
+        .operations
+        .iter()
+        .filter(|op| op.op_type == OperationType::IdentityTopUp)
+        .map(|op| op.frequency.times_per_block_range.start as u64)
+        .sum();
+
+    strategy.start_identities.number_of_identities as u64
+        + (identity_inserts_per_block + top_ups_per_block) * blocks
+}
+
+/// Fetches the current block info from the metadata of an epochs request,
+/// retrying a couple of times.
+async fn current_block_info(sdk: &Sdk) -> Result<BlockInfo, String> {
+    const MAX_RETRIES: u8 = 2;
+    let mut retries = 0;
+    let request = GetEpochsInfoRequest {
+        version: Some(get_epochs_info_request::Version::V0(
+            get_epochs_info_request::GetEpochsInfoRequestV0 {
+                start_epoch: None,
+                count: 1,
+                ascending: false,
+                prove: false,
+            },
+        )),
+    };
+    loop {
+        match sdk
+            .execute(request.clone(), RequestSettings::default())
+            .await
+        {
+            Ok(response) => {
+                if let Some(get_epochs_info_response::Version::V0(response_v0)) = response.version {
+                    if let Some(metadata) = response_v0.metadata {
+                        return Ok(BlockInfo {
+                            time_ms: metadata.time_ms,
+                            height: metadata.height,
+                            core_height: metadata.core_chain_locked_height,
+                            epoch: Epoch::new(metadata.epoch as u16).unwrap(),
+                        });
+                    }
+                }
+                return Ok(BlockInfo::default());
+            }
+            Err(e) if retries < MAX_RETRIES => {
+                tracing::error!("Error executing request, retrying: {:?}", e);
+                retries += 1;
+            }
+            Err(e) => {
+                tracing::error!("Failed to execute request after retries: {:?}", e);
+                return Err(format!("Failed to execute request after retries: {:?}", e));
+            }
+        }
+    }
+}
+
+/// Answers document queries of strategy operations from the local Drive
+/// instance.
+fn local_documents(drive: &Drive, query: LocalDocumentQuery) -> Vec<Document> {
+    match query {
+        LocalDocumentQuery::RandomDocumentQuery(random_query) => {
+            let document_type = random_query.document_type;
+            let data_contract = random_query.data_contract;
+
+            // Construct a DriveQuery based on the document_type and data_contract
+            let drive_query = DriveQuery::any_item_query(data_contract, document_type.as_ref());
+
+            // Query the Drive for documents
+            match drive.query_documents(drive_query, None, false, None, None) {
+                Ok(outcome) => match outcome {
+                    QueryDocumentsOutcome::V0(outcome_v0) => {
+                        let documents = outcome_v0.documents_owned();
+                        tracing::info!("Fetched {} documents using DriveQuery", documents.len());
+                        documents
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("Error fetching documents using DriveQuery: {:?}", e);
+                    vec![]
                 }
             }
         }
     }
 }
 
+/// Fetches an identity with its balance from the local Drive instance, an
+/// identity without any data is returned if it's not there.
+fn local_identity(
+    drive: &Drive,
+    identifier: Identifier,
+    platform_version: &PlatformVersion,
+) -> PartialIdentity {
+    let empty_identity = || PartialIdentity {
+        id: identifier,
+        loaded_public_keys: BTreeMap::new(),
+        balance: None,
+        revision: None,
+        not_found_public_keys: BTreeSet::new(),
+    };
+
+    match drive.fetch_identity_with_balance(identifier.into_buffer(), None, platform_version) {
+        Ok(maybe_partial_identity) => {
+            let partial_identity = maybe_partial_identity.unwrap_or_else(empty_identity);
+            tracing::info!(
+                "Fetched identity info for identifier {}: {:?}",
+                identifier,
+                partial_identity
+            );
+            partial_identity
+        }
+        Err(e) => {
+            tracing::error!("Error fetching identity: {:?}", e);
+            empty_identity()
+        }
+    }
+}
+
+/// Index based change to a list of strategy entries.
+#[derive(Debug, Clone, Copy)]
+enum EntryEdit {
+    Remove(usize),
+    Duplicate(usize),
+    Move { from: usize, to: usize },
+}
+
+impl EntryEdit {
+    /// Whether all indexes of the edit refer to existing entries.
+    fn in_range(&self, len: usize) -> bool {
+        match *self {
+            EntryEdit::Remove(index) | EntryEdit::Duplicate(index) => index < len,
+            EntryEdit::Move { from, to } => from < len && to < len,
+        }
+    }
+
+    /// Applies the edit, the indexes must be [in range](Self::in_range).
+    fn apply<T: Clone>(&self, entries: &mut Vec<T>) {
+        match *self {
+            EntryEdit::Remove(index) => {
+                entries.remove(index);
+            }
+            EntryEdit::Duplicate(index) => {
+                let entry = entries[index].clone();
+                entries.insert(index + 1, entry);
+            }
+            EntryEdit::Move { from, to } => {
+                let entry = entries.remove(from);
+                entries.insert(to, entry);
+            }
+        }
+    }
+}
+
+/// Drops the operation keys and seed stored for a strategy, they don't apply
+/// to another strategy created under the same name.
+async fn clear_strategy_settings(app_state: &AppState, strategy_name: &str) {
+    app_state
+        .available_strategies_operation_keys
+        .lock()
+        .await
+        .remove(strategy_name);
+    app_state
+        .available_strategies_seeds
+        .lock()
+        .await
+        .remove(strategy_name);
+}
+
+/// Seed of a run: the requested one, else the one stored for the strategy,
+/// else a random one that is recorded so the run can be replayed.
+async fn run_seed(app_state: &AppState, strategy_name: &str, requested: Option<u64>) -> u64 {
+    match requested {
+        Some(seed) => seed,
+        None => app_state
+            .available_strategies_seeds
+            .lock()
+            .await
+            .get(strategy_name)
+            .copied()
+            .unwrap_or_else(rand::random),
+    }
+}
+
+fn selected_strategy_event<'s>(
+    strategy_name: String,
+    strategies_lock: MutexGuard<'s, StrategiesMap>,
+    contract_names_lock: MutexGuard<'s, BTreeMap<String, StrategyContractNames>>,
+) -> BackendEvent<'s> {
+    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+        strategy_name.clone(),
+        MutexGuard::map(strategies_lock, |strategies| {
+            strategies.get_mut(&strategy_name).expect("strategy exists")
+        }),
+        MutexGuard::map(contract_names_lock, |names| {
+            names.entry(strategy_name.clone()).or_default()
+        }),
+    ))
+}
+
+async fn edit_operations(
+    app_state: &AppState,
+    strategy_name: String,
+    edit: EntryEdit,
+) -> BackendEvent {
+    let mut strategies_lock = app_state.available_strategies.lock().await;
+    let Some(strategy) = strategies_lock.get_mut(&strategy_name) else {
+        return BackendEvent::StrategyError {
+            error: "Strategy doesn't exist in app state.".to_owned(),
+        };
+    };
+    if !edit.in_range(strategy.operations.len()) {
+        return BackendEvent::StrategyError {
+            error: format!("No such operation in strategy {}", strategy_name),
+        };
+    }
+    let operations_count = strategy.operations.len();
+    edit.apply(&mut strategy.operations);
+
+    // Assigned keys follow operations entry by entry
+    if let Some(operation_keys) = app_state
+        .available_strategies_operation_keys
+        .lock()
+        .await
+        .get_mut(&strategy_name)
+    {
+        operation_keys.resize(operations_count, None);
+        edit.apply(operation_keys);
+    }
+
+    let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
+    selected_strategy_event(strategy_name, strategies_lock, contract_names_lock)
+}
+
+async fn edit_start_contracts(
+    app_state: &AppState,
+    strategy_name: String,
+    edit: EntryEdit,
+) -> BackendEvent {
+    let mut strategies_lock = app_state.available_strategies.lock().await;
+    let mut contract_names_lock = app_state.available_strategies_contract_names.lock().await;
+    let Some(strategy) = strategies_lock.get_mut(&strategy_name) else {
+        return BackendEvent::StrategyError {
+            error: "Strategy doesn't exist in app state.".to_owned(),
+        };
+    };
+    if !edit.in_range(strategy.start_contracts.len()) {
+        return BackendEvent::StrategyError {
+            error: format!("No such start contract in strategy {}", strategy_name),
+        };
+    }
+    edit.apply(&mut strategy.start_contracts);
+
+    // Displayed names follow start contracts entry by entry
+    if let Some(contract_names) = contract_names_lock.get_mut(&strategy_name) {
+        if edit.in_range(contract_names.len()) {
+            edit.apply(contract_names);
+        }
+    }
+
+    selected_strategy_event(strategy_name, strategies_lock, contract_names_lock)
+}
+
+/// Looks up a known or supporting contract by name for a strategy.
+fn created_contract(
+    contract_name: &str,
+    known_contracts: &KnownContractsMap,
+    supporting_contracts: &BTreeMap<String, DataContract>,
+    platform_version: &PlatformVersion,
+) -> Result<CreatedDataContract, String> {
+    // Set a fake identity nonce for now. We will set real identity nonces during strategy execution.
+    let fake_identity_nonce = 1;
+
+    let data_contract = known_contracts
+        .get(contract_name)
+        .or_else(|| supporting_contracts.get(contract_name))
+        .cloned()
+        .ok_or_else(|| format!("Contract {} not found", contract_name))?;
+    CreatedDataContract::from_contract_and_identity_nonce(
+        data_contract,
+        fake_identity_nonce,
+        platform_version,
+    )
+    .map_err(|e| {
+        format!(
+            "Error converting DataContract to CreatedDataContract: {:?}",
+            e
+        )
+    })
+}
+
+/// Slots for `count` updates of a start contract: the slots of its `existing`
+/// updates are kept in order, extra updates follow the last one.
+fn update_slots(existing: Option<&BTreeMap<u64, CreatedDataContract>>, count: usize) -> Vec<u64> {
+    let existing: Vec<u64> = existing
+        .into_iter()
+        .flat_map(|updates| updates.keys().copied())
+        .collect();
+    let next = existing.last().map_or(1, |slot| slot + 1);
+    existing.into_iter().chain(next..).take(count).collect()
+}
+
+/// Builds a start contract from the names of a contract and its updates,
+/// looked up in known contracts first. Updates are planned at `update_slots`
+/// in order and checked against the versions before them.
+fn start_contract_from_names(
+    contract_names: &[String],
+    update_slots: &[u64],
+    known_contracts: &KnownContractsMap,
+    supporting_contracts: &BTreeMap<String, DataContract>,
+    platform_version: &PlatformVersion,
+) -> Result<
+    (
+        CreatedDataContract,
+        Option<BTreeMap<u64, CreatedDataContract>>,
+    ),
+    String,
+> {
+    let created_contract = |contract_name: &String| {
+        created_contract(
+            contract_name,
+            known_contracts,
+            supporting_contracts,
+            platform_version,
+        )
+    };
+
+    let (first_contract_name, update_names) = contract_names
+        .split_first()
+        .ok_or_else(|| "No contract selected".to_owned())?;
+    let original_contract = created_contract(first_contract_name)?;
+    let updates = update_slots
+        .iter()
+        .zip(update_names)
+        .map(|(slot, name)| Ok((*slot, created_contract(name)?)))
+        .collect::<Result<BTreeMap<u64, _>, String>>()?;
+    contract_updates::check_timeline(&original_contract, &updates)?;
+
+    Ok((
+        original_contract,
+        if updates.is_empty() {
+            None
+        } else {
+            Some(updates)
+        },
+    ))
+}
+
 pub async fn update_known_contracts(
     sdk: &Sdk,
     known_contracts: &Mutex<KnownContractsMap>,
@@ -0,0 +1,174 @@
+//! Timeline of the updates planned for a start contract.
+//!
+//! Updates are keyed by their slot, the update of slot `n` is applied
+//! [CONTRACT_UPDATE_BLOCK_INTERVAL] × `n` blocks after the contract is
+//! created. Every update must be compatible with the version it replaces.
+
+use std::collections::BTreeMap;
+
+use dpp::data_contract::{
+    accessors::v0::DataContractV0Getters, created_data_contract::CreatedDataContract,
+    document_type::accessors::DocumentTypeV0Getters, DataContract,
+};
+
+/// Number of blocks between two update slots of a start contract.
+pub const CONTRACT_UPDATE_BLOCK_INTERVAL: u64 = 3;
+
+/// Change to the update timeline of a start contract, blocks are offsets from
+/// the contract creation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ContractUpdateEdit {
+    /// Plans a contract as the update at `block`, replacing the one planned
+    /// there if any
+    Set {
+        block: u64,
+        contract_name: String,
+    },
+    Move {
+        from_block: u64,
+        to_block: u64,
+    },
+    Remove {
+        block: u64,
+    },
+}
+
+/// Slot of the update at `block`.
+pub(super) fn update_slot(block: u64) -> Result<u64, String> {
+    if block == 0 || block % CONTRACT_UPDATE_BLOCK_INTERVAL != 0 {
+        Err(format!(
+            "Contract updates can only be planned every {} blocks, {} is not a valid offset",
+            CONTRACT_UPDATE_BLOCK_INTERVAL, block
+        ))
+    } else {
+        Ok(block / CONTRACT_UPDATE_BLOCK_INTERVAL)
+    }
+}
+
+impl ContractUpdateEdit {
+    /// Applies the edit to the updates of a start contract and their names,
+    /// `created_contract` resolves the contract of a newly planned update.
+    pub(super) fn apply(
+        self,
+        updates: &mut BTreeMap<u64, CreatedDataContract>,
+        update_names: &mut BTreeMap<u64, String>,
+        created_contract: impl FnOnce(&str) -> Result<CreatedDataContract, String>,
+    ) -> Result<(), String> {
+        match self {
+            ContractUpdateEdit::Set {
+                block,
+                contract_name,
+            } => {
+                let slot = update_slot(block)?;
+                updates.insert(slot, created_contract(&contract_name)?);
+                update_names.insert(slot, contract_name);
+            }
+            ContractUpdateEdit::Move {
+                from_block,
+                to_block,
+            } => {
+                let from = update_slot(from_block)?;
+                let to = update_slot(to_block)?;
+                if from == to {
+                    return Ok(());
+                }
+                if updates.contains_key(&to) {
+                    return Err(format!(
+                        "An update is already planned at block {}",
+                        to_block
+                    ));
+                }
+                let update = updates
+                    .remove(&from)
+                    .ok_or_else(|| format!("No update planned at block {}", from_block))?;
+                updates.insert(to, update);
+                if let Some(name) = update_names.remove(&from) {
+                    update_names.insert(to, name);
+                }
+            }
+            ContractUpdateEdit::Remove { block } => {
+                let slot = update_slot(block)?;
+                updates
+                    .remove(&slot)
+                    .ok_or_else(|| format!("No update planned at block {}", block))?;
+                update_names.remove(&slot);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reasons `next` can't replace `previous`, empty if it is a compatible update:
+/// document types and their properties can be added but not removed, and
+/// existing properties keep their type and optionality.
+fn update_incompatibilities(previous: &DataContract, next: &DataContract) -> Vec<String> {
+    let mut problems = Vec::new();
+    let next_document_types = next.document_types();
+
+    for (name, previous_type) in previous.document_types() {
+        let Some(next_type) = next_document_types.get(name) else {
+            problems.push(format!("document type {} is removed", name));
+            continue;
+        };
+
+        if next_type.documents_mutable() != previous_type.documents_mutable() {
+            problems.push(format!("mutability of document type {} changes", name));
+        }
+
+        for (property_name, previous_property) in previous_type.properties() {
+            match next_type.properties().get(property_name) {
+                None => problems.push(format!("property {}.{} is removed", name, property_name)),
+                Some(next_property)
+                    if next_property.property_type != previous_property.property_type =>
+                {
+                    problems.push(format!(
+                        "type of property {}.{} changes",
+                        name, property_name
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        for required in next_type
+            .required_fields()
+            .difference(previous_type.required_fields())
+        {
+            problems.push(format!("property {}.{} becomes required", name, required));
+        }
+    }
+
+    problems
+}
+
+/// Checks every planned update against the version before it.
+pub(super) fn check_timeline(
+    original: &CreatedDataContract,
+    updates: &BTreeMap<u64, CreatedDataContract>,
+) -> Result<(), String> {
+    let mut previous = original.data_contract();
+    let mut problems = Vec::new();
+    for (slot, update) in updates {
+        problems.extend(
+            update_incompatibilities(previous, update.data_contract())
+                .into_iter()
+                .map(|problem| {
+                    format!(
+                        "update at block {}: {}",
+                        slot * CONTRACT_UPDATE_BLOCK_INTERVAL,
+                        problem
+                    )
+                }),
+        );
+        previous = update.data_contract();
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Incompatible contract updates: {}",
+            problems.join("; ")
+        ))
+    }
+}
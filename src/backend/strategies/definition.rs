@@ -0,0 +1,1100 @@
+//! Declarative strategy definitions.
+//!
+//! A strategy can be written by hand as a TOML or JSON file (picked by the
+//! extension) and compiled into a [Strategy]. Contracts are referred to by
+//! their names among known and supporting contracts, the same names the TUI
+//! shows. Parameters of random document types aren't part of the format, they
+//! are drawn from the seed of the definition when it has one and generated
+//! anew every time it's compiled otherwise.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use dpp::{
+    data_contract::{
+        accessors::v0::DataContractV0Getters,
+        created_data_contract::CreatedDataContract,
+        document_type::{
+            accessors::DocumentTypeV0Getters,
+            random_document::{DocumentFieldFillSize, DocumentFieldFillType},
+            v0::random_document_type::{
+                FieldMinMaxBounds, FieldTypeWeights, RandomDocumentTypeParameters,
+            },
+            DocumentType,
+        },
+        DataContract,
+    },
+    identity::{KeyType, Purpose, SecurityLevel},
+    platform_value::string_encoding::Encoding,
+    version::PlatformVersion,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use strategy_tests::{
+    frequency::Frequency,
+    operations::{
+        DataContractUpdateAction, DataContractUpdateOp, DocumentAction, DocumentOp,
+        IdentityUpdateOp, Operation, OperationType,
+    },
+    IdentityInsertInfo, StartIdentities, Strategy,
+};
+use toml::Spanned;
+
+use super::contract_updates::{self, CONTRACT_UPDATE_BLOCK_INTERVAL};
+use crate::backend::StrategyContractNames;
+
+/// Directory the TUI suggests to save strategy definitions to.
+pub const DEFINITIONS_DIR: &str = "supporting_files/strategy_definitions";
+
+const MIN_KEYS_PER_IDENTITY: u8 = 3;
+const MAX_KEYS_PER_IDENTITY: u8 = 32;
+
+/// Same starting balance the TUI sets for start identities.
+const DEFAULT_STARTING_BALANCE: u64 = 10_000_000;
+
+/// A problem found in a strategy definition file.
+#[derive(Debug, Clone)]
+pub struct DefinitionProblem {
+    /// Line and column of the offending item, JSON files only have them for
+    /// syntax errors
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for DefinitionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => {
+                write!(f, "line {}, column {}: {}", line, column, self.message)
+            }
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DefinitionError {
+    #[error("unable to read {}: {}", .0.display(), .1)]
+    Read(PathBuf, io::Error),
+    #[error("unable to write {}: {}", .0.display(), .1)]
+    Write(PathBuf, io::Error),
+    #[error("{} is neither a .toml nor a .json file", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("invalid strategy definition {}:{}", .file.display(), problems_list(.problems))]
+    Invalid {
+        file: PathBuf,
+        problems: Vec<DefinitionProblem>,
+    },
+    #[error("strategy can't be expressed as a definition: {0}")]
+    Unsupported(String),
+    #[error("unable to serialize strategy definition: {0}")]
+    Serialize(String),
+}
+
+fn problems_list(problems: &[DefinitionProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n  {}", problem))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self, DefinitionError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            _ => Err(DefinitionError::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyDefinition {
+    /// Name of the strategy, the file name is used if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Seed of the runs and of the random parameters of the operations, a
+    /// random one is used every time if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub start_identities: StartIdentitiesDefinition,
+    /// No identities are inserted during the run if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_inserts: Option<FrequencyDefinition>,
+    #[serde(default)]
+    pub start_contracts: Vec<StartContractDefinition>,
+    #[serde(default)]
+    pub operations: Vec<OperationDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StartIdentitiesDefinition {
+    #[serde(default)]
+    pub count: u16,
+    #[serde(default = "default_keys_per_identity")]
+    pub keys_per_identity: u8,
+    /// Starting balance of every identity in credits
+    #[serde(default = "default_starting_balance")]
+    pub balance: u64,
+    /// Adds a critical transfer key to every identity
+    #[serde(default)]
+    pub transfer_key: bool,
+}
+
+impl Default for StartIdentitiesDefinition {
+    fn default() -> Self {
+        StartIdentitiesDefinition {
+            count: 0,
+            keys_per_identity: default_keys_per_identity(),
+            balance: default_starting_balance(),
+            transfer_key: false,
+        }
+    }
+}
+
+fn default_keys_per_identity() -> u8 {
+    MIN_KEYS_PER_IDENTITY
+}
+
+fn default_starting_balance() -> u64 {
+    DEFAULT_STARTING_BALANCE
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StartContractDefinition {
+    pub contract: String,
+    /// Contracts applied as updates, by block offset from the contract
+    /// creation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updates: Vec<ContractUpdateDefinition>,
+}
+
+/// Update of a start contract, `block` is a multiple of
+/// [CONTRACT_UPDATE_BLOCK_INTERVAL] counted from the contract creation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractUpdateDefinition {
+    pub block: u64,
+    pub contract: String,
+}
+
+/// How often an event happens, the number of times per block is picked
+/// between `times_per_block` and `max_times_per_block`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrequencyDefinition {
+    #[serde(default = "default_times_per_block")]
+    pub times_per_block: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_times_per_block: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chance_per_block: Option<f64>,
+}
+
+impl Default for FrequencyDefinition {
+    fn default() -> Self {
+        FrequencyDefinition {
+            times_per_block: default_times_per_block(),
+            max_times_per_block: None,
+            chance_per_block: None,
+        }
+    }
+}
+
+fn default_times_per_block() -> u16 {
+    1
+}
+
+/// An operation picked by `type`, every operation runs once per block unless
+/// its `frequency` says otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum OperationDefinition {
+    DocumentInsertRandom {
+        contract: String,
+        document_type: String,
+        #[serde(default)]
+        fill_size: FillSize,
+        /// Populates fields that aren't required
+        #[serde(default)]
+        fill_optional_fields: bool,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    DocumentDelete {
+        contract: String,
+        document_type: String,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    IdentityTopUp {
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    IdentityTransfer {
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    IdentityWithdrawal {
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    IdentityAddKeys {
+        count: u16,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    IdentityDisableKeys {
+        count: u16,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    ContractCreateRandom {
+        /// Maximum number of random document types per contract
+        document_types: u16,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+    ContractUpdateNewDocumentTypes {
+        contract: String,
+        #[serde(default)]
+        frequency: FrequencyDefinition,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillSize {
+    Min,
+    Max,
+    #[default]
+    Any,
+}
+
+impl From<FillSize> for DocumentFieldFillSize {
+    fn from(fill_size: FillSize) -> Self {
+        match fill_size {
+            FillSize::Min => DocumentFieldFillSize::MinDocumentFillSize,
+            FillSize::Max => DocumentFieldFillSize::MaxDocumentFillSize,
+            FillSize::Any => DocumentFieldFillSize::AnyDocumentFillSize,
+        }
+    }
+}
+
+impl From<DocumentFieldFillSize> for FillSize {
+    fn from(fill_size: DocumentFieldFillSize) -> Self {
+        match fill_size {
+            DocumentFieldFillSize::MinDocumentFillSize => FillSize::Min,
+            DocumentFieldFillSize::MaxDocumentFillSize => FillSize::Max,
+            DocumentFieldFillSize::AnyDocumentFillSize => FillSize::Any,
+        }
+    }
+}
+
+/// A strategy compiled from its definition.
+pub(crate) struct CompiledStrategy {
+    pub(crate) name: Option<String>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) strategy: Strategy,
+    pub(crate) contract_names: StrategyContractNames,
+}
+
+/// Item of a definition a problem was found in.
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    section: &'static str,
+    index: Option<usize>,
+    /// Table of the item the field belongs to
+    table: Option<&'static str>,
+    field: Option<&'static str>,
+}
+
+impl Location {
+    fn new(section: &'static str, index: Option<usize>, field: Option<&'static str>) -> Self {
+        Location {
+            section,
+            index,
+            table: None,
+            field,
+        }
+    }
+
+    fn within(self, table: &'static str) -> Self {
+        Location {
+            table: Some(table),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.section)?;
+        if let Some(index) = self.index {
+            write!(f, "[{}]", index)?;
+        }
+        if let Some(table) = self.table {
+            write!(f, ".{}", table)?;
+        }
+        if let Some(field) = self.field {
+            write!(f, ".{}", field)?;
+        }
+        Ok(())
+    }
+}
+
+type Problems = Vec<(Location, String)>;
+
+impl StrategyDefinition {
+    /// Reads the definition file at `path` and compiles it, looking up
+    /// contracts by name with `get_contract`.
+    pub(crate) fn load(
+        path: &Path,
+        get_contract: impl Fn(&str) -> Option<DataContract>,
+        platform_version: &PlatformVersion,
+    ) -> Result<CompiledStrategy, DefinitionError> {
+        let format = Format::of(path)?;
+        let source =
+            fs::read_to_string(path).map_err(|e| DefinitionError::Read(path.to_owned(), e))?;
+        let invalid = |problems| DefinitionError::Invalid {
+            file: path.to_owned(),
+            problems,
+        };
+
+        let definition = Self::parse(&source, format).map_err(|problem| invalid(vec![problem]))?;
+
+        let spans = match format {
+            Format::Toml => toml::from_str::<TomlSpans>(&source).ok(),
+            Format::Json => None,
+        };
+        definition
+            .compile(get_contract, platform_version)
+            .map_err(|problems| {
+                invalid(
+                    problems
+                        .into_iter()
+                        .map(|(location, message)| DefinitionProblem {
+                            position: spans
+                                .as_ref()
+                                .and_then(|spans| spans.span(&location))
+                                .map(|span| position_of(&source, span.start)),
+                            message: format!("{}: {}", location, message),
+                        })
+                        .collect(),
+                )
+            })
+    }
+
+    fn parse(source: &str, format: Format) -> Result<Self, DefinitionProblem> {
+        match format {
+            Format::Toml => toml::from_str(source).map_err(|e| DefinitionProblem {
+                position: e.span().map(|span| position_of(source, span.start)),
+                message: e.message().to_owned(),
+            }),
+            Format::Json => serde_json::from_str(source).map_err(|e| {
+                let message = e.to_string();
+                let position = format!(" at line {} column {}", e.line(), e.column());
+                DefinitionProblem {
+                    position: (e.line() > 0).then(|| (e.line(), e.column())),
+                    message: message
+                        .strip_suffix(&position)
+                        .unwrap_or(&message)
+                        .to_owned(),
+                }
+            }),
+        }
+    }
+
+    /// Builds the strategy, collecting all problems found.
+    fn compile(
+        &self,
+        get_contract: impl Fn(&str) -> Option<DataContract>,
+        platform_version: &PlatformVersion,
+    ) -> Result<CompiledStrategy, Problems> {
+        let mut problems = Vec::new();
+        let mut strategy = Strategy::default();
+
+        let identities = &self.start_identities;
+        if identities.count > 0
+            && !(MIN_KEYS_PER_IDENTITY..=MAX_KEYS_PER_IDENTITY)
+                .contains(&identities.keys_per_identity)
+        {
+            problems.push((
+                Location::new("start_identities", None, Some("keys_per_identity")),
+                format!(
+                    "must be between {} and {}",
+                    MIN_KEYS_PER_IDENTITY, MAX_KEYS_PER_IDENTITY
+                ),
+            ));
+        }
+        let mut extra_keys = BTreeMap::new();
+        if identities.transfer_key {
+            extra_keys.insert(
+                Purpose::TRANSFER,
+                [(SecurityLevel::CRITICAL, vec![KeyType::ECDSA_SECP256K1])].into(),
+            );
+        }
+        strategy.start_identities = StartIdentities {
+            number_of_identities: identities.count,
+            keys_per_identity: identities.keys_per_identity,
+            starting_balances: identities.balance,
+            extra_keys,
+        };
+
+        if let Some(frequency) = &self.identity_inserts {
+            strategy.identity_inserts = IdentityInsertInfo {
+                frequency: frequency.compile(
+                    |field| Location::new("identity_inserts", None, Some(field)),
+                    &mut problems,
+                ),
+                start_keys: 3,
+                extra_keys: BTreeMap::new(),
+            };
+        }
+
+        let mut contract_names = StrategyContractNames::new();
+        for (index, start_contract) in self.start_contracts.iter().enumerate() {
+            let original =
+                created_contract(&start_contract.contract, &get_contract, platform_version)
+                    .map_err(|message| {
+                        problems.push((
+                            Location::new("start_contracts", Some(index), Some("contract")),
+                            message,
+                        ))
+                    });
+
+            // Updates are keyed by their slot, as the TUI does
+            let mut updates = BTreeMap::new();
+            let mut update_names = BTreeMap::new();
+            let updates_location =
+                || Location::new("start_contracts", Some(index), Some("updates"));
+            for update in &start_contract.updates {
+                let slot = match contract_updates::update_slot(update.block) {
+                    Ok(slot) if updates.contains_key(&slot) => {
+                        problems.push((
+                            updates_location(),
+                            format!("more than one update at block {}", update.block),
+                        ));
+                        continue;
+                    }
+                    Ok(slot) => slot,
+                    Err(message) => {
+                        problems.push((updates_location(), message));
+                        continue;
+                    }
+                };
+                match created_contract(&update.contract, &get_contract, platform_version) {
+                    Ok(created) => {
+                        updates.insert(slot, created);
+                        update_names.insert(slot, update.contract.clone());
+                    }
+                    Err(message) => problems.push((updates_location(), message)),
+                }
+            }
+
+            if let Ok(original) = &original {
+                if let Err(message) = contract_updates::check_timeline(original, &updates) {
+                    problems.push((updates_location(), message));
+                }
+            }
+
+            if let Ok(original) = original {
+                strategy
+                    .start_contracts
+                    .push((original, (!updates.is_empty()).then_some(updates)));
+                contract_names.push((
+                    start_contract.contract.clone(),
+                    (!update_names.is_empty()).then_some(update_names),
+                ));
+            }
+        }
+
+        for (index, operation) in self.operations.iter().enumerate() {
+            let frequency = operation.frequency().compile(
+                |field| Location::new("operations", Some(index), Some(field)).within("frequency"),
+                &mut problems,
+            );
+            let mut rng = strategy_rng(self.seed, OPERATIONS_RNG_STREAM + index as u64);
+            match operation.compile(&get_contract, &mut rng) {
+                Ok(op_type) => strategy.operations.push(Operation { op_type, frequency }),
+                Err((field, message)) => problems.push((
+                    Location::new("operations", Some(index), Some(field)),
+                    message,
+                )),
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(CompiledStrategy {
+                name: self.name.clone().filter(|name| !name.is_empty()),
+                seed: self.seed,
+                strategy,
+                contract_names,
+            })
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Describes an existing strategy. Contracts are named after the
+    /// `contracts` they were made of, the strategy's own contract names are
+    /// preferred for start contracts since updates share their contract's ID.
+    pub(crate) fn from_strategy(
+        name: &str,
+        seed: Option<u64>,
+        strategy: &Strategy,
+        contract_names: &[(String, Option<BTreeMap<u64, String>>)],
+        contracts: &BTreeMap<String, DataContract>,
+    ) -> Result<Self, DefinitionError> {
+        let name_of = |contract: &DataContract| {
+            contracts
+                .iter()
+                .find(|(_, known)| known.id() == contract.id())
+                .map(|(name, _)| name.clone())
+                .ok_or_else(|| {
+                    DefinitionError::Unsupported(format!(
+                        "contract {} isn't among known or supporting contracts",
+                        contract.id().to_string(Encoding::Base58)
+                    ))
+                })
+        };
+        let start_contract_name =
+            |stored_name: Option<&String>, contract: &DataContract| match stored_name {
+                Some(stored_name) if contracts.contains_key(stored_name) => Ok(stored_name.clone()),
+                _ => name_of(contract),
+            };
+
+        let mut start_contracts = Vec::new();
+        for (index, (contract, updates)) in strategy.start_contracts.iter().enumerate() {
+            let (stored_name, stored_updates) = match contract_names.get(index) {
+                Some((stored_name, stored_updates)) => (Some(stored_name), stored_updates.as_ref()),
+                None => (None, None),
+            };
+            start_contracts.push(StartContractDefinition {
+                contract: start_contract_name(stored_name, contract.data_contract())?,
+                updates: updates
+                    .iter()
+                    .flatten()
+                    .map(|(slot, update)| {
+                        Ok(ContractUpdateDefinition {
+                            block: slot * CONTRACT_UPDATE_BLOCK_INTERVAL,
+                            contract: start_contract_name(
+                                stored_updates.and_then(|names| names.get(slot)),
+                                update.data_contract(),
+                            )?,
+                        })
+                    })
+                    .collect::<Result<_, DefinitionError>>()?,
+            });
+        }
+
+        let operations = strategy
+            .operations
+            .iter()
+            .map(|operation| OperationDefinition::from_operation(operation, &name_of))
+            .collect::<Result<_, DefinitionError>>()?;
+
+        let identity_inserts = &strategy.identity_inserts.frequency;
+
+        Ok(StrategyDefinition {
+            name: Some(name.to_owned()),
+            seed,
+            start_identities: StartIdentitiesDefinition {
+                count: strategy.start_identities.number_of_identities,
+                keys_per_identity: strategy.start_identities.keys_per_identity,
+                balance: strategy.start_identities.starting_balances,
+                transfer_key: strategy
+                    .start_identities
+                    .extra_keys
+                    .contains_key(&Purpose::TRANSFER),
+            },
+            identity_inserts: (!identity_inserts.times_per_block_range.is_empty())
+                .then(|| FrequencyDefinition::from(identity_inserts)),
+            start_contracts,
+            operations,
+        })
+    }
+
+    /// Writes the definition as TOML or JSON depending on the extension of
+    /// `path`, creating missing parent directories.
+    pub fn save(&self, path: &Path) -> Result<(), DefinitionError> {
+        let content = match Format::of(path)? {
+            Format::Toml => toml::to_string_pretty(self)
+                .map_err(|e| DefinitionError::Serialize(e.to_string()))?,
+            Format::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| DefinitionError::Serialize(e.to_string()))?,
+        };
+
+        let write_error = |e| DefinitionError::Write(path.to_owned(), e);
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        fs::write(path, content).map_err(write_error)
+    }
+}
+
+fn created_contract(
+    name: &str,
+    get_contract: impl Fn(&str) -> Option<DataContract>,
+    platform_version: &PlatformVersion,
+) -> Result<CreatedDataContract, String> {
+    let contract = get_contract(name)
+        .ok_or_else(|| format!("no known or supporting contract named {}", name))?;
+    // Set a fake identity nonce for now. We will set real identity nonces during strategy execution.
+    CreatedDataContract::from_contract_and_identity_nonce(contract, 1, platform_version)
+        .map_err(|e| format!("unable to use contract {}: {:?}", name, e))
+}
+
+fn document_type(
+    get_contract: impl Fn(&str) -> Option<DataContract>,
+    contract_name: &str,
+    document_type_name: &str,
+) -> Result<(DataContract, DocumentType), (&'static str, String)> {
+    let contract = get_contract(contract_name).ok_or_else(|| {
+        (
+            "contract",
+            format!("no known or supporting contract named {}", contract_name),
+        )
+    })?;
+    let document_type = contract
+        .document_type_cloned_for_name(document_type_name)
+        .map_err(|_| {
+            (
+                "document_type",
+                format!(
+                    "contract {} has no document type {}, expected one of: {}",
+                    contract_name,
+                    document_type_name,
+                    contract
+                        .document_types()
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+        })?;
+    Ok((contract, document_type))
+}
+
+impl FrequencyDefinition {
+    /// Builds the frequency, `location` tells where each field is.
+    fn compile(
+        &self,
+        location: impl Fn(&'static str) -> Location,
+        problems: &mut Problems,
+    ) -> Frequency {
+        let max_times_per_block = self.max_times_per_block.unwrap_or(self.times_per_block);
+        if max_times_per_block < self.times_per_block {
+            problems.push((
+                location("max_times_per_block"),
+                "must not be less than times_per_block".to_owned(),
+            ));
+        }
+        if let Some(chance) = self.chance_per_block {
+            if !(chance > 0.0 && chance <= 1.0) {
+                problems.push((
+                    location("chance_per_block"),
+                    "must be greater than 0 and at most 1".to_owned(),
+                ));
+            }
+        }
+
+        Frequency {
+            times_per_block_range: self.times_per_block
+                ..max_times_per_block
+                    .max(self.times_per_block)
+                    .saturating_add(1),
+            chance_per_block: self.chance_per_block,
+        }
+    }
+}
+
+impl From<&Frequency> for FrequencyDefinition {
+    fn from(frequency: &Frequency) -> Self {
+        let Range { start, end } = frequency.times_per_block_range;
+        FrequencyDefinition {
+            times_per_block: start,
+            max_times_per_block: (end.saturating_sub(1) > start).then(|| end - 1),
+            chance_per_block: frequency.chance_per_block,
+        }
+    }
+}
+
+impl OperationDefinition {
+    fn frequency(&self) -> &FrequencyDefinition {
+        match self {
+            OperationDefinition::DocumentInsertRandom { frequency, .. }
+            | OperationDefinition::DocumentDelete { frequency, .. }
+            | OperationDefinition::IdentityTopUp { frequency }
+            | OperationDefinition::IdentityTransfer { frequency }
+            | OperationDefinition::IdentityWithdrawal { frequency }
+            | OperationDefinition::IdentityAddKeys { frequency, .. }
+            | OperationDefinition::IdentityDisableKeys { frequency, .. }
+            | OperationDefinition::ContractCreateRandom { frequency, .. }
+            | OperationDefinition::ContractUpdateNewDocumentTypes { frequency, .. } => frequency,
+        }
+    }
+
+    /// Builds the operation type, returning the offending field on error.
+    fn compile(
+        &self,
+        get_contract: impl Fn(&str) -> Option<DataContract>,
+        rng: &mut StdRng,
+    ) -> Result<OperationType, (&'static str, String)> {
+        let at_least_one = |field: &'static str, count: u16| {
+            if count == 0 {
+                Err((field, "must be at least 1".to_owned()))
+            } else {
+                Ok(count)
+            }
+        };
+
+        Ok(match self {
+            OperationDefinition::DocumentInsertRandom {
+                contract,
+                document_type: document_type_name,
+                fill_size,
+                fill_optional_fields,
+                ..
+            } => {
+                let (contract, document_type) =
+                    document_type(get_contract, contract, document_type_name)?;
+                let fill_type = if *fill_optional_fields {
+                    DocumentFieldFillType::FillIfNotRequired
+                } else {
+                    DocumentFieldFillType::DoNotFillIfNotRequired
+                };
+                OperationType::Document(DocumentOp {
+                    contract,
+                    document_type,
+                    action: DocumentAction::DocumentActionInsertRandom(
+                        fill_type,
+                        (*fill_size).into(),
+                    ),
+                })
+            }
+            OperationDefinition::DocumentDelete {
+                contract,
+                document_type: document_type_name,
+                ..
+            } => {
+                let (contract, document_type) =
+                    document_type(get_contract, contract, document_type_name)?;
+                OperationType::Document(DocumentOp {
+                    contract,
+                    document_type,
+                    action: DocumentAction::DocumentActionDelete,
+                })
+            }
+            OperationDefinition::IdentityTopUp { .. } => OperationType::IdentityTopUp,
+            OperationDefinition::IdentityTransfer { .. } => OperationType::IdentityTransfer,
+            OperationDefinition::IdentityWithdrawal { .. } => OperationType::IdentityWithdrawal,
+            OperationDefinition::IdentityAddKeys { count, .. } => OperationType::IdentityUpdate(
+                IdentityUpdateOp::IdentityUpdateAddKeys(at_least_one("count", *count)?),
+            ),
+            OperationDefinition::IdentityDisableKeys { count, .. } => {
+                OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateDisableKey(
+                    at_least_one("count", *count)?,
+                ))
+            }
+            OperationDefinition::ContractCreateRandom { document_types, .. } => {
+                let document_types = at_least_one("document_types", *document_types)?;
+                OperationType::ContractCreate(
+                    random_document_type_parameters(rng),
+                    1..document_types.saturating_add(1),
+                )
+            }
+            OperationDefinition::ContractUpdateNewDocumentTypes { contract, .. } => {
+                let contract = get_contract(contract).ok_or_else(|| {
+                    (
+                        "contract",
+                        format!("no known or supporting contract named {}", contract),
+                    )
+                })?;
+                OperationType::ContractUpdate(DataContractUpdateOp {
+                    action: DataContractUpdateAction::DataContractNewDocumentTypes(
+                        random_document_type_parameters(rng),
+                    ),
+                    contract,
+                    document_type: None,
+                })
+            }
+        })
+    }
+
+    fn from_operation(
+        operation: &Operation,
+        name_of: impl Fn(&DataContract) -> Result<String, DefinitionError>,
+    ) -> Result<Self, DefinitionError> {
+        let frequency = FrequencyDefinition::from(&operation.frequency);
+        Ok(match &operation.op_type {
+            OperationType::Document(op) => {
+                let contract = name_of(&op.contract)?;
+                let document_type = op.document_type.name().clone();
+                match &op.action {
+                    DocumentAction::DocumentActionInsertRandom(fill_type, fill_size) => {
+                        OperationDefinition::DocumentInsertRandom {
+                            contract,
+                            document_type,
+                            fill_size: (*fill_size).into(),
+                            fill_optional_fields: matches!(
+                                fill_type,
+                                DocumentFieldFillType::FillIfNotRequired
+                            ),
+                            frequency,
+                        }
+                    }
+                    DocumentAction::DocumentActionDelete => OperationDefinition::DocumentDelete {
+                        contract,
+                        document_type,
+                        frequency,
+                    },
+                    action => {
+                        return Err(DefinitionError::Unsupported(format!(
+                            "document action {:?} has no definition",
+                            action
+                        )))
+                    }
+                }
+            }
+            OperationType::IdentityTopUp => OperationDefinition::IdentityTopUp { frequency },
+            OperationType::IdentityTransfer => OperationDefinition::IdentityTransfer { frequency },
+            OperationType::IdentityWithdrawal => {
+                OperationDefinition::IdentityWithdrawal { frequency }
+            }
+            OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateAddKeys(count)) => {
+                OperationDefinition::IdentityAddKeys {
+                    count: *count,
+                    frequency,
+                }
+            }
+            OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateDisableKey(count)) => {
+                OperationDefinition::IdentityDisableKeys {
+                    count: *count,
+                    frequency,
+                }
+            }
+            OperationType::ContractCreate(_, document_types) => {
+                OperationDefinition::ContractCreateRandom {
+                    document_types: document_types.end.saturating_sub(1),
+                    frequency,
+                }
+            }
+            OperationType::ContractUpdate(op) => match op.action {
+                DataContractUpdateAction::DataContractNewDocumentTypes(_) => {
+                    OperationDefinition::ContractUpdateNewDocumentTypes {
+                        contract: name_of(&op.contract)?,
+                        frequency,
+                    }
+                }
+                DataContractUpdateAction::DataContractNewOptionalFields(..) => {
+                    return Err(DefinitionError::Unsupported(
+                        "contract updates with random optional fields have no definition"
+                            .to_owned(),
+                    ))
+                }
+            },
+        })
+    }
+}
+
+/// [strategy_rng] stream of the operations, offset by their position.
+pub(crate) const OPERATIONS_RNG_STREAM: u64 = 0;
+/// [strategy_rng] stream of the start contract variants, offset by the
+/// position of the first variant.
+pub(super) const START_CONTRACTS_RNG_STREAM: u64 = 1 << 32;
+/// [strategy_rng] stream of the one-time keys of the asset locks of a run.
+pub(super) const ASSET_LOCKS_RNG_STREAM: u64 = 2 << 32;
+
+/// Generator of the random choices made while building a strategy, such as
+/// document type parameters or contract variant IDs. The choices follow the
+/// strategy seed if one is set, `stream` tells apart the parts of the strategy
+/// so that editing one doesn't change the choices of another.
+pub(crate) fn strategy_rng(seed: Option<u64>, stream: u64) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Random parameters for the document types of created or updated contracts.
+pub(crate) fn random_document_type_parameters(rng: &mut impl Rng) -> RandomDocumentTypeParameters {
+    let random_number1 = rng.gen_range(3..=50);
+    let random_number2 = rng.gen_range(3..=50);
+    let random_number3 = rng.gen::<i64>() - 1000000;
+
+    RandomDocumentTypeParameters {
+        new_fields_optional_count_range: 1..random_number1,
+        new_fields_required_count_range: 1..random_number2,
+        new_indexes_count_range: 1..rng.gen_range(2..=10),
+        field_weights: FieldTypeWeights {
+            string_weight: rng.gen_range(1..=100),
+            float_weight: rng.gen_range(1..=100),
+            integer_weight: rng.gen_range(1..=100),
+            date_weight: rng.gen_range(1..=100),
+            boolean_weight: rng.gen_range(1..=100),
+            byte_array_weight: rng.gen_range(1..=100),
+        },
+        field_bounds: FieldMinMaxBounds {
+            string_min_len: 1..10,
+            string_has_min_len_chance: rng.gen_range(0.01..=1.0),
+            string_max_len: 10..63,
+            string_has_max_len_chance: rng.gen_range(0.01..=1.0),
+            integer_min: 1..10,
+            integer_has_min_chance: rng.gen_range(0.01..=1.0),
+            integer_max: 10..10000,
+            integer_has_max_chance: rng.gen_range(0.01..=1.0),
+            float_min: 0.1..10.0,
+            float_has_min_chance: rng.gen_range(0.01..=1.0),
+            float_max: 10.0..1000.0,
+            float_has_max_chance: rng.gen_range(0.01..=1.0),
+            date_min: random_number3,
+            date_max: random_number3 + 1000000,
+            byte_array_min_len: 1..10,
+            byte_array_has_min_len_chance: rng.gen_range(0.01..=1.0),
+            byte_array_max_len: 10..255,
+            byte_array_has_max_len_chance: rng.gen_range(0.01..=1.0),
+        },
+        keep_history_chance: rng.gen_range(0.01..=1.0),
+        documents_mutable_chance: rng.gen_range(0.01..=1.0),
+        documents_can_be_deleted_chance: rng.gen_range(0.01..=1.0),
+    }
+}
+
+/// Line and column, both counted from 1, of the byte at `offset`.
+fn position_of(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+type SpannedTable = Spanned<BTreeMap<String, Spanned<toml::Value>>>;
+
+/// Spans of the items of a TOML definition, serde doesn't keep them for the
+/// definition itself since JSON has none.
+#[derive(Deserialize)]
+struct TomlSpans {
+    #[serde(default)]
+    start_identities: Option<SpannedTable>,
+    #[serde(default)]
+    identity_inserts: Option<SpannedTable>,
+    #[serde(default)]
+    start_contracts: Vec<SpannedTable>,
+    #[serde(default)]
+    operations: Vec<SpannedTable>,
+}
+
+impl TomlSpans {
+    /// Span of the field or table of `location`, falls back to its item.
+    fn span(&self, location: &Location) -> Option<Range<usize>> {
+        let item = match (location.section, location.index) {
+            ("start_identities", None) => self.start_identities.as_ref(),
+            ("identity_inserts", None) => self.identity_inserts.as_ref(),
+            ("start_contracts", Some(index)) => self.start_contracts.get(index),
+            ("operations", Some(index)) => self.operations.get(index),
+            _ => None,
+        }?;
+        let field = location
+            .table
+            .or(location.field)
+            .and_then(|key| item.get_ref().get(key));
+
+        Some(field.map_or_else(|| item.span(), Spanned::span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/supporting_files/strategy_definitions/example.toml"
+    );
+
+    #[test]
+    fn parses_the_example() {
+        let source = fs::read_to_string(EXAMPLE).expect("example definition");
+        let definition = StrategyDefinition::parse(&source, Format::Toml).expect("valid example");
+
+        assert_eq!(definition.operations.len(), 3);
+        let frequency = definition.operations[0].frequency();
+        assert_eq!(frequency.times_per_block, 2);
+        assert_eq!(frequency.max_times_per_block, Some(5));
+        assert!(matches!(
+            definition.operations[1],
+            OperationDefinition::IdentityTopUp { .. }
+        ));
+        assert_eq!(definition.operations[1].frequency().times_per_block, 1);
+    }
+
+    #[test]
+    fn rejects_unknown_operation_fields() {
+        let toml_source = "[[operations]]\ntype = \"identity_top_up\"\ntimes_per_block = 2\n";
+        let problem = StrategyDefinition::parse(toml_source, Format::Toml)
+            .expect_err("unknown field in TOML");
+        assert!(problem.message.contains("times_per_block"));
+        assert!(problem.position.is_some());
+
+        let json_source = "{\n  \"operations\": [\n    { \"type\": \"identity_top_up\", \
+                           \"frequency\": { \"times\": 2 } }\n  ]\n}";
+        let problem = StrategyDefinition::parse(json_source, Format::Json)
+            .expect_err("unknown field in JSON");
+        assert!(problem.message.contains("times"));
+        assert_eq!(problem.position.map(|(line, _)| line), Some(3));
+    }
+
+    #[test]
+    fn locates_problems_in_toml() {
+        let path =
+            std::env::temp_dir().join(format!("explorer-definition-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[[operations]]\ntype = \"identity_top_up\"\n\n[[operations]]\n\
+             type = \"contract_update_new_document_types\"\ncontract = \"missing\"\n\
+             frequency = { times_per_block = 2, max_times_per_block = 1 }\n",
+        )
+        .expect("written definition");
+
+        let result = StrategyDefinition::load(&path, |_| None, PlatformVersion::latest());
+        fs::remove_file(&path).expect("removed definition");
+
+        let Err(DefinitionError::Invalid { problems, .. }) = result else {
+            panic!("expected an invalid definition");
+        };
+        let position_of_problem = |prefix: &str| {
+            problems
+                .iter()
+                .find(|problem| problem.message.starts_with(prefix))
+                .unwrap_or_else(|| panic!("no problem with {}", prefix))
+                .position
+                .map(|(line, _)| line)
+        };
+        assert_eq!(position_of_problem("operations[1].contract"), Some(6));
+        assert_eq!(
+            position_of_problem("operations[1].frequency.max_times_per_block"),
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,447 @@
+//! Dry runs of strategies.
+//!
+//! State transitions are generated block by block against the local Drive
+//! instance exactly like a real run would, but nothing is broadcasted: the
+//! run only reports what would have been sent.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use dash_sdk::{platform::transition::put_settings::PutSettings, Sdk};
+use dpp::{
+    identity::accessors::IdentityGettersV0,
+    platform_value::{string_encoding::Encoding, Identifier},
+    serialization::PlatformSerializable,
+    state_transition::{StateTransition, StateTransitionLike},
+};
+use drive::drive::identity::key::fetch::IdentityKeysRequest;
+use futures::{future::join_all, FutureExt};
+use rand::{rngs::StdRng, SeedableRng};
+use rs_dapi_client::RequestSettings;
+use serde::Serialize;
+use strategy_tests::{operations::FinalizeBlockOperation, LocalDocumentQuery, StrategyConfig};
+use tokio_util::sync::CancellationToken;
+
+use super::{asset_lock_proofs_needed, current_block_info, keys, local_documents, local_identity};
+use crate::backend::AppState;
+
+/// Storage fee in credits per byte of a serialized state transition, used for
+/// a rough estimate: processing fees are not accounted for.
+const STORAGE_CREDITS_PER_BYTE: u64 = 27_000 + 400;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub strategy_name: String,
+    /// Seed of the random generation
+    pub seed: u64,
+    /// Number of blocks generated, the two initialization blocks included
+    pub blocks: u64,
+    /// Unset if the dry run was stopped or generation failed
+    pub completed: bool,
+    pub transitions_by_type: BTreeMap<String, DryRunTransitionSummary>,
+    pub transition_count: u64,
+    /// Storage fees estimated from the serialized size of all transitions
+    pub estimated_fees: u64,
+    pub nonces: Vec<NonceUsage>,
+    /// Identity creates and top ups need asset lock proofs which a dry run
+    /// doesn't create, so these transitions are not generated
+    pub asset_lock_proofs_needed: u64,
+    pub errors: Vec<DryRunError>,
+    /// Directory the serialized transitions were written to
+    pub dump_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DryRunTransitionSummary {
+    pub count: u64,
+    pub bytes: u64,
+    pub estimated_fees: u64,
+}
+
+/// Nonces consumed by an identity, per contract for contract bound nonces.
+#[derive(Debug, Clone, Serialize)]
+pub struct NonceUsage {
+    pub identity: String,
+    pub contract: Option<String>,
+    pub first: u64,
+    pub last: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunError {
+    /// Index of the block, the first two are initialization blocks
+    pub block: u64,
+    pub error: String,
+}
+
+impl Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Dry run of strategy '{}' {} after {} blocks (2 initialization blocks included)",
+            self.strategy_name,
+            if self.completed {
+                "completed"
+            } else {
+                "stopped"
+            },
+            self.blocks
+        )?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "State transitions: {}", self.transition_count)?;
+        for (transition_type, summary) in &self.transitions_by_type {
+            writeln!(
+                f,
+                "    {}: {} ({} bytes, ~{} credits)",
+                transition_type, summary.count, summary.bytes, summary.estimated_fees
+            )?;
+        }
+        writeln!(f, "Estimated storage fees: {} credits", self.estimated_fees)?;
+        if self.asset_lock_proofs_needed > 0 {
+            writeln!(
+                f,
+                "Asset lock proofs needed: {} (identity creates and top ups are not generated)",
+                self.asset_lock_proofs_needed
+            )?;
+        }
+        if !self.nonces.is_empty() {
+            writeln!(f, "Nonces used:")?;
+            for nonce in &self.nonces {
+                match &nonce.contract {
+                    Some(contract) => writeln!(
+                        f,
+                        "    {} on contract {}: {} to {}",
+                        nonce.identity, contract, nonce.first, nonce.last
+                    )?,
+                    None => writeln!(
+                        f,
+                        "    {}: {} to {}",
+                        nonce.identity, nonce.first, nonce.last
+                    )?,
+                }
+            }
+        }
+        if !self.errors.is_empty() {
+            writeln!(f, "Generation errors:")?;
+            for error in &self.errors {
+                writeln!(f, "    Block {}: {}", error.block, error.error)?;
+            }
+        }
+        if let Some(dump_dir) = &self.dump_dir {
+            writeln!(f, "State transitions written to {}", dump_dir.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates state transitions of the strategy for `blocks` blocks after the
+/// two initialization blocks without broadcasting them. Platform is only
+/// queried for the current block and nonces, the strategy and app state are
+/// left untouched.
+pub(super) async fn dry_run_strategy(
+    sdk: &Sdk,
+    app_state: &AppState,
+    strategy_name: &str,
+    blocks: u64,
+    dump_dir: Option<&Path>,
+    seed: u64,
+    stop: &CancellationToken,
+) -> Result<DryRunReport, String> {
+    let mut strategy = app_state
+        .available_strategies
+        .lock()
+        .await
+        .get(strategy_name)
+        .cloned()
+        .ok_or_else(|| format!("Strategy {} doesn't exist in app state", strategy_name))?;
+    let loaded_identity = app_state
+        .loaded_identity
+        .lock()
+        .await
+        .clone()
+        .ok_or_else(|| "No loaded identity".to_owned())?;
+    let operation_keys = app_state
+        .available_strategies_operation_keys
+        .lock()
+        .await
+        .get(strategy_name)
+        .cloned()
+        .unwrap_or_default();
+    let (mut signer, identity_view) = keys::preflight(
+        &loaded_identity,
+        &*app_state.identity_private_keys.lock().await,
+        &strategy.operations,
+        &operation_keys,
+    )
+    .map_err(|e| e.to_string())?;
+    let mut known_contracts = app_state.known_contracts.lock().await.clone();
+
+    if let Some(dump_dir) = dump_dir {
+        fs::create_dir_all(dump_dir)
+            .map_err(|e| format!("Unable to create {}: {}", dump_dir.display(), e))?;
+    }
+
+    let initial_block_info = current_block_info(sdk).await?;
+    let start_block_height = initial_block_info.height + 1;
+    let mut current_block_info = initial_block_info.clone();
+    current_block_info.height = start_block_height;
+
+    let mut errors = Vec::new();
+    let (mut identity_nonce_counter, mut contract_nonce_counter) =
+        fetch_nonces(sdk, loaded_identity.id(), &strategy, &mut errors).await;
+    let initial_identity_nonces = identity_nonce_counter.clone();
+    let initial_contract_nonces = contract_nonce_counter.clone();
+
+    let asset_lock_proofs_needed = asset_lock_proofs_needed(&strategy, blocks);
+
+    let mut current_identities = vec![identity_view];
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut transitions_by_type: BTreeMap<String, DryRunTransitionSummary> = BTreeMap::new();
+    let mut transition_count = 0;
+    let mut generated_blocks = 0;
+    let mut completed = true;
+
+    for index in 1..=blocks + 2 {
+        if stop.is_cancelled() {
+            tracing::info!("Stop requested, dry run stops at block {}", index);
+            completed = false;
+            break;
+        }
+        tracing::info!("Dry run of strategy '{}', block {}", strategy_name, index);
+
+        // Drive is only locked while a block is generated, so the rest of the
+        // app can use it in between
+        let generation = {
+            let drive_lock = app_state.drive.lock().await;
+            let mut document_query_callback =
+                |query: LocalDocumentQuery| local_documents(&drive_lock, query);
+            let mut identity_fetch_callback =
+                |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
+                    local_identity(&drive_lock, identifier, sdk.version())
+                };
+
+            // Generation panics on inconsistent strategies, that is reported
+            // rather than taking the app down
+            AssertUnwindSafe(strategy.state_transitions_for_block(
+                &mut document_query_callback,
+                &mut identity_fetch_callback,
+                &mut Vec::new(),
+                &current_block_info,
+                &mut current_identities,
+                &mut known_contracts,
+                &mut signer,
+                &mut identity_nonce_counter,
+                &mut contract_nonce_counter,
+                &mut rng,
+                &StrategyConfig {
+                    start_block_height,
+                    number_of_blocks: blocks,
+                },
+                sdk.version(),
+            ))
+            .catch_unwind()
+            .await
+        };
+        generated_blocks = index;
+
+        let (transitions, finalize_operations, mut new_identities) = match generation {
+            Ok(generated) => generated,
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_owned());
+                errors.push(DryRunError {
+                    block: index,
+                    error: format!("state transition generation panicked: {}", message),
+                });
+                completed = false;
+                break;
+            }
+        };
+
+        current_identities.append(&mut new_identities);
+        for operation in finalize_operations {
+            match operation {
+                FinalizeBlockOperation::IdentityAddKeys(identifier, keys) => {
+                    if let Some(identity) = current_identities
+                        .iter_mut()
+                        .find(|identity| identity.id() == identifier)
+                    {
+                        for key in keys {
+                            identity.add_public_key(key);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (transition_index, transition) in transitions.iter().enumerate() {
+            transition_count += 1;
+            let transition_type = transition.name().to_owned();
+            let bytes = match transition.serialize_to_bytes() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    errors.push(DryRunError {
+                        block: index,
+                        error: format!("unable to serialize {}: {}", transition_type, e),
+                    });
+                    continue;
+                }
+            };
+
+            let summary = transitions_by_type.entry(transition_type).or_default();
+            summary.count += 1;
+            summary.bytes += bytes.len() as u64;
+            summary.estimated_fees += bytes.len() as u64 * STORAGE_CREDITS_PER_BYTE;
+
+            if let Some(dump_dir) = dump_dir {
+                if let Err(e) =
+                    dump_transition(dump_dir, index, transition_index, transition, &bytes)
+                {
+                    errors.push(DryRunError {
+                        block: index,
+                        error: e,
+                    });
+                }
+            }
+        }
+
+        current_block_info.height += 1;
+        current_block_info.time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_millis() as u64;
+    }
+
+    let identity_nonces = identity_nonce_counter
+        .iter()
+        .map(|(identity_id, last)| NonceUsage {
+            identity: identity_id.to_string(Encoding::Base58),
+            contract: None,
+            first: initial_identity_nonces
+                .get(identity_id)
+                .copied()
+                .unwrap_or_default(),
+            last: *last,
+        });
+    let contract_nonces =
+        contract_nonce_counter
+            .iter()
+            .map(|((identity_id, contract_id), last)| NonceUsage {
+                identity: identity_id.to_string(Encoding::Base58),
+                contract: Some(contract_id.to_string(Encoding::Base58)),
+                first: initial_contract_nonces
+                    .get(&(*identity_id, *contract_id))
+                    .copied()
+                    .unwrap_or_default(),
+                last: *last,
+            });
+
+    Ok(DryRunReport {
+        strategy_name: strategy_name.to_owned(),
+        seed,
+        blocks: generated_blocks,
+        completed,
+        estimated_fees: transitions_by_type
+            .values()
+            .map(|summary| summary.estimated_fees)
+            .sum(),
+        transitions_by_type,
+        transition_count,
+        nonces: identity_nonces
+            .chain(contract_nonces)
+            .filter(|nonce| nonce.last != nonce.first)
+            .collect(),
+        asset_lock_proofs_needed,
+        errors,
+        dump_dir: dump_dir.map(Path::to_owned),
+    })
+}
+
+/// Current identity nonce and identity contract nonces of the contracts the
+/// strategy uses, nonces that can't be fetched start from zero.
+async fn fetch_nonces(
+    sdk: &Sdk,
+    identity_id: Identifier,
+    strategy: &strategy_tests::Strategy,
+    errors: &mut Vec<DryRunError>,
+) -> (
+    BTreeMap<Identifier, u64>,
+    BTreeMap<(Identifier, Identifier), u64>,
+) {
+    // Cached nonces are never used
+    let settings = || {
+        Some(PutSettings {
+            request_settings: RequestSettings::default(),
+            identity_nonce_stale_time_s: Some(0),
+            user_fee_increase: None,
+        })
+    };
+
+    let mut identity_nonces = BTreeMap::new();
+    match sdk.get_identity_nonce(identity_id, false, settings()).await {
+        Ok(nonce) => {
+            identity_nonces.insert(identity_id, nonce);
+        }
+        Err(e) => errors.push(DryRunError {
+            block: 0,
+            error: format!("unable to fetch identity nonce, starting from 0: {}", e),
+        }),
+    }
+
+    let contract_results = join_all(strategy.used_contract_ids().into_iter().map(
+        |contract_id| async move {
+            (
+                contract_id,
+                sdk.get_identity_contract_nonce(identity_id, contract_id, false, settings())
+                    .await,
+            )
+        },
+    ))
+    .await;
+
+    let mut contract_nonces = BTreeMap::new();
+    for (contract_id, result) in contract_results {
+        match result {
+            Ok(nonce) => {
+                contract_nonces.insert((identity_id, contract_id), nonce);
+            }
+            Err(e) => errors.push(DryRunError {
+                block: 0,
+                error: format!(
+                    "unable to fetch identity contract nonce for {}, starting from 0: {}",
+                    contract_id.to_string(Encoding::Base58),
+                    e
+                ),
+            }),
+        }
+    }
+
+    (identity_nonces, contract_nonces)
+}
+
+/// Writes a serialized transition as hex, named by block, position and type.
+fn dump_transition(
+    dump_dir: &Path,
+    block: u64,
+    transition_index: usize,
+    transition: &StateTransition,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let path = dump_dir.join(format!(
+        "{:04}_{:03}_{}.hex",
+        block,
+        transition_index + 1,
+        transition.name()
+    ));
+    fs::write(&path, hex::encode(bytes))
+        .map_err(|e| format!("unable to write {}: {}", path.display(), e))
+}
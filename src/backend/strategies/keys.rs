@@ -0,0 +1,193 @@
+//! Keys of the loaded identity the strategy operations are signed with.
+//!
+//! Operations pick the first key of the identity matching their purpose and
+//! security level, so the run hands them an identity holding only the keys
+//! assigned to the operations. Assignments are resolved before anything is
+//! broadcasted, missing private keys are reported all at once.
+
+use std::fmt;
+
+use bincode::{Decode, Encode};
+use dpp::{
+    data_contract::document_type::accessors::DocumentTypeV0Getters,
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, Identity,
+        IdentityPublicKey, KeyID, Purpose, SecurityLevel,
+    },
+};
+use serde::{Deserialize, Serialize};
+use simple_signer::signer::SimpleSigner;
+use strategy_tests::operations::{Operation, OperationType};
+
+use crate::backend::state::IdentityPrivateKeysMap;
+
+/// Key assigned to a strategy operation.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKey {
+    /// Specific key of the loaded identity
+    Id(KeyID),
+    /// First key of the loaded identity with this purpose and security level
+    Policy {
+        purpose: Purpose,
+        security_level: SecurityLevel,
+    },
+}
+
+impl fmt::Display for OperationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationKey::Id(key_id) => write!(f, "key {}", key_id),
+            OperationKey::Policy {
+                purpose,
+                security_level,
+            } => write!(f, "{} {} key", security_level, purpose),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("operations can't be signed with the loaded identity:\n{}", .0.join("\n"))]
+pub struct KeyPreflightError(pub Vec<String>);
+
+/// Purpose and security levels of the key an operation is signed with, `None`
+/// if it isn't signed with a key of the loaded identity.
+fn key_requirement(op_type: &OperationType) -> Option<(Purpose, Vec<SecurityLevel>)> {
+    let up_to = |weakest: SecurityLevel| {
+        [
+            SecurityLevel::CRITICAL,
+            SecurityLevel::HIGH,
+            SecurityLevel::MEDIUM,
+        ]
+        .into_iter()
+        .filter(|level| *level <= weakest)
+        .collect()
+    };
+
+    match op_type {
+        OperationType::Document(op) => Some((
+            Purpose::AUTHENTICATION,
+            up_to(op.document_type.security_level_requirement()),
+        )),
+        OperationType::IdentityUpdate(_) => {
+            Some((Purpose::AUTHENTICATION, vec![SecurityLevel::MASTER]))
+        }
+        OperationType::IdentityTransfer | OperationType::IdentityWithdrawal => {
+            Some((Purpose::TRANSFER, vec![SecurityLevel::CRITICAL]))
+        }
+        OperationType::ContractCreate(..) | OperationType::ContractUpdate(_) => Some((
+            Purpose::AUTHENTICATION,
+            vec![SecurityLevel::CRITICAL, SecurityLevel::HIGH],
+        )),
+        OperationType::IdentityTopUp => None,
+    }
+}
+
+fn matches(key: &IdentityPublicKey, purpose: Purpose, security_levels: &[SecurityLevel]) -> bool {
+    key.disabled_at().is_none()
+        && key.purpose() == purpose
+        && security_levels.contains(&key.security_level())
+}
+
+/// Resolves the key of every operation and returns the signer and the view of
+/// the loaded identity to run the strategy with. Operations without an
+/// assigned key use the first key matching their requirement.
+pub(super) fn preflight(
+    identity: &Identity,
+    private_keys: &IdentityPrivateKeysMap,
+    operations: &[Operation],
+    operation_keys: &[Option<OperationKey>],
+) -> Result<(SimpleSigner, Identity), KeyPreflightError> {
+    let public_keys = identity.public_keys();
+    let mut problems = Vec::new();
+    let mut resolved = Vec::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        let Some((purpose, security_levels)) = key_requirement(&operation.op_type) else {
+            continue;
+        };
+        let assigned = operation_keys.get(index).copied().flatten();
+        let key = match assigned {
+            Some(OperationKey::Id(key_id)) => public_keys.get(&key_id),
+            Some(OperationKey::Policy {
+                purpose,
+                security_level,
+            }) => public_keys
+                .values()
+                .find(|key| matches(key, purpose, &[security_level])),
+            None => public_keys
+                .values()
+                .find(|key| matches(key, purpose, &security_levels)),
+        };
+
+        let operation_name = format!("operation {}", index + 1);
+        let Some(key) = key else {
+            problems.push(match assigned {
+                Some(assigned) => format!("{}: the identity has no {}", operation_name, assigned),
+                None => format!(
+                    "{}: the identity has no {} key of security level {:?}",
+                    operation_name, purpose, security_levels
+                ),
+            });
+            continue;
+        };
+        if !matches(key, purpose, &security_levels) {
+            problems.push(format!(
+                "{}: key {} ({} {}) can't sign it",
+                operation_name,
+                key.id(),
+                key.security_level(),
+                key.purpose()
+            ));
+            continue;
+        }
+        if !private_keys.contains_key(&(identity.id(), key.id())) {
+            problems.push(format!(
+                "{}: private key of key {} is missing",
+                operation_name,
+                key.id()
+            ));
+            continue;
+        }
+        resolved.push((index, purpose, security_levels, key.clone()));
+    }
+
+    // Operations pick the first matching key of the identity view, it must be
+    // the resolved one
+    let mut view = identity.clone();
+    let Identity::V0(view_v0) = &mut view;
+    view_v0
+        .public_keys
+        .retain(|key_id, _| resolved.iter().any(|(.., key)| key.id() == *key_id));
+    for (index, purpose, security_levels, key) in &resolved {
+        if let Some(picked) = view_v0
+            .public_keys
+            .values()
+            .find(|candidate| matches(candidate, *purpose, security_levels))
+        {
+            if picked.id() != key.id() {
+                problems.push(format!(
+                    "operation {}: would be signed with key {} assigned to another operation \
+                     instead of key {}",
+                    index + 1,
+                    picked.id(),
+                    key.id()
+                ));
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(KeyPreflightError(problems));
+    }
+
+    let mut signer = SimpleSigner::default();
+    for (.., key) in resolved {
+        if let Some(private_key) = private_keys.get(&(identity.id(), key.id())) {
+            signer.private_keys.insert(key, private_key.clone());
+        }
+    }
+
+    Ok((signer, view))
+}
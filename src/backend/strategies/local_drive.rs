@@ -0,0 +1,166 @@
+//! Mirroring of confirmed strategy state transitions into the local Drive.
+//!
+//! Operations replacing or deleting documents pick them from the local Drive
+//! instance, so documents, contracts and identities created during a run are
+//! written there from the verified proofs of their state transitions.
+
+use std::collections::BTreeSet;
+
+use dpp::{
+    block::block_info::BlockInfo,
+    data_contract::{accessors::v0::DataContractV0Getters, DataContract},
+    document::DocumentV0Getters,
+    platform_value::{string_encoding::Encoding, Identifier},
+    state_transition::{
+        documents_batch_transition::{
+            document_base_transition::v0::v0_methods::DocumentBaseTransitionV0Methods,
+            document_transition::DocumentTransitionV0Methods, DocumentsBatchTransition,
+        },
+        proof_result::StateTransitionProofResult,
+        StateTransition,
+    },
+    version::PlatformVersion,
+    ProtocolError,
+};
+use drive::drive::{
+    object_size_info::{DocumentAndContractInfo, DocumentInfo, OwnedDocumentInfo},
+    Drive,
+};
+
+use crate::backend::state::KnownContractsMap;
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum LocalDriveError {
+    #[error("data contract {0} is not known")]
+    UnknownContract(String),
+    #[error("invalid document type: {0}")]
+    DocumentType(#[from] ProtocolError),
+    #[error("drive error: {0}")]
+    Drive(#[from] drive::error::Error),
+}
+
+/// Whether the proof of the transition has something to apply locally.
+pub(super) fn is_mirrored(transition: &StateTransition) -> bool {
+    matches!(
+        transition,
+        StateTransition::DocumentsBatch(_)
+            | StateTransition::IdentityCreate(_)
+            | StateTransition::DataContractCreate(_)
+            | StateTransition::DataContractUpdate(_)
+    )
+}
+
+/// Applies proof results to the local Drive, keeping track of the contracts
+/// already written there during the run.
+#[derive(Default)]
+pub(super) struct LocalDriveSync {
+    applied_contracts: BTreeSet<Identifier>,
+}
+
+impl LocalDriveSync {
+    pub(super) fn apply(
+        &mut self,
+        drive: &Drive,
+        transition: &StateTransition,
+        proof_result: StateTransitionProofResult,
+        known_contracts: &KnownContractsMap,
+        block_info: &BlockInfo,
+        platform_version: &PlatformVersion,
+    ) -> Result<(), LocalDriveError> {
+        match proof_result {
+            StateTransitionProofResult::VerifiedDataContract(contract) => {
+                drive.apply_contract(&contract, *block_info, true, None, None, platform_version)?;
+                self.applied_contracts.insert(contract.id());
+            }
+            StateTransitionProofResult::VerifiedIdentity(identity) => {
+                drive.add_new_identity(
+                    identity,
+                    false,
+                    block_info,
+                    true,
+                    None,
+                    platform_version,
+                )?;
+            }
+            StateTransitionProofResult::VerifiedDocuments(documents) => {
+                let StateTransition::DocumentsBatch(DocumentsBatchTransition::V0(batch)) =
+                    transition
+                else {
+                    return Ok(());
+                };
+                for document_transition in &batch.transitions {
+                    let base = document_transition.base();
+                    let Some(document) = documents.get(&base.id()) else {
+                        continue;
+                    };
+                    let contract = self.contract(
+                        drive,
+                        base.data_contract_id(),
+                        known_contracts,
+                        block_info,
+                        platform_version,
+                    )?;
+                    match document {
+                        // Created or replaced
+                        Some(document) => {
+                            let document_type =
+                                contract.document_type_for_name(base.document_type_name())?;
+                            drive.add_document_for_contract(
+                                DocumentAndContractInfo {
+                                    owned_document_info: OwnedDocumentInfo {
+                                        document_info: DocumentInfo::DocumentRefInfo((
+                                            document, None,
+                                        )),
+                                        owner_id: Some(document.owner_id().to_buffer()),
+                                    },
+                                    contract,
+                                    document_type,
+                                },
+                                true,
+                                *block_info,
+                                true,
+                                None,
+                                platform_version,
+                            )?;
+                        }
+                        // Deleted
+                        None => {
+                            drive.delete_document_for_contract(
+                                base.id(),
+                                contract,
+                                base.document_type_name(),
+                                *block_info,
+                                true,
+                                None,
+                                platform_version,
+                            )?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Looks up a known contract, writing it to the local Drive first if the
+    /// run didn't do so yet, as documents can't be stored without it.
+    fn contract<'c>(
+        &mut self,
+        drive: &Drive,
+        id: Identifier,
+        known_contracts: &'c KnownContractsMap,
+        block_info: &BlockInfo,
+        platform_version: &PlatformVersion,
+    ) -> Result<&'c DataContract, LocalDriveError> {
+        let contract = known_contracts
+            .values()
+            .find(|contract| contract.id() == id)
+            .ok_or_else(|| LocalDriveError::UnknownContract(id.to_string(Encoding::Base58)))?;
+        if !self.applied_contracts.contains(&id) {
+            drive.apply_contract(contract, *block_info, true, None, None, platform_version)?;
+            self.applied_contracts.insert(id);
+        }
+        Ok(contract)
+    }
+}
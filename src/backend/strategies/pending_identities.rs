@@ -0,0 +1,169 @@
+//! Identities created during a strategy run are held back until their
+//! registration is confirmed, so later operations never reference identities
+//! that don't exist on Platform.
+//!
+//! Block mode waits for every result and settles registrations right away.
+//! Time mode only broadcasts, so the results of identity creates are awaited
+//! in the background and settled on the following seconds.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use dapi_grpc::platform::v0::wait_for_state_transition_result_response::{
+    self, wait_for_state_transition_result_response_v0,
+};
+use dash_sdk::{platform::transition::broadcast_request::BroadcastRequestForStateTransition, Sdk};
+use dpp::{
+    identity::{accessors::IdentityGettersV0, Identity},
+    platform_value::{string_encoding::Encoding, Identifier},
+    state_transition::{StateTransition, StateTransitionLike},
+};
+use rs_dapi_client::{DapiRequest, RequestSettings};
+use tokio::sync::mpsc;
+
+use super::report::IdentityRegistrations;
+
+/// ID of the identity registered by the transition, if it is an identity
+/// create.
+pub(super) fn created_identity_id(transition: &StateTransition) -> Option<Identifier> {
+    match transition {
+        StateTransition::IdentityCreate(identity_create_transition) => identity_create_transition
+            .modified_data_ids()
+            .first()
+            .copied(),
+        _ => None,
+    }
+}
+
+pub(super) struct PendingIdentities {
+    pending: BTreeMap<Identifier, Identity>,
+    registrations: IdentityRegistrations,
+    /// Registration results of the identity creates awaited in the
+    /// background, `None` if the result is unknown
+    results: (
+        mpsc::UnboundedSender<(Identifier, Option<bool>)>,
+        mpsc::UnboundedReceiver<(Identifier, Option<bool>)>,
+    ),
+    /// Number of identity creates awaited in the background
+    watched: usize,
+}
+
+impl Default for PendingIdentities {
+    fn default() -> Self {
+        PendingIdentities {
+            pending: BTreeMap::new(),
+            registrations: IdentityRegistrations::default(),
+            results: mpsc::unbounded_channel(),
+            watched: 0,
+        }
+    }
+}
+
+impl PendingIdentities {
+    pub(super) fn add(&mut self, identities: impl IntoIterator<Item = Identity>) {
+        self.pending.extend(
+            identities
+                .into_iter()
+                .map(|identity| (identity.id(), identity)),
+        );
+    }
+
+    /// Settles the registration of a pending identity, returning it if it was
+    /// registered so it can be used by the following blocks.
+    pub(super) fn settle(&mut self, id: Identifier, registered: bool) -> Option<Identity> {
+        let identity = self.pending.remove(&id)?;
+        if registered {
+            self.registrations.confirmed += 1;
+            Some(identity)
+        } else {
+            self.registrations.failed += 1;
+            None
+        }
+    }
+
+    /// Awaits the result of a broadcasted identity create in the background,
+    /// see [PendingIdentities::settle_watched]. The identity stays pending if
+    /// no result arrives.
+    pub(super) fn watch(&mut self, sdk: &Sdk, id: Identifier, transition: StateTransition) {
+        let Ok(wait_request) = transition.wait_for_state_transition_result_request() else {
+            tracing::error!(
+                "Failed to create wait request for the registration of identity {}",
+                id.to_string(Encoding::Base58)
+            );
+            return;
+        };
+
+        let sdk = sdk.clone();
+        let results = self.results.0.clone();
+        self.watched += 1;
+        tokio::spawn(async move {
+            let registered =
+                match wait_request.execute(&sdk, RequestSettings::default()).await {
+                    Ok(response) => match response.version {
+                        Some(wait_for_state_transition_result_response::Version::V0(
+                            v0_response,
+                        )) if v0_response.metadata.is_some() => Some(!matches!(
+                            v0_response.result,
+                            Some(wait_for_state_transition_result_response_v0::Result::Error(
+                                _
+                            ))
+                        )),
+                        _ => None,
+                    },
+                    Err(e) => {
+                        tracing::error!(
+                            "Wait result error for the registration of identity {}: {:?}",
+                            id.to_string(Encoding::Base58),
+                            e
+                        );
+                        None
+                    }
+                };
+            let _ = results.send((id, registered));
+        });
+    }
+
+    /// Settles the watched registrations whose results arrived, returning the
+    /// registered identities.
+    pub(super) fn settle_watched(&mut self) -> Vec<Identity> {
+        let mut registered_identities = Vec::new();
+        while let Ok(result) = self.results.1.try_recv() {
+            registered_identities.extend(self.settle_watched_result(result));
+        }
+        registered_identities
+    }
+
+    /// Waits at most `timeout` for the results of the watched registrations
+    /// and settles them, returning the registered identities.
+    pub(super) async fn finish_watched(&mut self, timeout: Duration) -> Vec<Identity> {
+        let mut registered_identities = self.settle_watched();
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.watched > 0 {
+            match tokio::time::timeout_at(deadline, self.results.1.recv()).await {
+                Ok(Some(result)) => {
+                    registered_identities.extend(self.settle_watched_result(result))
+                }
+                _ => break,
+            }
+        }
+        registered_identities
+    }
+
+    fn settle_watched_result(
+        &mut self,
+        (id, registered): (Identifier, Option<bool>),
+    ) -> Option<Identity> {
+        self.watched -= 1;
+        // The registration may still have succeeded, so the identity stays
+        // pending
+        self.settle(id, registered?)
+    }
+
+    /// Tally of the registrations, identities still pending count as
+    /// unconfirmed.
+    pub(super) fn registrations(&self) -> IdentityRegistrations {
+        IdentityRegistrations {
+            unconfirmed: self.pending.len() as u64,
+            ..self.registrations
+        }
+    }
+}
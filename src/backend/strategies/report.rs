@@ -0,0 +1,324 @@
+//! Machine-readable reports of strategy runs.
+//!
+//! Every run is recorded block by block (or second by second in time mode)
+//! and written as JSON, or as CSV with one row per state transition if the
+//! file name ends with `.csv`.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use dpp::{
+    data_contract::accessors::v0::DataContractV0Getters, platform_value::string_encoding::Encoding,
+};
+use serde::Serialize;
+use strategy_tests::{
+    operations::{
+        DataContractUpdateAction::{DataContractNewDocumentTypes, DataContractNewOptionalFields},
+        OperationType,
+    },
+    Strategy,
+};
+
+/// Directory the reports of runs started from the TUI are written to.
+pub const REPORTS_DIR: &str = "supporting_files/strategy_reports";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    #[error("unable to write report {0}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("unable to serialize report: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyRunReport {
+    pub strategy_name: String,
+    /// Unix time in seconds the run started at
+    pub started_at: u64,
+    pub run: RunSettings,
+    pub strategy: StrategySummary,
+    pub start_block_height: u64,
+    pub end_block_height: u64,
+    pub completed: bool,
+    pub stop_reason: Option<String>,
+    pub blocks: Vec<BlockRecord>,
+    /// Transition counts by type over the whole run
+    pub transitions_by_type: BTreeMap<String, TransitionTypeSummary>,
+    /// IDs of identities the run attempted to create
+    pub created_identities: Vec<String>,
+    pub identity_registrations: IdentityRegistrations,
+    /// IDs of contracts the run attempted to create
+    pub created_contracts: Vec<String>,
+    /// Credits spent by the loaded identity, negative if it gained credits
+    pub identity_credits_spent: i64,
+    /// Duffs spent from the loaded wallet, negative if it gained duffs
+    pub wallet_duffs_spent: i64,
+}
+
+/// Outcomes of the identity registrations attempted by a run.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IdentityRegistrations {
+    /// Registered identities, used by the operations of later blocks
+    pub confirmed: u64,
+    pub failed: u64,
+    /// Broadcasted without a result, never used by later operations
+    pub unconfirmed: u64,
+}
+
+impl fmt::Display for IdentityRegistrations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} confirmed, {} failed, {} unconfirmed",
+            self.confirmed, self.failed, self.unconfirmed
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSettings {
+    pub block_mode: bool,
+    pub blocks_or_seconds: u64,
+    pub verify_proofs: bool,
+    /// Seed of the random generation, the same seed replays the same
+    /// operations
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategySummary {
+    pub start_identities: u16,
+    pub keys_per_identity: u8,
+    pub start_identities_balance: u64,
+    pub start_contracts: Vec<String>,
+    pub identity_inserts: FrequencySummary,
+    pub operations: Vec<OperationSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrequencySummary {
+    pub times_per_block_min: u16,
+    pub times_per_block_max: u16,
+    pub chance_per_block: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationSummary {
+    pub operation: String,
+    pub frequency: FrequencySummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockRecord {
+    /// Index of the block (or second), the first two are initialization
+    /// blocks
+    pub index: u64,
+    pub block_height: u64,
+    pub transitions: Vec<TransitionRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransitionRecord {
+    pub transition_type: String,
+    pub outcome: TransitionOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionOutcome {
+    /// Executed on Platform, the result was received
+    Confirmed,
+    /// Broadcasted without waiting for the result (time mode)
+    Broadcasted,
+    Failed,
+}
+
+impl TransitionOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransitionOutcome::Confirmed => "confirmed",
+            TransitionOutcome::Broadcasted => "broadcasted",
+            TransitionOutcome::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TransitionTypeSummary {
+    pub sent: u64,
+    pub confirmed: u64,
+    pub broadcasted: u64,
+    pub failed: u64,
+}
+
+impl From<&Strategy> for StrategySummary {
+    fn from(strategy: &Strategy) -> Self {
+        StrategySummary {
+            start_identities: strategy.start_identities.number_of_identities as u16,
+            keys_per_identity: strategy.start_identities.keys_per_identity,
+            start_identities_balance: strategy.start_identities.starting_balances,
+            start_contracts: strategy
+                .start_contracts
+                .iter()
+                .map(|(contract, _)| contract.data_contract().id().to_string(Encoding::Base58))
+                .collect(),
+            identity_inserts: FrequencySummary {
+                times_per_block_min: strategy
+                    .identity_inserts
+                    .frequency
+                    .times_per_block_range
+                    .start,
+                times_per_block_max: strategy
+                    .identity_inserts
+                    .frequency
+                    .times_per_block_range
+                    .end,
+                chance_per_block: strategy.identity_inserts.frequency.chance_per_block,
+            },
+            operations: strategy
+                .operations
+                .iter()
+                .map(|op| OperationSummary {
+                    operation: operation_name(&op.op_type),
+                    frequency: FrequencySummary {
+                        times_per_block_min: op.frequency.times_per_block_range.start,
+                        times_per_block_max: op.frequency.times_per_block_range.end,
+                        chance_per_block: op.frequency.chance_per_block,
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+fn operation_name(op_type: &OperationType) -> String {
+    match op_type {
+        OperationType::Document(op) => format!(
+            "Document({:?}): Contract: {}",
+            op.action,
+            op.contract.id().to_string(Encoding::Base58)
+        ),
+        OperationType::IdentityTopUp => "IdentityTopUp".to_string(),
+        OperationType::IdentityUpdate(op) => format!("IdentityUpdate({:?})", op),
+        OperationType::IdentityWithdrawal => "IdentityWithdrawal".to_string(),
+        OperationType::ContractCreate(..) => "ContractCreateRandom".to_string(),
+        OperationType::ContractUpdate(op) => {
+            let op_type = match op.action {
+                DataContractNewDocumentTypes(_) => "NewDocTypesRandom",
+                DataContractNewOptionalFields(..) => "NewFieldsRandom",
+            };
+            format!(
+                "ContractUpdate({}): Contract: {}",
+                op_type,
+                op.contract.id().to_string(Encoding::Base58)
+            )
+        }
+        OperationType::IdentityTransfer => "IdentityTransfer".to_string(),
+    }
+}
+
+impl BlockRecord {
+    pub(crate) fn new(index: u64, block_height: u64) -> Self {
+        BlockRecord {
+            index,
+            block_height,
+            transitions: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        transition_type: impl Into<String>,
+        outcome: Result<TransitionOutcome, String>,
+    ) {
+        let (outcome, error) = match outcome {
+            Ok(outcome) => (outcome, None),
+            Err(error) => (TransitionOutcome::Failed, Some(error)),
+        };
+        self.transitions.push(TransitionRecord {
+            transition_type: transition_type.into(),
+            outcome,
+            error,
+        });
+    }
+}
+
+impl StrategyRunReport {
+    /// Path of the report in [REPORTS_DIR], unique per strategy and start
+    /// time.
+    pub fn default_path(&self) -> PathBuf {
+        Path::new(REPORTS_DIR).join(format!("{}_{}.json", self.strategy_name, self.started_at))
+    }
+
+    /// Fills the per type totals from recorded blocks.
+    pub(crate) fn summarize(&mut self) {
+        self.transitions_by_type.clear();
+        for transition in self.blocks.iter().flat_map(|block| &block.transitions) {
+            let summary = self
+                .transitions_by_type
+                .entry(transition.transition_type.clone())
+                .or_default();
+            summary.sent += 1;
+            match transition.outcome {
+                TransitionOutcome::Confirmed => summary.confirmed += 1,
+                TransitionOutcome::Broadcasted => summary.broadcasted += 1,
+                TransitionOutcome::Failed => summary.failed += 1,
+            }
+        }
+    }
+
+    /// Writes the report as CSV if the path has a `csv` extension and as JSON
+    /// otherwise, creating missing parent directories.
+    pub fn write(&self, path: &Path) -> Result<(), ReportError> {
+        let io_error = |e| ReportError::Io(path.to_owned(), e);
+
+        let content = if path.extension().is_some_and(|extension| extension == "csv") {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let mut file = fs::File::create(path).map_err(io_error)?;
+        file.write_all(content.as_bytes()).map_err(io_error)
+    }
+
+    /// One row per state transition, run level data is left to the JSON
+    /// format.
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("strategy,index,block_height,transition_type,outcome,error\n");
+        for block in &self.blocks {
+            for transition in &block.transitions {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    csv_field(&self.strategy_name),
+                    block.index,
+                    block.block_height,
+                    csv_field(&transition.transition_type),
+                    transition.outcome.as_str(),
+                    csv_field(transition.error.as_deref().unwrap_or_default()),
+                ));
+            }
+        }
+        csv
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
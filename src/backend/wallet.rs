@@ -16,6 +16,7 @@ use dapi_grpc::core::v0::{
 };
 use dash_sdk::{RequestSettings, Sdk};
 use dpp::dashcore::{
+    bip32::{ChildNumber, DerivationPath, ExtendedPrivKey},
     hashes::Hash,
     psbt::serialize::Serialize,
     secp256k1::{Message, Secp256k1},
@@ -34,29 +35,56 @@ use crate::backend::insight::{InsightAPIClient, InsightError};
 #[derive(Debug, Clone, PartialEq)]
 pub enum WalletTask {
     AddByPrivateKey(String),
+    AddHDWallet(String),
     Refresh,
     CopyAddress,
     ClearLoadedWallet,
     SplitUTXOs(u32),
 }
 
+/// Never shows the keys a wallet is added with.
+impl std::fmt::Display for WalletTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletTask::AddByPrivateKey(_) => write!(f, "Add wallet by private key"),
+            WalletTask::AddHDWallet(_) => write!(f, "Add HD wallet"),
+            WalletTask::Refresh => write!(f, "Refresh wallet"),
+            WalletTask::CopyAddress => write!(f, "Copy wallet address"),
+            WalletTask::ClearLoadedWallet => write!(f, "Clear loaded wallet"),
+            WalletTask::SplitUTXOs(count) => write!(f, "Split wallet UTXOs into {}", count),
+        }
+    }
+}
+
+/// Loads a [SingleKeyWallet] from a hex or WIF encoded private key.
 pub async fn add_wallet_by_private_key<'s>(
     wallet_state: &'s Mutex<Option<Wallet>>,
     private_key: &String,
-) {
-    let private_key = if private_key.len() == 64 {
+    network: Network,
+) -> Result<(), WalletError> {
+    let mut private_key = if private_key.len() == 64 {
         // hex
-        let bytes = hex::decode(private_key).expect("expected hex"); // TODO error hadling
-        PrivateKey::from_slice(bytes.as_slice(), Network::Testnet).expect("expected private key")
+        let bytes = hex::decode(private_key)
+            .map_err(|e| WalletError::Key(format!("invalid hex: {}", e)))?;
+        PrivateKey::from_slice(bytes.as_slice(), network)
+            .map_err(|e| WalletError::Key(e.to_string()))?
     } else {
-        PrivateKey::from_wif(private_key.as_str()).expect("expected WIF key")
-        // TODO error handling
+        let private_key = PrivateKey::from_wif(private_key.as_str())
+            .map_err(|e| WalletError::Key(e.to_string()))?;
+        if (private_key.network == Network::Dash) != (network == Network::Dash) {
+            return Err(WalletError::Key(format!(
+                "WIF key is for {}, but {} is configured",
+                private_key.network, network
+            )));
+        }
+        private_key
     };
+    // WIF doesn't distinguish between testnet, devnet and regtest
+    private_key.network = network;
 
     let secp = Secp256k1::new();
     let public_key = private_key.public_key(&secp);
-    // todo: make the network be part of state
-    let address = Address::p2pkh(&public_key, Network::Testnet);
+    let address = Address::p2pkh(&public_key, network);
     let wallet = Wallet::SingleKeyWallet(SingleKeyWallet {
         private_key,
         public_key,
@@ -66,6 +94,33 @@ pub async fn add_wallet_by_private_key<'s>(
 
     let mut wallet_guard = wallet_state.lock().await;
     *wallet_guard = Some(wallet);
+
+    Ok(())
+}
+
+/// Loads an [HDWallet] from either a BIP39 mnemonic or an extended private key
+/// and scans its chains for funds.
+pub async fn add_hd_wallet<'s>(
+    wallet_state: &'s Mutex<Option<Wallet>>,
+    mnemonic_or_xprv: &str,
+    insight: &InsightAPIClient,
+    network: Network,
+) -> Result<(), WalletError> {
+    let mut wallet = if mnemonic_or_xprv.starts_with("xprv") || mnemonic_or_xprv.starts_with("tprv")
+    {
+        HDWallet::from_xprv(mnemonic_or_xprv, network)?
+    } else {
+        HDWallet::from_mnemonic(mnemonic_or_xprv, network)?
+    };
+
+    if let Err(err) = wallet.scan(insight).await {
+        tracing::error!("Failed to scan HD wallet chains: {}", err);
+    }
+
+    let mut wallet_guard = wallet_state.lock().await;
+    *wallet_guard = Some(Wallet::HDWallet(wallet));
+
+    Ok(())
 }
 
 pub(super) async fn run_wallet_task<'s>(
@@ -73,10 +128,16 @@ pub(super) async fn run_wallet_task<'s>(
     wallet_state: &'s Mutex<Option<Wallet>>,
     task: WalletTask,
     insight: &'s InsightAPIClient,
+    network: Network,
 ) -> BackendEvent<'s> {
     match task {
         WalletTask::AddByPrivateKey(ref private_key) => {
-            add_wallet_by_private_key(&wallet_state, private_key).await;
+            if let Err(err) = add_wallet_by_private_key(&wallet_state, private_key, network).await {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(err.to_string()),
+                };
+            }
 
             let wallet_guard = wallet_state.lock().await;
             let loaded_wallet_update = MutexGuard::map(wallet_guard, |opt| {
@@ -89,6 +150,26 @@ pub(super) async fn run_wallet_task<'s>(
                 app_state_update: AppStateUpdate::LoadedWallet(loaded_wallet_update),
             }
         }
+        WalletTask::AddHDWallet(ref mnemonic_or_xprv) => {
+            if let Err(err) = add_hd_wallet(&wallet_state, mnemonic_or_xprv, insight, network).await
+            {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(err.to_string()),
+                };
+            }
+
+            let wallet_guard = wallet_state.lock().await;
+            let loaded_wallet_update = MutexGuard::map(wallet_guard, |opt| {
+                opt.as_mut().expect("wallet was set above")
+            });
+
+            BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(task),
+                execution_result: Ok("Added HD wallet".into()),
+                app_state_update: AppStateUpdate::LoadedWallet(loaded_wallet_update),
+            }
+        }
         WalletTask::Refresh => {
             let mut wallet_guard = wallet_state.lock().await;
             if let Some(wallet) = wallet_guard.deref_mut() {
@@ -158,6 +239,12 @@ pub(super) async fn run_wallet_task<'s>(
                             },
                         }
                     }
+                    Wallet::HDWallet(_) => BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(
+                            "Splitting UTXOs is not supported for HD wallets".into()
+                        ),
+                    },
                 }
             } else {
                 BackendEvent::TaskCompleted {
@@ -175,19 +262,32 @@ pub enum WalletError {
     Insight(InsightError),
     #[error("not enough balance")]
     Balance,
+    #[error("invalid wallet key: {0}")]
+    Key(String),
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
 pub enum Wallet {
     SingleKeyWallet(SingleKeyWallet),
+    HDWallet(HDWallet),
 }
 
 impl Wallet {
-    pub(crate) fn private_key_for_address(&self, address: &Address) -> &PrivateKey {
+    /// Key of one of the wallet addresses, `None` if the address isn't one of
+    /// them.
+    pub(crate) fn private_key_for_address(&self, address: &Address) -> Option<&PrivateKey> {
         match self {
             Wallet::SingleKeyWallet(single_wallet) => {
                 single_wallet.private_key_for_address(address)
             }
+            Wallet::HDWallet(hd_wallet) => hd_wallet.private_key_for_address(address),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => wallet.private_key.network,
+            Wallet::HDWallet(wallet) => wallet.network,
         }
     }
 
@@ -202,7 +302,7 @@ impl Wallet {
         };
         let fee = 10_000;
         let random_private_key: [u8; 32] = rng.gen();
-        let private_key = PrivateKey::from_slice(&random_private_key, Network::Testnet)
+        let private_key = PrivateKey::from_slice(&random_private_key, self.network())
             .expect("expected a private key");
 
         let secp = Secp256k1::new();
@@ -215,6 +315,9 @@ impl Wallet {
             .ok_or(WalletError::Balance)?;
 
         let change_address = self.change_address();
+        if let Wallet::HDWallet(hd_wallet) = self {
+            hd_wallet.advance_change_index();
+        }
 
         let payload_output = TxOut {
             value: amount,
@@ -282,38 +385,42 @@ impl Wallet {
         // Now we can drop the cache to end the immutable borrow
         drop(cache);
 
-        tx.input
-            .iter_mut()
-            .zip(sighashes.into_iter())
-            .for_each(|(input, sighash)| {
-                // You need to provide the actual script_pubkey of the UTXO being spent
-                let (_, public_key, input_address) = utxos
-                    .remove(&input.previous_output)
-                    .expect("expected a txout");
-                let message =
-                    Message::from_slice(sighash.as_byte_array()).expect("Error creating message");
-
-                let private_key = self.private_key_for_address(&input_address);
+        for (input, sighash) in tx.input.iter_mut().zip(sighashes.into_iter()) {
+            // You need to provide the actual script_pubkey of the UTXO being spent
+            let (_, public_key, input_address) = utxos
+                .remove(&input.previous_output)
+                .expect("expected a txout");
+            let message =
+                Message::from_slice(sighash.as_byte_array()).expect("Error creating message");
+
+            let input_private_key =
+                self.private_key_for_address(&input_address)
+                    .ok_or_else(|| {
+                        WalletError::Key(format!(
+                            "no private key for input address {}",
+                            input_address
+                        ))
+                    })?;
 
-                // Sign the message with the private key
-                let sig = secp.sign_ecdsa(&message, &private_key.inner);
+            // Sign the message with the private key
+            let sig = secp.sign_ecdsa(&message, &input_private_key.inner);
 
-                // Serialize the DER-encoded signature and append the sighash type
-                let mut serialized_sig = sig.serialize_der().to_vec();
+            // Serialize the DER-encoded signature and append the sighash type
+            let mut serialized_sig = sig.serialize_der().to_vec();
 
-                let mut sig_script = vec![serialized_sig.len() as u8 + 1];
+            let mut sig_script = vec![serialized_sig.len() as u8 + 1];
 
-                sig_script.append(&mut serialized_sig);
+            sig_script.append(&mut serialized_sig);
 
-                sig_script.push(1);
+            sig_script.push(1);
 
-                let mut serialized_pub_key = public_key.serialize();
+            let mut serialized_pub_key = public_key.serialize();
 
-                sig_script.push(serialized_pub_key.len() as u8);
-                sig_script.append(&mut serialized_pub_key);
-                // Create script_sig
-                input.script_sig = ScriptBuf::from_bytes(sig_script);
-            });
+            sig_script.push(serialized_pub_key.len() as u8);
+            sig_script.append(&mut serialized_pub_key);
+            // Create script_sig
+            input.script_sig = ScriptBuf::from_bytes(sig_script);
+        }
 
         Ok((tx, private_key))
     }
@@ -321,12 +428,14 @@ impl Wallet {
     pub fn receive_address(&self) -> Address {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.receive_address(),
+            Wallet::HDWallet(wallet) => wallet.receive_address(),
         }
     }
 
     pub fn change_address(&self) -> Address {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.change_address(),
+            Wallet::HDWallet(wallet) => wallet.change_address(),
         }
     }
 
@@ -340,18 +449,35 @@ impl Wallet {
                     wallet.balance_dash_formatted()
                 )
             }
+            Wallet::HDWallet(wallet) => {
+                format!(
+                    "HD Wallet \nreceive address: {} \nchange address: {} \nbalance: {}",
+                    wallet.receive_address(),
+                    wallet.change_address(),
+                    wallet.balance_dash_formatted()
+                )
+            }
         }
     }
 
     pub fn balance_dash_formatted(&self) -> String {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.balance_dash_formatted(),
+            Wallet::HDWallet(wallet) => wallet.balance_dash_formatted(),
         }
     }
 
     pub fn balance(&self) -> u64 {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.balance(),
+            Wallet::HDWallet(wallet) => wallet.balance(),
+        }
+    }
+
+    pub fn utxos_count(&self) -> usize {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => wallet.utxos.len(),
+            Wallet::HDWallet(wallet) => wallet.utxos.len(),
         }
     }
 
@@ -361,6 +487,7 @@ impl Wallet {
     ) -> Option<(BTreeMap<OutPoint, (TxOut, PublicKey, Address)>, u64)> {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.take_unspent_utxos_for(amount),
+            Wallet::HDWallet(wallet) => wallet.take_unspent_utxos_for(amount),
         }
     }
 
@@ -381,6 +508,7 @@ impl Wallet {
                     Err(err) => Err(err),
                 }
             }
+            Wallet::HDWallet(wallet) => wallet.scan(insight).await,
         }
     }
 }
@@ -407,6 +535,7 @@ impl Clone for SingleKeyWallet {
 impl Encode for SingleKeyWallet {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.private_key.inner.as_ref().encode(encoder)?;
+        self.private_key.network.to_string().encode(encoder)?;
         let string_utxos = self
             .utxos
             .iter()
@@ -425,58 +554,51 @@ impl Encode for SingleKeyWallet {
 impl Decode for SingleKeyWallet {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         let bytes = <[u8; 32]>::decode(decoder)?;
+        let network = String::decode(decoder)?;
         let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
 
-        let private_key = PrivateKey::from_slice(bytes.as_slice(), Network::Testnet)
-            .expect("expected private key");
+        let network = Network::from_str(&network)
+            .map_err(|e| DecodeError::OtherString(format!("invalid wallet network: {}", e)))?;
 
-        let secp = Secp256k1::new();
-        let public_key = private_key.public_key(&secp);
-        // todo: make the network be part of state
-        let address = Address::p2pkh(&public_key, Network::Testnet);
-
-        let utxos = string_utxos
-            .iter()
-            .map(|(outpoint, value, script)| {
-                let script = ScriptBuf::from_hex(script)
-                    .map_err(|_| {
-                        InsightError(format!(
-                            "Invalid scriptPubKey format from load of {}",
-                            script
-                        ))
-                    })
-                    .unwrap();
-                (
-                    OutPoint::from_str(outpoint).expect("expected valid outpoint"),
-                    TxOut {
-                        value: *value,
-                        script_pubkey: script,
-                    },
-                )
-            })
-            .collect::<HashMap<_, _>>();
-
-        Ok(SingleKeyWallet {
-            private_key,
-            public_key,
-            address,
-            utxos,
-        })
+        Ok(SingleKeyWallet::from_raw_parts(
+            bytes,
+            network,
+            string_utxos,
+        ))
     }
 }
 
 impl<'a> BorrowDecode<'a> for SingleKeyWallet {
     fn borrow_decode<D: BorrowDecoder<'a>>(decoder: &mut D) -> Result<Self, DecodeError> {
         let bytes = <[u8; 32]>::decode(decoder)?;
+        let network = String::decode(decoder)?;
         let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
 
-        let private_key = PrivateKey::from_slice(bytes.as_slice(), Network::Testnet)
-            .expect("expected private key");
+        let network = Network::from_str(&network)
+            .map_err(|e| DecodeError::OtherString(format!("invalid wallet network: {}", e)))?;
+
+        Ok(SingleKeyWallet::from_raw_parts(
+            bytes,
+            network,
+            string_utxos,
+        ))
+    }
+}
+
+impl SingleKeyWallet {
+    /// Rebuilds a wallet from its persisted parts: secret key bytes and UTXOs
+    /// as `(outpoint, value, script hex)` triples.
+    pub(crate) fn from_raw_parts(
+        private_key: [u8; 32],
+        network: Network,
+        string_utxos: Vec<(String, u64, String)>,
+    ) -> Self {
+        let private_key =
+            PrivateKey::from_slice(private_key.as_slice(), network).expect("expected private key");
 
         let secp = Secp256k1::new();
         let public_key = private_key.public_key(&secp);
-        // todo: make the network be part of state
-        let address = Address::p2pkh(&public_key, Network::Testnet);
+        let address = Address::p2pkh(&public_key, network);
 
         let utxos = string_utxos
             .iter()
@@ -499,16 +621,14 @@ impl<'a> BorrowDecode<'a> for SingleKeyWallet {
             })
             .collect::<HashMap<_, _>>();
 
-        Ok(SingleKeyWallet {
+        SingleKeyWallet {
             private_key,
             public_key,
             address,
             utxos,
-        })
+        }
     }
-}
 
-impl SingleKeyWallet {
     pub fn balance_dash_formatted(&self) -> String {
         let satoshis = self.balance();
         let dash = satoshis as f64 / 100_000_000f64;
@@ -743,10 +863,628 @@ impl SingleKeyWallet {
         self.address.clone()
     }
 
-    pub fn private_key_for_address(&self, address: &Address) -> &PrivateKey {
-        if &self.address != address {
-            panic!("address doesn't match");
+    pub fn private_key_for_address(&self, address: &Address) -> Option<&PrivateKey> {
+        (&self.address == address).then_some(&self.private_key)
+    }
+}
+
+/// Number of consecutive unused addresses after which chain scanning stops,
+/// as recommended by BIP44.
+const GAP_LIMIT: u32 = 20;
+
+/// An address derived from an [HDWallet] chain together with its keys.
+#[derive(Debug, Clone)]
+pub struct DerivedAddress {
+    pub index: u32,
+    pub private_key: PrivateKey,
+    pub public_key: PublicKey,
+    pub address: Address,
+}
+
+/// Hierarchical deterministic wallet following BIP44 (`m/44'/coin'/0'`),
+/// with separate receive (`/0/i`) and change (`/1/i`) chains.
+///
+/// Insight only reports unspent outputs, so an address is considered used if it
+/// holds or once held a known UTXO; indices never move backwards once used.
+#[derive(Debug, Clone)]
+pub struct HDWallet {
+    pub network: Network,
+    pub mnemonic: Option<String>,
+    pub master_key: ExtendedPrivKey,
+    pub account_key: ExtendedPrivKey,
+    pub receive_addresses: Vec<DerivedAddress>,
+    pub change_addresses: Vec<DerivedAddress>,
+    /// Index of the first unused receive address
+    pub receive_index: u32,
+    /// Index of the first unused change address
+    pub change_index: u32,
+    pub utxos: HashMap<OutPoint, TxOut>,
+}
+
+impl HDWallet {
+    pub fn from_mnemonic(phrase: &str, network: Network) -> Result<Self, WalletError> {
+        let mnemonic = bip39::Mnemonic::parse_normalized(phrase)
+            .map_err(|e| WalletError::Key(format!("invalid mnemonic: {}", e)))?;
+        let seed = mnemonic.to_seed("");
+        let master_key = ExtendedPrivKey::new_master(network, &seed)
+            .map_err(|e| WalletError::Key(e.to_string()))?;
+
+        Self::from_master_key(master_key, Some(mnemonic.to_string()), network)
+    }
+
+    /// Extended keys don't distinguish between testnet, devnet and regtest, so
+    /// the network is provided separately.
+    pub fn from_xprv(xprv: &str, network: Network) -> Result<Self, WalletError> {
+        let master_key =
+            ExtendedPrivKey::from_str(xprv).map_err(|e| WalletError::Key(e.to_string()))?;
+        if (master_key.network == Network::Dash) != (network == Network::Dash) {
+            return Err(WalletError::Key(format!(
+                "extended key is for {}, but {} is configured",
+                master_key.network, network
+            )));
         }
-        &self.private_key
+
+        Self::from_master_key(master_key, None, network)
+    }
+
+    fn from_master_key(
+        master_key: ExtendedPrivKey,
+        mnemonic: Option<String>,
+        network: Network,
+    ) -> Result<Self, WalletError> {
+        let coin_type = match network {
+            Network::Dash => 5,
+            _ => 1,
+        };
+        let account_path = DerivationPath::from_str(&format!("m/44'/{}'/0'", coin_type))
+            .expect("valid derivation path");
+        let secp = Secp256k1::new();
+        let account_key = master_key
+            .derive_priv(&secp, &account_path)
+            .map_err(|e| WalletError::Key(e.to_string()))?;
+
+        let mut wallet = HDWallet {
+            network,
+            mnemonic,
+            master_key,
+            account_key,
+            receive_addresses: Vec::new(),
+            change_addresses: Vec::new(),
+            receive_index: 0,
+            change_index: 0,
+            utxos: Default::default(),
+        };
+        wallet.derive_up_to(false, GAP_LIMIT);
+        wallet.derive_up_to(true, GAP_LIMIT);
+
+        Ok(wallet)
+    }
+
+    fn derive_address(&self, change: bool, index: u32) -> DerivedAddress {
+        let secp = Secp256k1::new();
+        let path = [
+            ChildNumber::from_normal_idx(change as u32).expect("valid chain index"),
+            ChildNumber::from_normal_idx(index).expect("valid address index"),
+        ];
+        let private_key = self
+            .account_key
+            .derive_priv(&secp, &path)
+            .expect("non-hardened derivation cannot fail")
+            .to_priv();
+        let public_key = private_key.public_key(&secp);
+        let address = Address::p2pkh(&public_key, self.network);
+
+        DerivedAddress {
+            index,
+            private_key,
+            public_key,
+            address,
+        }
+    }
+
+    fn chain(&self, change: bool) -> &Vec<DerivedAddress> {
+        if change {
+            &self.change_addresses
+        } else {
+            &self.receive_addresses
+        }
+    }
+
+    /// Makes sure the chain has at least `count` derived addresses.
+    fn derive_up_to(&mut self, change: bool, count: u32) {
+        let derived = self.chain(change).len() as u32;
+        let new_addresses = (derived..count)
+            .map(|index| self.derive_address(change, index))
+            .collect::<Vec<_>>();
+        if change {
+            self.change_addresses.extend(new_addresses);
+        } else {
+            self.receive_addresses.extend(new_addresses);
+        }
+    }
+
+    /// Moves to the next change address, used once a change output was spent
+    /// to the current one.
+    pub(crate) fn advance_change_index(&mut self) {
+        self.change_index += 1;
+        self.derive_up_to(true, self.change_index + GAP_LIMIT);
+    }
+
+    fn derived_address_for_script(&self, script: &ScriptBuf) -> Option<&DerivedAddress> {
+        self.receive_addresses
+            .iter()
+            .chain(self.change_addresses.iter())
+            .find(|derived| &derived.address.script_pubkey() == script)
+    }
+
+    /// Queries Insight for UTXOs of one chain, deriving addresses in windows of
+    /// [GAP_LIMIT] until a whole window has no funds.
+    async fn scan_chain(
+        &mut self,
+        change: bool,
+        insight: &InsightAPIClient,
+    ) -> Result<HashMap<OutPoint, TxOut>, InsightError> {
+        let mut chain_utxos = HashMap::new();
+        let mut next_unused = if change {
+            self.change_index
+        } else {
+            self.receive_index
+        };
+        let mut window_start = 0;
+
+        loop {
+            let window_end = window_start.max(next_unused) + GAP_LIMIT;
+            self.derive_up_to(change, window_end);
+
+            let window = &self.chain(change)[window_start as usize..window_end as usize];
+            let addresses = window.iter().map(|d| &d.address).collect::<Vec<_>>();
+            let utxos = insight.utxos_with_amount_for_addresses(&addresses).await?;
+
+            let mut window_used = false;
+            for txout in utxos.values() {
+                if let Some(derived) = window
+                    .iter()
+                    .find(|d| d.address.script_pubkey() == txout.script_pubkey)
+                {
+                    window_used = true;
+                    next_unused = next_unused.max(derived.index + 1);
+                }
+            }
+            chain_utxos.extend(utxos);
+
+            if !window_used {
+                break;
+            }
+            window_start = window_end;
+        }
+
+        if change {
+            self.change_index = next_unused;
+        } else {
+            self.receive_index = next_unused;
+        }
+        self.derive_up_to(change, next_unused + GAP_LIMIT);
+
+        Ok(chain_utxos)
+    }
+
+    /// Reloads UTXOs of both receive and change chains using gap limit
+    /// scanning.
+    pub async fn scan(
+        &mut self,
+        insight: &InsightAPIClient,
+    ) -> Result<HashMap<OutPoint, TxOut>, InsightError> {
+        let mut utxos = self.scan_chain(false, insight).await?;
+        utxos.extend(self.scan_chain(true, insight).await?);
+        self.utxos = utxos.clone();
+
+        Ok(utxos)
+    }
+
+    pub fn balance_dash_formatted(&self) -> String {
+        let satoshis = self.balance();
+        let dash = satoshis as f64 / 100_000_000f64;
+        format!("{:.4}", dash)
+    }
+
+    pub fn balance(&self) -> u64 {
+        self.utxos.iter().map(|(_, out)| out.value).sum()
+    }
+
+    pub fn take_unspent_utxos_for(
+        &mut self,
+        amount: u64,
+    ) -> Option<(BTreeMap<OutPoint, (TxOut, PublicKey, Address)>, u64)> {
+        let mut required: i64 = amount as i64;
+        let mut taken_utxos = BTreeMap::new();
+
+        for (outpoint, utxo) in self.utxos.iter() {
+            if required <= 0 {
+                break;
+            }
+            let Some(derived) = self.derived_address_for_script(&utxo.script_pubkey) else {
+                tracing::warn!("UTXO {} doesn't belong to any derived address", outpoint);
+                continue;
+            };
+            required -= utxo.value as i64;
+            taken_utxos.insert(
+                outpoint.clone(),
+                (utxo.clone(), derived.public_key, derived.address.clone()),
+            );
+        }
+
+        // If we didn't gather enough UTXOs to cover the required amount
+        if required > 0 {
+            return None;
+        }
+
+        // Remove taken UTXOs from the original list
+        for (outpoint, _) in &taken_utxos {
+            self.utxos.remove(outpoint);
+        }
+
+        Some((taken_utxos, required.abs() as u64))
+    }
+
+    pub fn receive_address(&self) -> Address {
+        self.receive_addresses[self.receive_index as usize]
+            .address
+            .clone()
+    }
+
+    pub fn change_address(&self) -> Address {
+        self.change_addresses[self.change_index as usize]
+            .address
+            .clone()
+    }
+
+    pub fn private_key_for_address(&self, address: &Address) -> Option<&PrivateKey> {
+        self.receive_addresses
+            .iter()
+            .chain(self.change_addresses.iter())
+            .find(|derived| &derived.address == address)
+            .map(|derived| &derived.private_key)
+    }
+}
+
+impl Encode for HDWallet {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.network.to_string().encode(encoder)?;
+        self.master_key.to_string().encode(encoder)?;
+        self.mnemonic.encode(encoder)?;
+        self.receive_index.encode(encoder)?;
+        self.change_index.encode(encoder)?;
+        (self.receive_addresses.len() as u32).encode(encoder)?;
+        (self.change_addresses.len() as u32).encode(encoder)?;
+        let string_utxos = self
+            .utxos
+            .iter()
+            .map(|(outpoint, txout)| {
+                (
+                    outpoint.to_string(),
+                    txout.value,
+                    hex::encode(txout.script_pubkey.as_bytes()),
+                )
+            })
+            .collect::<Vec<_>>();
+        string_utxos.encode(encoder)
+    }
+}
+
+impl Decode for HDWallet {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let network = String::decode(decoder)?;
+        let master_key = String::decode(decoder)?;
+        let mnemonic = Option::<String>::decode(decoder)?;
+        let receive_index = u32::decode(decoder)?;
+        let change_index = u32::decode(decoder)?;
+        let receive_count = u32::decode(decoder)?;
+        let change_count = u32::decode(decoder)?;
+        let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
+
+        let network = Network::from_str(&network)
+            .map_err(|e| DecodeError::OtherString(format!("invalid wallet network: {}", e)))?;
+        let master_key = ExtendedPrivKey::from_str(&master_key)
+            .map_err(|e| DecodeError::OtherString(format!("invalid xprv: {}", e)))?;
+        let mut wallet = HDWallet::from_master_key(master_key, mnemonic, network)
+            .map_err(|e| DecodeError::OtherString(e.to_string()))?;
+        wallet.receive_index = receive_index;
+        wallet.change_index = change_index;
+        wallet.derive_up_to(false, receive_count.max(receive_index + GAP_LIMIT));
+        wallet.derive_up_to(true, change_count.max(change_index + GAP_LIMIT));
+
+        wallet.utxos = string_utxos
+            .iter()
+            .map(|(outpoint, value, script)| {
+                let script = ScriptBuf::from_hex(script).map_err(|_| {
+                    DecodeError::OtherString(format!(
+                        "Invalid scriptPubKey format from load of {}",
+                        script
+                    ))
+                })?;
+                let outpoint = OutPoint::from_str(outpoint)
+                    .map_err(|e| DecodeError::OtherString(e.to_string()))?;
+                Ok((
+                    outpoint,
+                    TxOut {
+                        value: *value,
+                        script_pubkey: script,
+                    },
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, DecodeError>>()?;
+
+        Ok(wallet)
+    }
+}
+
+bincode::impl_borrow_decode!(HDWallet);
+
+#[cfg(test)]
+mod tests {
+    use dpp::dashcore::Txid;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// BIP39 test vector mnemonic, the expected addresses follow BIP44 with
+    /// coin type 1 on testnet and 5 on mainnet.
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+
+    fn testnet_wallet() -> HDWallet {
+        HDWallet::from_mnemonic(MNEMONIC, Network::Testnet).expect("valid mnemonic")
+    }
+
+    fn addresses(chain: &[DerivedAddress]) -> Vec<String> {
+        chain
+            .iter()
+            .take(2)
+            .map(|derived| derived.address.to_string())
+            .collect()
+    }
+
+    /// Serves Insight UTXO requests with the outputs of `funded` addresses.
+    async fn insight_stub(funded: Vec<(Address, OutPoint, TxOut)>) -> InsightAPIClient {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("listener");
+        let uri = format!("http://{}", listener.local_addr().expect("local address"));
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+                let body = loop {
+                    let read = stream.read(&mut buffer).await.expect("request");
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                        let content_length = headers
+                            .lines()
+                            .filter_map(|line| line.split_once(':'))
+                            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                            .unwrap_or_default();
+                        if body.len() >= content_length {
+                            break body.to_owned();
+                        }
+                    }
+                    assert!(read > 0, "incomplete request");
+                };
+
+                let utxos = body
+                    .trim_start_matches("addrs=")
+                    .split(',')
+                    .filter_map(|address| {
+                        funded
+                            .iter()
+                            .find(|(funded_address, ..)| funded_address.to_string() == address)
+                    })
+                    .map(|(_, outpoint, txout)| {
+                        serde_json::json!({
+                            "txid": outpoint.txid.to_string(),
+                            "vout": outpoint.vout,
+                            "satoshis": txout.value,
+                            "scriptPubKey": hex::encode(txout.script_pubkey.as_bytes()),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let json = serde_json::to_string(&utxos).expect("json");
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+                     {}\r\nConnection: close\r\n\r\n{}",
+                    json.len(),
+                    json
+                );
+                stream
+                    .write_all(response.as_bytes())
+                    .await
+                    .expect("response");
+            }
+        });
+
+        InsightAPIClient::new(uri.parse().expect("valid uri"))
+    }
+
+    #[test]
+    fn derives_bip44_testnet_addresses() {
+        let wallet = testnet_wallet();
+
+        assert_eq!(
+            addresses(&wallet.receive_addresses),
+            [
+                "yRd4FhXfVGHXpsuZXPNkMrfD9GVj46pnjt",
+                "yfd64jEpzzTLrHnR1wq3iiYXh68AiU8mcw"
+            ]
+        );
+        assert_eq!(
+            addresses(&wallet.change_addresses),
+            [
+                "yNwHG9D2rnrJRQ8j7TG6xnWfbr8D634HeM",
+                "yewnDakKiGUZqrReJqUanPt1rNH8uGWUL5"
+            ]
+        );
+        assert_eq!(wallet.receive_addresses.len(), GAP_LIMIT as usize);
+        assert_eq!(wallet.change_addresses.len(), GAP_LIMIT as usize);
+        assert_eq!(
+            wallet.receive_address().to_string(),
+            "yRd4FhXfVGHXpsuZXPNkMrfD9GVj46pnjt"
+        );
+        assert_eq!(
+            wallet.change_address().to_string(),
+            "yNwHG9D2rnrJRQ8j7TG6xnWfbr8D634HeM"
+        );
+    }
+
+    #[test]
+    fn derives_bip44_mainnet_addresses() {
+        let wallet = HDWallet::from_mnemonic(MNEMONIC, Network::Dash).expect("valid mnemonic");
+
+        assert_eq!(
+            addresses(&wallet.receive_addresses),
+            [
+                "XoJA8qE3N2Y3jMLEtZ3vcN42qseZ8LvFf5",
+                "XbctnEsgWTn5j1co3emZynemxSFPqkLRKZ"
+            ]
+        );
+        assert_eq!(
+            addresses(&wallet.change_addresses),
+            [
+                "XeBdurzVrhrFtgqf9SxzQqvhHodb53njW4",
+                "XnykfdCWrHENs8aH8X59RLZAn15VAP1cPB"
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_private_keys_of_derived_addresses() {
+        let hd_wallet = testnet_wallet();
+        let receive_address = hd_wallet.receive_addresses[1].address.clone();
+        let change_address = hd_wallet.change_addresses[1].address.clone();
+        let underived_address = hd_wallet.derive_address(false, GAP_LIMIT + 10).address;
+        let wallet = Wallet::HDWallet(hd_wallet);
+
+        assert_eq!(
+            wallet
+                .private_key_for_address(&receive_address)
+                .expect("receive key")
+                .to_wif(),
+            "cUATcNZMgKQn5vUYuVvKVnoQUKcvyJuZvHyHFHfoi5mm4E1T7Gs3"
+        );
+        assert_eq!(
+            wallet
+                .private_key_for_address(&change_address)
+                .expect("change key")
+                .to_wif(),
+            "cQKL28ckgwwLCxmbSgJEVoiRF5kSKzHZ6xvT6s2KsF94tXp4QaVH"
+        );
+        assert!(wallet.private_key_for_address(&underived_address).is_none());
+    }
+
+    #[tokio::test]
+    async fn scans_chains_up_to_the_gap_limit() {
+        let mut wallet = testnet_wallet();
+
+        let mut funded = Vec::new();
+        let mut fund = |change: bool, index: u32, value: u64| {
+            let address = wallet.derive_address(change, index).address;
+            let outpoint = OutPoint {
+                txid: Txid::from_hex(&format!("{:064x}", funded.len() + 1)).expect("txid"),
+                vout: 0,
+            };
+            let txout = TxOut {
+                value,
+                script_pubkey: address.script_pubkey(),
+            };
+            funded.push((address, outpoint, txout));
+        };
+        // Found in the first window, which makes the second one reach index 40
+        fund(false, 0, 1_000);
+        // Found in the second window
+        fund(false, 25, 2_000);
+        // Index 40 to 59 are unused, so this is past the gap limit
+        fund(false, 60, 4_000);
+        fund(true, 2, 8_000);
+
+        let insight = insight_stub(funded).await;
+        let utxos = wallet.scan(&insight).await.expect("scanned wallet");
+
+        assert_eq!(utxos.len(), 3);
+        assert_eq!(wallet.balance(), 11_000);
+        assert_eq!(wallet.receive_index, 26);
+        assert_eq!(wallet.change_index, 3);
+        assert_eq!(
+            wallet.receive_address(),
+            wallet.derive_address(false, 26).address
+        );
+        assert_eq!(
+            wallet.change_address(),
+            wallet.derive_address(true, 3).address
+        );
+        assert!(wallet.receive_addresses.len() >= (26 + GAP_LIMIT) as usize);
+        assert!(wallet.change_addresses.len() >= (3 + GAP_LIMIT) as usize);
+    }
+
+    #[test]
+    fn encoded_hd_wallet_decodes_to_the_same_wallet() {
+        let mut hd_wallet = testnet_wallet();
+        hd_wallet.receive_index = 3;
+        hd_wallet.advance_change_index();
+        let address = hd_wallet.receive_addresses[2].address.clone();
+        hd_wallet.utxos.insert(
+            OutPoint {
+                txid: Txid::from_hex(&format!("{:064x}", 1)).expect("txid"),
+                vout: 1,
+            },
+            TxOut {
+                value: 50_000,
+                script_pubkey: address.script_pubkey(),
+            },
+        );
+        let wallet = Wallet::HDWallet(hd_wallet);
+
+        let config = bincode::config::standard();
+        let bytes = bincode::encode_to_vec(&wallet, config).expect("encoded wallet");
+        let (decoded, read): (Wallet, usize) =
+            bincode::decode_from_slice(&bytes, config).expect("decoded wallet");
+        assert_eq!(read, bytes.len());
+
+        let (Wallet::HDWallet(original), Wallet::HDWallet(decoded)) = (&wallet, &decoded) else {
+            panic!("expected HD wallets");
+        };
+        assert_eq!(decoded.network, original.network);
+        assert_eq!(decoded.mnemonic, original.mnemonic);
+        assert_eq!(decoded.master_key, original.master_key);
+        assert_eq!(decoded.account_key, original.account_key);
+        assert_eq!(decoded.receive_index, original.receive_index);
+        assert_eq!(decoded.change_index, original.change_index);
+        assert_eq!(
+            decoded
+                .receive_addresses
+                .iter()
+                .map(|derived| &derived.address)
+                .collect::<Vec<_>>(),
+            original
+                .receive_addresses
+                .iter()
+                .map(|derived| &derived.address)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            decoded
+                .change_addresses
+                .iter()
+                .map(|derived| &derived.address)
+                .collect::<Vec<_>>(),
+            original
+                .change_addresses
+                .iter()
+                .map(|derived| &derived.address)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(decoded.utxos, original.utxos);
+        assert_eq!(decoded.balance(), 50_000);
     }
 }
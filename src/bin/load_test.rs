@@ -55,6 +55,11 @@ use rs_platform_explorer::{
         identities::IdentityTask, insight::InsightAPIClient, wallet::WalletTask, Backend, Task,
     },
     config::Config,
+    load_testing::{
+        errors::{endpoint_in, ErrorCategory, ErrorTally, UNKNOWN_ENDPOINT},
+        latency::Histogram,
+        schedule::{OpenLoopSchedule, RateProfile},
+    },
 };
 use simple_signer::signer::SimpleSigner;
 use tokio::{sync::Semaphore, time::Instant};
@@ -106,6 +111,15 @@ struct Args {
         default_value = "15"
     )]
     refill_amount: u64,
+
+    #[arg(long, help = "Name of the configuration profile to use.")]
+    profile: Option<String>,
+
+    #[arg(
+        long,
+        help = "Broadcasts documents open-loop following a rate profile instead of --rate and --connections, with latencies measured from the intended send times: constant:RATE, ramp:FROM..TO/SECS, step:RATE/SECS,... or spike:BASE,PEAK@START/SECS (documents per second)."
+    )]
+    schedule: Option<RateProfile>,
 }
 
 #[tokio::main]
@@ -144,7 +158,13 @@ async fn main() {
     }));
 
     // Load configuration
-    let config = Config::load();
+    let config = match Config::load(args.profile.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Setup Platform SDK
     let address_list = config.dapi_address_list();
@@ -162,7 +182,13 @@ async fn main() {
 
     let insight = InsightAPIClient::new(config.insight_api_uri());
 
-    let backend = Backend::new(&sdk, insight, config.clone()).await;
+    let backend = match Backend::new(&sdk, insight, config.clone()).await {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("Failed to load application state: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Create wallet if not initialized
     if backend.state().loaded_wallet.lock().await.is_none() {
@@ -302,6 +328,7 @@ async fn main() {
         Duration::from_secs(args.time.into()),
         args.connections,
         args.rate,
+        args.schedule,
     )
     .await;
 }
@@ -495,15 +522,24 @@ async fn broadcast_random_documents_load_test(
     duration: Duration,
     concurrent_requests: u16,
     rate_limit_per_sec: u32,
+    schedule: Option<RateProfile>,
 ) {
     let rate_limit_per_sec = NonZeroU32::new(rate_limit_per_sec).unwrap_or(NonZeroU32::MAX);
-    tracing::info!(
-        document_type = document_type.name(),
-        "broadcasting up to {} random documents per second in {} parallel threads for {} secs",
-        rate_limit_per_sec,
-        concurrent_requests,
-        duration.as_secs_f32()
-    );
+    match &schedule {
+        Some(profile) => tracing::info!(
+            document_type = document_type.name(),
+            "broadcasting random documents open-loop at {} for {} secs",
+            profile,
+            duration.as_secs_f32()
+        ),
+        None => tracing::info!(
+            document_type = document_type.name(),
+            "broadcasting up to {} random documents per second in {} parallel threads for {} secs",
+            rate_limit_per_sec,
+            concurrent_requests,
+            duration.as_secs_f32()
+        ),
+    }
 
     let identity_id = identity.id();
 
@@ -534,12 +570,15 @@ async fn broadcast_random_documents_load_test(
 
     // what the hell
     let oks = Arc::new(AtomicUsize::new(0)); // Atomic counter for tasks
-    let errs = Arc::new(AtomicUsize::new(0)); // Atomic counter for tasks
+    let errors = Arc::new(ErrorTally::default());
     let pending = Arc::new(AtomicUsize::new(0));
     let last_report = Arc::new(AtomicU64::new(0));
 
     let start_time = Instant::now();
 
+    let mut schedule = schedule.map(|profile| OpenLoopSchedule::new(profile, start_time));
+    let latency = Arc::new(Histogram::default());
+
     let mut tasks = Vec::new();
 
     let settings = RequestSettings {
@@ -568,12 +607,26 @@ async fn broadcast_random_documents_load_test(
             if cancel.is_cancelled() {
                 break;
             }
-            // Acquire a permit
-            let permits = Arc::clone(&permits);
-            let permit = permits.acquire_owned().await.unwrap();
+            // Wait for the intended send time of the document when open-loop,
+            // acquire a permit otherwise
+            let (permit, send_time) = match &mut schedule {
+                Some(schedule) => {
+                    let send_time = schedule.next_send_time();
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(send_time) => {},
+                        _ = cancel.cancelled() => break,
+                    }
+                    (None, Some(send_time))
+                }
+                None => {
+                    let permits = Arc::clone(&permits);
+                    (Some(permits.acquire_owned().await.unwrap()), None)
+                }
+            };
 
             let oks = Arc::clone(&oks);
-            let errs = Arc::clone(&errs);
+            let errors = Arc::clone(&errors);
+            let latency = Arc::clone(&latency);
             let pending = Arc::clone(&pending);
             let last_report = Arc::clone(&last_report);
 
@@ -608,9 +661,15 @@ async fn broadcast_random_documents_load_test(
                     .expect("expected a random document");
 
                 // Wait for the rate limiter to allow further processing
-                tokio::select! {
-                   _ = rate_limiter.until_ready() => {},
-                   _ = cancel_task.cancelled() => return,
+                let sent_at = match send_time {
+                    Some(send_time) => send_time,
+                    None => {
+                        tokio::select! {
+                           _ = rate_limiter.until_ready() => {},
+                           _ = cancel_task.cancelled() => return,
+                        };
+                        Instant::now()
+                    }
                 };
 
                 // Broadcast the document
@@ -627,11 +686,13 @@ async fn broadcast_random_documents_load_test(
                     && elapsed_secs != last_report.load(Ordering::SeqCst)
                 {
                     tracing::info!(
-                        "{} secs passed: {} pending, {} successful, {} failed",
+                        "{} secs passed: {} pending, {} successful, {} failed, latency: {}{}",
                         elapsed_secs,
                         pending.load(Ordering::SeqCst),
                         oks.load(Ordering::SeqCst),
-                        errs.load(Ordering::SeqCst),
+                        errors.total(),
+                        latency.summary(),
+                        errors.report_message(),
                     );
                     last_report.swap(elapsed_secs, Ordering::SeqCst);
                 }
@@ -651,6 +712,7 @@ async fn broadcast_random_documents_load_test(
                     )
                     .await;
 
+                latency.record(sent_at.elapsed());
                 pending.fetch_sub(1, Ordering::SeqCst);
 
                 match result {
@@ -663,14 +725,18 @@ async fn broadcast_random_documents_load_test(
                         );
                     }
                     Err(error) => {
-                        tracing::error!(
+                        tracing::debug!(
                             ?error,
                             "failed to broadcast document {}: {}",
                             random_document.id().to_string(Encoding::Base58),
                             error
                         );
 
-                        errs.fetch_add(1, Ordering::SeqCst);
+                        errors.add(
+                            endpoint_in(&error).unwrap_or(UNKNOWN_ENDPOINT),
+                            ErrorCategory::of_sdk_error(&error),
+                            || error.to_string(),
+                        );
                     }
                 };
 
@@ -683,17 +749,19 @@ async fn broadcast_random_documents_load_test(
 
     join_all(tasks).await;
 
-    let oks = oks.load(Ordering::SeqCst);
-    let errs = errs.load(Ordering::SeqCst);
+    let oks = oks.load(Ordering::SeqCst) as u64;
+    let errs = errors.total();
 
     tracing::info!(
         document_type = document_type.name(),
         "broadcasting {} random documents during {} secs. successfully: {}, failed: {}, rate: {} \
-         docs/sec",
+         docs/sec, latency: {}{}",
         oks + errs,
         duration.as_secs_f32(),
         oks,
         errs,
-        (oks + errs) as f32 / duration.as_secs_f32()
+        (oks + errs) as f32 / duration.as_secs_f32(),
+        latency.summary(),
+        errors.report_message()
     );
 }
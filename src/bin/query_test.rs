@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::{
     fmt,
@@ -12,36 +14,50 @@ use std::{
 };
 
 use clap::Parser;
-use dapi_grpc::platform::v0::get_identity_request;
 use dapi_grpc::platform::v0::GetIdentityRequest;
 use dapi_grpc::platform::v0::{
-    get_data_contract_request, get_data_contracts_request,
-    get_identity_balance_and_revision_request, get_identity_balance_request,
-    get_identity_contract_nonce_request, get_identity_keys_request, get_identity_nonce_request,
-    key_request_type, AllKeys, GetDataContractRequest, GetDataContractsRequest,
+    GetConsensusParamsRequest, GetDataContractHistoryRequest, GetDataContractRequest,
+    GetDataContractsRequest, GetDocumentsRequest, GetEpochsInfoRequest,
     GetIdentityBalanceAndRevisionRequest, GetIdentityBalanceRequest,
-    GetIdentityContractNonceRequest, GetIdentityKeysRequest, GetIdentityNonceRequest,
-    KeyRequestType,
+    GetIdentityByPublicKeyHashRequest, GetIdentityContractNonceRequest, GetIdentityKeysRequest,
+    GetIdentityNonceRequest, GetProtocolVersionUpgradeStateRequest,
+    GetProtocolVersionUpgradeVoteStatusRequest,
 };
 use dapi_grpc::tonic::transport::Uri;
-use dapi_grpc::tonic::{Code, Status as TransportError};
+use dapi_grpc::tonic::Status as TransportError;
 use dashmap::DashMap;
 use futures::future::{join_all, BoxFuture};
 use futures::FutureExt;
 use governor::clock::DefaultClock;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
-use rand::seq::SliceRandom;
 use rs_dapi_client::{
     Address, AddressList, DapiClient, DapiClientError, DapiRequest, DapiRequestExecutor,
     RequestSettings,
 };
-use rs_platform_explorer::config::Config;
+use rs_platform_explorer::{
+    config::Config,
+    load_testing::{
+        errors::{ErrorCategory, ErrorCount, ErrorTally},
+        latency::{Histogram, LatencySummary},
+        schedule::{OpenLoopSchedule, RateProfile},
+    },
+};
+use serde::Serialize;
+use tokio::task::JoinSet;
 use tokio::time::{interval, Instant};
 use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
 use tracing_subscriber::EnvFilter;
 
+mod plan;
+
+use plan::RequestPlan;
+
+/// Longest time requests still in flight when the test stops are waited for,
+/// covers both the connect and the request timeouts.
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[derive(Parser, Debug)]
 struct Args {
     #[arg(
@@ -72,6 +88,24 @@ struct Args {
     )]
     time: Option<u16>,
     // TODO: Configure report time
+    #[arg(long, help = "Name of the configuration profile to use.")]
+    profile: Option<String>,
+    #[arg(
+        long,
+        help = "Request plan file (TOML or JSON) listing the requests to send and their weights, a built-in plan is used if omitted."
+    )]
+    plan: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Writes a JSON summary of the test with latency percentiles to the given file."
+    )]
+    summary: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with_all = ["rate", "rate_unit"],
+        help = "Sends requests open-loop following a rate profile, with latencies measured from the intended send times: constant:RATE, ramp:FROM..TO/SECS, step:RATE/SECS,... or spike:BASE,PEAK@START/SECS (rates per second)."
+    )]
+    schedule: Option<RateProfile>,
 }
 
 #[tokio::main]
@@ -113,164 +147,35 @@ async fn main() {
         default_panic_hook(panic_info);
     }));
 
-    let config = Config::load();
+    let config = match Config::load(args.profile.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     let rate = Rate::new(args.rate, args.rate_unit);
 
-    // TODO: It should be configurable and all request types should be added
-    let requests = vec![
-        // Existing DPNS identity
-        GetIdentityRequest {
-            version: Some(get_identity_request::Version::V0(
-                get_identity_request::GetIdentityRequestV0 {
-                    id: dpp::system_data_contracts::dpns_contract::OWNER_ID_BYTES.to_vec(),
-                    prove: true,
-                },
-            )),
-        }.into(),
-        // Existing DashPay identity
-        GetIdentityRequest {
-            version: Some(get_identity_request::Version::V0(
-                get_identity_request::GetIdentityRequestV0 {
-                    id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES.to_vec(),
-                    prove: true,
-                },
-            )),
-        }.into(),
-        // Existing Masternode reward shares identity
-        GetIdentityRequest {
-            version: Some(get_identity_request::Version::V0(
-                get_identity_request::GetIdentityRequestV0 {
-                    id: dpp::system_data_contracts::masternode_reward_shares_contract::OWNER_ID_BYTES.to_vec(),
-                    prove: true,
-                },
-            )),
-        }.into(),
-        // Non-existing identity
-        GetIdentityRequest {
-            version: Some(get_identity_request::Version::V0(
-                get_identity_request::GetIdentityRequestV0 {
-                    id: vec![0; 32],
-                    prove: false,
-                },
-            )),
-        }.into(),
-        // Get identity nonce
-        GetIdentityNonceRequest {
-            version: Some(get_identity_nonce_request::Version::V0(get_identity_nonce_request::GetIdentityNonceRequestV0 {
-                identity_id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES.to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        // Get identity contract nonce
-        GetIdentityContractNonceRequest {
-            version: Some(
-                get_identity_contract_nonce_request::Version::V0(get_identity_contract_nonce_request::GetIdentityContractNonceRequestV0 {
-                    identity_id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES
-                        .to_vec(),
-                    contract_id: dpp::system_data_contracts::dashpay_contract::ID.to_vec(),
-                    prove: true,
-                },
-            )),
-        }.into(),
-        // Get identity contract nonce
-        GetIdentityContractNonceRequest {
-            version: Some(
-                get_identity_contract_nonce_request::Version::V0(get_identity_contract_nonce_request::GetIdentityContractNonceRequestV0 {
-                    identity_id: dpp::system_data_contracts::dpns_contract::OWNER_ID_BYTES
-                        .to_vec(),
-                    contract_id: dpp::system_data_contracts::dpns_contract::ID.to_vec(),
-                    prove: true,
-                },
-            )),
-        }.into(),
-        // Non existing identity contract nonce
-        GetIdentityContractNonceRequest {
-            version: Some(
-                get_identity_contract_nonce_request::Version::V0(get_identity_contract_nonce_request::GetIdentityContractNonceRequestV0 {
-                    identity_id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES
-                        .to_vec(),
-                    contract_id: vec![0; 32],
-                    prove: false,
-                },
-            )),
-        }.into(),
-        // Get DashPay identity balance
-        GetIdentityBalanceRequest {
-            version: Some(get_identity_balance_request::Version::V0(get_identity_balance_request::GetIdentityBalanceRequestV0 {
-                id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES
-                    .to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        // Get DashPay identity balance and revision
-        GetIdentityBalanceAndRevisionRequest {
-            version: Some(get_identity_balance_and_revision_request::Version::V0(get_identity_balance_and_revision_request::GetIdentityBalanceAndRevisionRequestV0 {
-                id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES
-                    .to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        GetIdentityKeysRequest {
-            version: Some(get_identity_keys_request::Version::V0(get_identity_keys_request::GetIdentityKeysRequestV0 {
-                identity_id: dpp::system_data_contracts::dashpay_contract::OWNER_ID_BYTES
-                    .to_vec(),
-                request_type: Some(KeyRequestType { request: Some(key_request_type::Request::AllKeys(AllKeys {})) }),
-                limit: None,
-                offset: None,
-                prove: true,
-            })),
-        }.into(),
-        // Dashpay contract
-        GetDataContractRequest {
-            version: Some(get_data_contract_request::Version::V0(get_data_contract_request::GetDataContractRequestV0 {
-                id: dpp::system_data_contracts::dashpay_contract::ID
-                    .to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        // DPNS contract
-        GetDataContractRequest {
-            version: Some(get_data_contract_request::Version::V0(get_data_contract_request::GetDataContractRequestV0 {
-                id: dpp::system_data_contracts::dpns_contract::ID
-                    .to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        // Masternode reward shares contract
-        GetDataContractRequest {
-            version: Some(get_data_contract_request::Version::V0(get_data_contract_request::GetDataContractRequestV0 {
-                id: dpp::system_data_contracts::masternode_reward_shares_contract::ID
-                    .to_vec(),
-                prove: true,
-            })),
-        }.into(),
-        // Non existing contract
-        GetDataContractRequest {
-            version: Some(get_data_contract_request::Version::V0(get_data_contract_request::GetDataContractRequestV0 {
-                id: vec![0; 32],
-                prove: false,
-            })),
-        }.into(),
-        // Non existing contract
-        GetDataContractsRequest {
-            version: Some(get_data_contracts_request::Version::V0(get_data_contracts_request::GetDataContractsRequestV0 {
-                ids: vec![dpp::system_data_contracts::dpns_contract::ID
-                    .to_vec(), dpp::system_data_contracts::masternode_reward_shares_contract::ID
-                    .to_vec(), vec![0; 32]],
-                prove: true,
-            })),
-        }.into(),
-    ];
-
-    let request_plan = RequestPlan::new(requests);
+    let request_plan = match &args.plan {
+        Some(path) => match RequestPlan::load(path) {
+            Ok(request_plan) => request_plan,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => RequestPlan::builtin(),
+    };
 
     send_many_request_to_drive(
         &config,
         args.time.map(|t| Duration::from_secs(t.into())),
         args.connections,
         rate,
+        args.schedule,
         request_plan,
+        args.summary,
     )
     .await;
 }
@@ -280,7 +185,9 @@ async fn send_many_request_to_drive(
     duration: Option<Duration>,
     concurrent_connections: u16,
     rate: Rate,
+    schedule: Option<RateProfile>,
     requests: RequestPlan,
+    summary_path: Option<PathBuf>,
 ) {
     let start_time = Instant::now();
     let cancel_test = CancellationToken::new();
@@ -293,9 +200,14 @@ async fn send_many_request_to_drive(
         String::new()
     };
 
+    let rate_message = match &schedule {
+        Some(profile) => format!("open-loop at {}", profile),
+        None => rate.to_string(),
+    };
+
     tracing::info!(
         "query {} identities with {} parallel connections{}",
-        rate,
+        rate_message,
         concurrent_connections,
         duration_message
     );
@@ -336,68 +248,174 @@ async fn send_many_request_to_drive(
 
     let mut addresses = AddressPool::from(config.dapi_addresses.as_str());
 
-    for connection_id in 0..concurrent_connections {
-        // TODO: Use SDK but it should be able to return TransportError
-        // Pick an address obe by one for each connection
-        // and create one client per connection
-        let address_list = addresses.next_one_address_list();
-        let client = DapiClient::new(address_list, request_settings);
-        let connection_client = Arc::new(client);
-
-        let connection_rate = Arc::clone(&rate);
-        let cancel_connection = cancel_test.clone();
-        let connection_summary = Arc::clone(&summary);
-        let connections_requests = Arc::clone(&requests);
-
-        // Send requests through the connection in a loop
-        let connection_task = tokio::spawn(async move {
-            while !cancel_connection.is_cancelled() {
-                // Wait for the rate limiter to allow further processing
-                tokio::select! {
-                    _ = connection_rate.limiter.until_ready() => {},
-                    _ = cancel_connection.cancelled() => return,
+    // TODO: Use SDK but it should be able to return TransportError
+    // Pick an address obe by one for each connection
+    // and create one client per connection
+    let connections: Vec<Connection> = (0..concurrent_connections)
+        .map(|id| {
+            let address = addresses.next_address().clone();
+            let connection_address = address.uri().to_string().into();
+            let mut address_list = AddressList::new();
+            address_list.add(address);
+            Connection {
+                id,
+                client: Arc::new(DapiClient::new(address_list, request_settings)),
+                address: connection_address,
+            }
+        })
+        .collect();
+
+    if let Some(profile) = schedule {
+        // Send requests at their intended times through the connections in
+        // turn, however long the previous ones take
+        let cancel_dispatch = cancel_test.clone();
+        let dispatch_summary = Arc::clone(&summary);
+        let dispatch_requests = Arc::clone(&requests);
+
+        let dispatch_task = tokio::spawn(async move {
+            let mut schedule = OpenLoopSchedule::new(profile, start_time);
+            let mut in_flight = JoinSet::new();
+
+            'dispatch: for connection in connections.iter().cycle() {
+                let send_time = schedule.next_send_time();
+                loop {
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(send_time) => break,
+                        // Collect completed requests while waiting
+                        Some(_) = in_flight.join_next(), if !in_flight.is_empty() => {},
+                        _ = cancel_dispatch.cancelled() => break 'dispatch,
+                    }
                 }
 
-                let request_client = Arc::clone(&connection_client);
-                let request_summary = Arc::clone(&connection_summary);
+                connection.spawn_request(
+                    &mut in_flight,
+                    request_settings,
+                    dispatch_requests.random(),
+                    &dispatch_summary,
+                    send_time,
+                );
+            }
 
-                // Select a random request from the list
-                let request = connections_requests.random();
+            drain_requests(in_flight).await;
+        });
 
-                // Send a request without waiting for the response,
-                // so we can send many requests in parallel through one connection
-                tokio::spawn(async move {
-                    let span = tracing::span!(
-                        tracing::Level::TRACE,
-                        "connection",
-                        connection_id = connection_id
+        tasks.push(dispatch_task);
+    } else {
+        for connection in connections {
+            let connection_rate = Arc::clone(&rate);
+            let cancel_connection = cancel_test.clone();
+            let connection_summary = Arc::clone(&summary);
+            let connections_requests = Arc::clone(&requests);
+
+            // Send requests through the connection in a loop
+            let connection_task = tokio::spawn(async move {
+                let mut in_flight = JoinSet::new();
+
+                while !cancel_connection.is_cancelled() {
+                    // Wait for the rate limiter to allow further processing
+                    tokio::select! {
+                        _ = connection_rate.limiter.until_ready() => {},
+                        // Collect completed requests while waiting
+                        Some(_) = in_flight.join_next(), if !in_flight.is_empty() => continue,
+                        _ = cancel_connection.cancelled() => break,
+                    }
+
+                    // Select a random request from the list
+                    let request = connections_requests.random();
+
+                    connection.spawn_request(
+                        &mut in_flight,
+                        request_settings,
+                        request,
+                        &connection_summary,
+                        Instant::now(),
                     );
+                }
 
-                    send_request(request_client, request_settings, request, &request_summary)
-                        .instrument(span)
-                        .await
-                });
-            }
-        });
+                drain_requests(in_flight).await;
+            });
 
-        tasks.push(connection_task);
+            tasks.push(connection_task);
+        }
     }
 
+    // Sending tasks finish once their requests in flight are drained
     join_all(tasks).await;
 
     tracing::info!("[DONE] {}", summary.report_message());
+
+    if let Some(summary_path) = summary_path {
+        let json = serde_json::to_string_pretty(&summary.final_summary())
+            .expect("test summary is serializable");
+        if let Err(e) = std::fs::write(&summary_path, json) {
+            tracing::error!("unable to write {}: {}", summary_path.display(), e);
+        }
+    }
+}
+
+/// Client of a connection to one DAPI address.
+struct Connection {
+    id: u16,
+    client: Arc<DapiClient>,
+    address: Arc<str>,
+}
+
+impl Connection {
+    /// Sends a request without waiting for the response, so many requests
+    /// can be sent in parallel through one connection. Its latency is
+    /// measured from `sent_at`, the request is tracked in `in_flight`.
+    fn spawn_request(
+        &self,
+        in_flight: &mut JoinSet<()>,
+        settings: RequestSettings,
+        request: AnyDapiRequest,
+        summary: &Arc<TestSummary>,
+        sent_at: Instant,
+    ) {
+        let client = Arc::clone(&self.client);
+        let address = Arc::clone(&self.address);
+        let summary = Arc::clone(summary);
+        let span = tracing::span!(tracing::Level::TRACE, "connection", connection_id = self.id);
+
+        in_flight.spawn(
+            async move { send_request(client, settings, request, &address, &summary, sent_at).await }
+                .instrument(span),
+        );
+    }
+}
+
+/// Waits for the requests still in flight to be counted, for at most
+/// [IN_FLIGHT_TIMEOUT]. Requests left after that are aborted.
+async fn drain_requests(mut in_flight: JoinSet<()>) {
+    let drained = tokio::time::timeout(IN_FLIGHT_TIMEOUT, async {
+        while in_flight.join_next().await.is_some() {}
+    })
+    .await;
+
+    if drained.is_err() {
+        tracing::warn!(
+            "{} requests didn't complete in time and are not counted",
+            in_flight.len()
+        );
+        in_flight.abort_all();
+    }
 }
 
 async fn send_request(
     client: Arc<DapiClient>,
     settings: RequestSettings,
     request: AnyDapiRequest,
+    address: &str,
     summary: &TestSummary,
+    sent_at: Instant,
 ) {
-    // TODO: Record response duration and then report average and histogram?
-    match request.execute(client.as_ref(), settings).await {
+    let request_name: &'static str = (&request).into();
+    let result = request.execute(client.as_ref(), settings).await;
+    summary.add_latency(request_name, address, sent_at.elapsed());
+
+    match result {
         Ok(_) => summary.add_ok(),
-        Err(DapiClientError::Transport(e, ..)) => summary.add_error(e),
+        Err(DapiClientError::Transport(e, ..)) => summary.add_error(address, e),
         Err(e) => panic!("unexpected error: {}", e),
     }
 }
@@ -413,34 +431,7 @@ fn cancel_at(cancellation_token: CancellationToken, deadline: Instant) {
     });
 }
 
-// TODO: Move to crate and reuse in load test and strategy
-
-struct RequestPlan {
-    requests: Vec<AnyDapiRequest>,
-}
-
-impl RequestPlan {
-    pub fn new(requests: Vec<AnyDapiRequest>) -> Self {
-        if requests.is_empty() {
-            panic!("request list must not be empty");
-        }
-
-        Self { requests }
-    }
-
-    pub fn random(&self) -> AnyDapiRequest {
-        self.random_with_rng(&mut rand::thread_rng())
-    }
-
-    pub fn random_with_rng(&self, rng: &mut impl rand::Rng) -> AnyDapiRequest {
-        self.requests
-            .choose(rng)
-            .expect("request list must not be empty")
-            .clone()
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, strum::IntoStaticStr)]
 #[allow(clippy::enum_variant_names)]
 enum AnyDapiRequest {
     GetIdentityRequest(GetIdentityRequest),
@@ -451,6 +442,13 @@ enum AnyDapiRequest {
     GetIdentityKeysRequest(GetIdentityKeysRequest),
     GetDataContractRequest(GetDataContractRequest),
     GetDataContractsRequest(GetDataContractsRequest),
+    GetDocumentsRequest(GetDocumentsRequest),
+    GetEpochsInfoRequest(GetEpochsInfoRequest),
+    GetProtocolVersionUpgradeStateRequest(GetProtocolVersionUpgradeStateRequest),
+    GetProtocolVersionUpgradeVoteStatusRequest(GetProtocolVersionUpgradeVoteStatusRequest),
+    GetIdentityByPublicKeyHashRequest(GetIdentityByPublicKeyHashRequest),
+    GetDataContractHistoryRequest(GetDataContractHistoryRequest),
+    GetConsensusParamsRequest(GetConsensusParamsRequest),
 }
 
 impl From<GetIdentityRequest> for AnyDapiRequest {
@@ -501,6 +499,48 @@ impl From<GetDataContractsRequest> for AnyDapiRequest {
     }
 }
 
+impl From<GetDocumentsRequest> for AnyDapiRequest {
+    fn from(value: GetDocumentsRequest) -> Self {
+        AnyDapiRequest::GetDocumentsRequest(value)
+    }
+}
+
+impl From<GetEpochsInfoRequest> for AnyDapiRequest {
+    fn from(value: GetEpochsInfoRequest) -> Self {
+        AnyDapiRequest::GetEpochsInfoRequest(value)
+    }
+}
+
+impl From<GetProtocolVersionUpgradeStateRequest> for AnyDapiRequest {
+    fn from(value: GetProtocolVersionUpgradeStateRequest) -> Self {
+        AnyDapiRequest::GetProtocolVersionUpgradeStateRequest(value)
+    }
+}
+
+impl From<GetProtocolVersionUpgradeVoteStatusRequest> for AnyDapiRequest {
+    fn from(value: GetProtocolVersionUpgradeVoteStatusRequest) -> Self {
+        AnyDapiRequest::GetProtocolVersionUpgradeVoteStatusRequest(value)
+    }
+}
+
+impl From<GetIdentityByPublicKeyHashRequest> for AnyDapiRequest {
+    fn from(value: GetIdentityByPublicKeyHashRequest) -> Self {
+        AnyDapiRequest::GetIdentityByPublicKeyHashRequest(value)
+    }
+}
+
+impl From<GetDataContractHistoryRequest> for AnyDapiRequest {
+    fn from(value: GetDataContractHistoryRequest) -> Self {
+        AnyDapiRequest::GetDataContractHistoryRequest(value)
+    }
+}
+
+impl From<GetConsensusParamsRequest> for AnyDapiRequest {
+    fn from(value: GetConsensusParamsRequest) -> Self {
+        AnyDapiRequest::GetConsensusParamsRequest(value)
+    }
+}
+
 impl DapiRequest for AnyDapiRequest {
     type Response = ();
     type TransportError = dapi_grpc::tonic::Status;
@@ -546,6 +586,34 @@ impl DapiRequest for AnyDapiRequest {
                 .execute(dapi_client, settings)
                 .map(|result| result.map(|_| ()))
                 .boxed(),
+            AnyDapiRequest::GetDocumentsRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetEpochsInfoRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetProtocolVersionUpgradeStateRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetProtocolVersionUpgradeVoteStatusRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetIdentityByPublicKeyHashRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetDataContractHistoryRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            AnyDapiRequest::GetConsensusParamsRequest(request) => request
+                .execute(dapi_client, settings)
+                .map(|result| result.map(|_| ()))
+                .boxed(),
         }
     }
 }
@@ -598,7 +666,22 @@ impl Rate {
 struct TestSummary {
     start_time: Instant,
     oks: AtomicU64,
-    errors_per_code: DashMap<Code, AtomicU64>,
+    errors: ErrorTally,
+    latency: Histogram,
+    latency_per_request: DashMap<&'static str, Histogram>,
+    latency_per_address: DashMap<String, Histogram>,
+}
+
+/// Summary written at the end of the test.
+#[derive(Serialize)]
+struct FinalSummary {
+    elapsed_secs: u64,
+    oks: u64,
+    errors: u64,
+    error_counts: Vec<ErrorCount>,
+    latency: LatencySummary,
+    latency_per_request: BTreeMap<&'static str, LatencySummary>,
+    latency_per_address: BTreeMap<String, LatencySummary>,
 }
 
 impl TestSummary {
@@ -606,7 +689,53 @@ impl TestSummary {
         Self {
             start_time,
             oks: Default::default(),
-            errors_per_code: Default::default(),
+            errors: Default::default(),
+            latency: Default::default(),
+            latency_per_request: Default::default(),
+            latency_per_address: Default::default(),
+        }
+    }
+
+    fn add_latency(&self, request_name: &'static str, address: &str, latency: Duration) {
+        self.latency.record(latency);
+        self.latency_per_request
+            .entry(request_name)
+            .or_default()
+            .record(latency);
+        // Avoid allocating the key once the address is known
+        if let Some(histogram) = self.latency_per_address.get(address) {
+            histogram.record(latency);
+        } else {
+            self.latency_per_address
+                .entry(address.to_owned())
+                .or_default()
+                .record(latency);
+        }
+    }
+
+    fn latency_per_request(&self) -> BTreeMap<&'static str, LatencySummary> {
+        self.latency_per_request
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().summary()))
+            .collect()
+    }
+
+    fn latency_per_address(&self) -> BTreeMap<String, LatencySummary> {
+        self.latency_per_address
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().summary()))
+            .collect()
+    }
+
+    fn final_summary(&self) -> FinalSummary {
+        FinalSummary {
+            elapsed_secs: self.start_time.elapsed().as_secs(),
+            oks: self.oks_count(),
+            errors: self.errors_count(),
+            error_counts: self.errors.counts(),
+            latency: self.latency.summary(),
+            latency_per_request: self.latency_per_request(),
+            latency_per_address: self.latency_per_address(),
         }
     }
 
@@ -618,18 +747,15 @@ impl TestSummary {
         self.oks.load(Ordering::Relaxed)
     }
 
-    fn add_error(&self, error: TransportError) {
-        self.errors_per_code
-            .entry(error.code())
-            .or_insert_with(|| AtomicU64::new(0))
-            .fetch_add(1, Ordering::Relaxed);
+    fn add_error(&self, address: &str, error: TransportError) {
+        self.errors
+            .add(address, ErrorCategory::of_status(&error), || {
+                error.message().to_owned()
+            });
     }
 
     fn errors_count(&self) -> u64 {
-        self.errors_per_code
-            .iter()
-            .map(|entry| entry.value().load(Ordering::Relaxed))
-            .sum()
+        self.errors.total()
     }
 
     // TODO: Drop intermediate values after report so we show only difference and then summary?
@@ -637,31 +763,25 @@ impl TestSummary {
         let elapsed_secs = self.start_time.elapsed().as_secs();
 
         let oks = self.oks.load(Ordering::Relaxed);
-        let mut errors = 0;
-        let mut error_messages = Vec::new();
-
-        for entry in self.errors_per_code.iter() {
-            let code = entry.key();
-            let count = entry.value().load(Ordering::Relaxed);
-
-            errors += count;
-
-            error_messages.push(format!("{:?} - {}", code, count));
-        }
+        let errors = self.errors_count();
 
         let total = oks + errors;
 
         let rate = total.checked_div(elapsed_secs).unwrap_or(0);
 
-        let error_message = if !error_messages.is_empty() {
-            format!(": {}", error_messages.join(", "))
-        } else {
-            String::new()
-        };
+        let error_message = self.errors.report_message();
+
+        let mut latency_message = format!("\n  latency: {}", self.latency.summary());
+        for (request_name, latency) in self.latency_per_request() {
+            latency_message.push_str(&format!("\n  {}: {}", request_name, latency));
+        }
+        for (address, latency) in self.latency_per_address() {
+            latency_message.push_str(&format!("\n  {}: {}", address, latency));
+        }
 
         format!(
-            "{elapsed_secs} secs passed. {total} processed ({rate} q/s): {oks} successful, {errors} failed{}",
-            error_message
+            "{elapsed_secs} secs passed. {total} processed ({rate} q/s): {oks} successful, {errors} failed{}{}",
+            error_message, latency_message
         )
     }
 }
@@ -684,12 +804,6 @@ impl AddressPool {
         self.current_index = (self.current_index + 1) % self.addresses.len();
         address
     }
-
-    fn next_one_address_list(&mut self) -> AddressList {
-        let mut address_list = AddressList::new();
-        address_list.add(self.next_address().clone());
-        address_list
-    }
 }
 
 impl From<&str> for AddressPool {
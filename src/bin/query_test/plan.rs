@@ -0,0 +1,563 @@
+//! Request plans of the query test.
+//!
+//! A plan lists the requests to send along with their weights, every request
+//! is picked with a probability proportional to its weight. Plans are written
+//! as TOML or JSON files (picked by the extension) with base58 encoded IDs
+//! and hex encoded hashes.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use dapi_grpc::platform::v0::{
+    get_consensus_params_request, get_data_contract_history_request, get_data_contract_request,
+    get_data_contracts_request, get_documents_request, get_epochs_info_request,
+    get_identity_balance_and_revision_request, get_identity_balance_request,
+    get_identity_by_public_key_hash_request, get_identity_contract_nonce_request,
+    get_identity_keys_request, get_identity_nonce_request, get_identity_request,
+    get_protocol_version_upgrade_state_request, get_protocol_version_upgrade_vote_status_request,
+    key_request_type, AllKeys, GetConsensusParamsRequest, GetDataContractHistoryRequest,
+    GetDataContractRequest, GetDataContractsRequest, GetDocumentsRequest, GetEpochsInfoRequest,
+    GetIdentityBalanceAndRevisionRequest, GetIdentityBalanceRequest,
+    GetIdentityByPublicKeyHashRequest, GetIdentityContractNonceRequest, GetIdentityKeysRequest,
+    GetIdentityNonceRequest, GetIdentityRequest, GetProtocolVersionUpgradeStateRequest,
+    GetProtocolVersionUpgradeVoteStatusRequest, KeyRequestType,
+};
+use dpp::{
+    platform_value::{string_encoding::Encoding, Identifier},
+    system_data_contracts::{dashpay_contract, dpns_contract, masternode_reward_shares_contract},
+};
+use rand::distributions::{Distribution, WeightedError, WeightedIndex};
+use serde::Deserialize;
+
+use crate::AnyDapiRequest;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum PlanError {
+    #[error("unable to read {}: {}", .0.display(), .1)]
+    Read(PathBuf, io::Error),
+    #[error("{} is neither a .toml nor a .json file", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("invalid request plan {}: {}", .0.display(), .1)]
+    Invalid(PathBuf, String),
+}
+
+/// Base58 encoded ID in a plan file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+struct PlanId(Vec<u8>);
+
+impl TryFrom<String> for PlanId {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Identifier::from_string(&value, Encoding::Base58)
+            .map(|id| PlanId(id.to_vec()))
+            .map_err(|e| format!("invalid ID {}: {}", value, e))
+    }
+}
+
+/// Hex encoded hash in a plan file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "String")]
+struct PlanHash(Vec<u8>);
+
+impl TryFrom<String> for PlanHash {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        hex::decode(&value)
+            .map(PlanHash)
+            .map_err(|e| format!("invalid hash {}: {}", value, e))
+    }
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_prove() -> bool {
+    true
+}
+
+fn default_ascending() -> bool {
+    true
+}
+
+/// Kind of a planned request and its parameters, proofs are requested unless
+/// `prove` is set to false.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum RequestKind {
+    Identity {
+        id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    IdentityNonce {
+        identity_id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    IdentityContractNonce {
+        identity_id: PlanId,
+        contract_id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    IdentityBalance {
+        id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    IdentityBalanceAndRevision {
+        id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    /// All keys of an identity
+    IdentityKeys {
+        identity_id: PlanId,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    DataContract {
+        id: PlanId,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    DataContracts {
+        ids: Vec<PlanId>,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    /// Versions of a contract keeping its history, starting from
+    /// `start_at_ms`
+    DataContractHistory {
+        id: PlanId,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        #[serde(default)]
+        start_at_ms: u64,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    /// Documents of a type without where and order by clauses, starting after
+    /// the document `start_after` if set
+    Documents {
+        data_contract_id: PlanId,
+        document_type: String,
+        #[serde(default)]
+        limit: u32,
+        start_after: Option<PlanId>,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    /// `count` epochs from `start_epoch`, or from the current one if unset
+    EpochsInfo {
+        start_epoch: Option<u32>,
+        count: u32,
+        #[serde(default = "default_ascending")]
+        ascending: bool,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    ProtocolVersionUpgradeState {
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    /// Votes of `count` masternodes from `start_pro_tx_hash`, or from the
+    /// first one if unset
+    ProtocolVersionUpgradeVoteStatus {
+        #[serde(default)]
+        start_pro_tx_hash: PlanHash,
+        count: u32,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    IdentityByPublicKeyHash {
+        public_key_hash: PlanHash,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+    ConsensusParams {
+        #[serde(default)]
+        height: i32,
+        #[serde(default = "default_prove")]
+        prove: bool,
+    },
+}
+
+impl RequestKind {
+    fn request(self) -> AnyDapiRequest {
+        match self {
+            RequestKind::Identity { id, prove } => GetIdentityRequest {
+                version: Some(get_identity_request::Version::V0(
+                    get_identity_request::GetIdentityRequestV0 { id: id.0, prove },
+                )),
+            }
+            .into(),
+            RequestKind::IdentityNonce { identity_id, prove } => GetIdentityNonceRequest {
+                version: Some(get_identity_nonce_request::Version::V0(
+                    get_identity_nonce_request::GetIdentityNonceRequestV0 {
+                        identity_id: identity_id.0,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::IdentityContractNonce {
+                identity_id,
+                contract_id,
+                prove,
+            } => GetIdentityContractNonceRequest {
+                version: Some(get_identity_contract_nonce_request::Version::V0(
+                    get_identity_contract_nonce_request::GetIdentityContractNonceRequestV0 {
+                        identity_id: identity_id.0,
+                        contract_id: contract_id.0,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::IdentityBalance { id, prove } => GetIdentityBalanceRequest {
+                version: Some(get_identity_balance_request::Version::V0(
+                    get_identity_balance_request::GetIdentityBalanceRequestV0 { id: id.0, prove },
+                )),
+            }
+            .into(),
+            RequestKind::IdentityBalanceAndRevision { id, prove } => {
+                GetIdentityBalanceAndRevisionRequest {
+                    version: Some(get_identity_balance_and_revision_request::Version::V0(
+                        get_identity_balance_and_revision_request::GetIdentityBalanceAndRevisionRequestV0 {
+                            id: id.0,
+                            prove,
+                        },
+                    )),
+                }
+                .into()
+            }
+            RequestKind::IdentityKeys {
+                identity_id,
+                limit,
+                offset,
+                prove,
+            } => GetIdentityKeysRequest {
+                version: Some(get_identity_keys_request::Version::V0(
+                    get_identity_keys_request::GetIdentityKeysRequestV0 {
+                        identity_id: identity_id.0,
+                        request_type: Some(KeyRequestType {
+                            request: Some(key_request_type::Request::AllKeys(AllKeys {})),
+                        }),
+                        limit,
+                        offset,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::DataContract { id, prove } => GetDataContractRequest {
+                version: Some(get_data_contract_request::Version::V0(
+                    get_data_contract_request::GetDataContractRequestV0 { id: id.0, prove },
+                )),
+            }
+            .into(),
+            RequestKind::DataContracts { ids, prove } => GetDataContractsRequest {
+                version: Some(get_data_contracts_request::Version::V0(
+                    get_data_contracts_request::GetDataContractsRequestV0 {
+                        ids: ids.into_iter().map(|id| id.0).collect(),
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::DataContractHistory {
+                id,
+                limit,
+                offset,
+                start_at_ms,
+                prove,
+            } => GetDataContractHistoryRequest {
+                version: Some(get_data_contract_history_request::Version::V0(
+                    get_data_contract_history_request::GetDataContractHistoryRequestV0 {
+                        id: id.0,
+                        limit,
+                        offset,
+                        start_at_ms,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::Documents {
+                data_contract_id,
+                document_type,
+                limit,
+                start_after,
+                prove,
+            } => GetDocumentsRequest {
+                version: Some(get_documents_request::Version::V0(
+                    get_documents_request::GetDocumentsRequestV0 {
+                        data_contract_id: data_contract_id.0,
+                        document_type,
+                        r#where: Vec::new(),
+                        order_by: Vec::new(),
+                        limit,
+                        start: start_after.map(|start_after| {
+                            get_documents_request::get_documents_request_v0::Start::StartAfter(
+                                start_after.0,
+                            )
+                        }),
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::EpochsInfo {
+                start_epoch,
+                count,
+                ascending,
+                prove,
+            } => GetEpochsInfoRequest {
+                version: Some(get_epochs_info_request::Version::V0(
+                    get_epochs_info_request::GetEpochsInfoRequestV0 {
+                        start_epoch,
+                        count,
+                        ascending,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::ProtocolVersionUpgradeState { prove } => {
+                GetProtocolVersionUpgradeStateRequest {
+                    version: Some(get_protocol_version_upgrade_state_request::Version::V0(
+                        get_protocol_version_upgrade_state_request::GetProtocolVersionUpgradeStateRequestV0 {
+                            prove,
+                        },
+                    )),
+                }
+                .into()
+            }
+            RequestKind::ProtocolVersionUpgradeVoteStatus {
+                start_pro_tx_hash,
+                count,
+                prove,
+            } => GetProtocolVersionUpgradeVoteStatusRequest {
+                version: Some(get_protocol_version_upgrade_vote_status_request::Version::V0(
+                    get_protocol_version_upgrade_vote_status_request::GetProtocolVersionUpgradeVoteStatusRequestV0 {
+                        start_pro_tx_hash: start_pro_tx_hash.0,
+                        count,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::IdentityByPublicKeyHash {
+                public_key_hash,
+                prove,
+            } => GetIdentityByPublicKeyHashRequest {
+                version: Some(get_identity_by_public_key_hash_request::Version::V0(
+                    get_identity_by_public_key_hash_request::GetIdentityByPublicKeyHashRequestV0 {
+                        public_key_hash: public_key_hash.0,
+                        prove,
+                    },
+                )),
+            }
+            .into(),
+            RequestKind::ConsensusParams { height, prove } => GetConsensusParamsRequest {
+                version: Some(get_consensus_params_request::Version::V0(
+                    get_consensus_params_request::GetConsensusParamsRequestV0 { height, prove },
+                )),
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannedRequest {
+    #[serde(default = "default_weight")]
+    weight: u32,
+    #[serde(flatten)]
+    kind: RequestKind,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlanFile {
+    requests: Vec<PlannedRequest>,
+}
+
+pub(crate) struct RequestPlan {
+    requests: Vec<AnyDapiRequest>,
+    weights: WeightedIndex<u32>,
+}
+
+impl RequestPlan {
+    /// Builds a plan from requests and their weights.
+    pub(crate) fn new(requests: Vec<(u32, AnyDapiRequest)>) -> Result<Self, String> {
+        let weights = WeightedIndex::new(requests.iter().map(|(weight, _)| *weight)).map_err(
+            |e| match e {
+                WeightedError::NoItem => "request list must not be empty".to_owned(),
+                WeightedError::AllWeightsZero => {
+                    "at least one request weight must be positive".to_owned()
+                }
+                e => e.to_string(),
+            },
+        )?;
+
+        Ok(Self {
+            requests: requests.into_iter().map(|(_, request)| request).collect(),
+            weights,
+        })
+    }
+
+    /// Reads the plan file at `path`.
+    pub(crate) fn load(path: &Path) -> Result<Self, PlanError> {
+        let source = fs::read_to_string(path).map_err(|e| PlanError::Read(path.to_owned(), e))?;
+        let invalid = |message: String| PlanError::Invalid(path.to_owned(), message);
+
+        let plan: PlanFile = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&source).map_err(|e| invalid(e.to_string()))?,
+            Some("json") => serde_json::from_str(&source).map_err(|e| invalid(e.to_string()))?,
+            _ => return Err(PlanError::UnknownFormat(path.to_owned())),
+        };
+
+        Self::new(
+            plan.requests
+                .into_iter()
+                .map(|planned| (planned.weight, planned.kind.request()))
+                .collect(),
+        )
+        .map_err(invalid)
+    }
+
+    /// Plan used when no file is given: identities, contracts and documents of
+    /// the system data contracts, a few requests of non-existing ones and
+    /// platform state queries, with equal weights.
+    pub(crate) fn builtin() -> Self {
+        let dpns_owner = || PlanId(dpns_contract::OWNER_ID_BYTES.to_vec());
+        let dashpay_owner = || PlanId(dashpay_contract::OWNER_ID_BYTES.to_vec());
+        let missing = || PlanId(vec![0; 32]);
+
+        let kinds = vec![
+            RequestKind::Identity {
+                id: dpns_owner(),
+                prove: true,
+            },
+            RequestKind::Identity {
+                id: dashpay_owner(),
+                prove: true,
+            },
+            RequestKind::Identity {
+                id: PlanId(masternode_reward_shares_contract::OWNER_ID_BYTES.to_vec()),
+                prove: true,
+            },
+            RequestKind::Identity {
+                id: missing(),
+                prove: false,
+            },
+            RequestKind::IdentityNonce {
+                identity_id: dashpay_owner(),
+                prove: true,
+            },
+            RequestKind::IdentityContractNonce {
+                identity_id: dashpay_owner(),
+                contract_id: PlanId(dashpay_contract::ID.to_vec()),
+                prove: true,
+            },
+            RequestKind::IdentityContractNonce {
+                identity_id: dpns_owner(),
+                contract_id: PlanId(dpns_contract::ID.to_vec()),
+                prove: true,
+            },
+            RequestKind::IdentityContractNonce {
+                identity_id: dashpay_owner(),
+                contract_id: missing(),
+                prove: false,
+            },
+            RequestKind::IdentityBalance {
+                id: dashpay_owner(),
+                prove: true,
+            },
+            RequestKind::IdentityBalanceAndRevision {
+                id: dashpay_owner(),
+                prove: true,
+            },
+            RequestKind::IdentityKeys {
+                identity_id: dashpay_owner(),
+                limit: None,
+                offset: None,
+                prove: true,
+            },
+            RequestKind::DataContract {
+                id: PlanId(dashpay_contract::ID.to_vec()),
+                prove: true,
+            },
+            RequestKind::DataContract {
+                id: PlanId(dpns_contract::ID.to_vec()),
+                prove: true,
+            },
+            RequestKind::DataContract {
+                id: PlanId(masternode_reward_shares_contract::ID.to_vec()),
+                prove: true,
+            },
+            RequestKind::DataContract {
+                id: missing(),
+                prove: false,
+            },
+            RequestKind::DataContracts {
+                ids: vec![
+                    PlanId(dpns_contract::ID.to_vec()),
+                    PlanId(masternode_reward_shares_contract::ID.to_vec()),
+                    missing(),
+                ],
+                prove: true,
+            },
+            RequestKind::Documents {
+                data_contract_id: PlanId(dpns_contract::ID.to_vec()),
+                document_type: "domain".to_owned(),
+                limit: 10,
+                start_after: None,
+                prove: true,
+            },
+            RequestKind::EpochsInfo {
+                start_epoch: None,
+                count: 5,
+                ascending: false,
+                prove: true,
+            },
+            RequestKind::ProtocolVersionUpgradeState { prove: true },
+            RequestKind::ProtocolVersionUpgradeVoteStatus {
+                start_pro_tx_hash: PlanHash::default(),
+                count: 10,
+                prove: true,
+            },
+            RequestKind::IdentityByPublicKeyHash {
+                public_key_hash: PlanHash(vec![0; 20]),
+                prove: false,
+            },
+        ];
+
+        Self::new(
+            kinds
+                .into_iter()
+                .map(|kind| (default_weight(), kind.request()))
+                .collect(),
+        )
+        .expect("built-in request plan is valid")
+    }
+
+    pub(crate) fn random(&self) -> AnyDapiRequest {
+        self.random_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Picks a request with a probability proportional to its weight.
+    pub(crate) fn random_with_rng(&self, rng: &mut impl rand::Rng) -> AnyDapiRequest {
+        self.requests[self.weights.sample(rng)].clone()
+    }
+}
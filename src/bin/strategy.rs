@@ -1,4 +1,4 @@
-use std::{fs::File, panic, time::Duration};
+use std::{fs::File, panic, path::PathBuf, time::Duration};
 
 use clap::{ArgAction, Parser};
 use dash_sdk::{RequestSettings, SdkBuilder};
@@ -9,7 +9,7 @@ use rs_platform_explorer::{
         identities::IdentityTask::{self},
         insight::InsightAPIClient,
         wallet::WalletTask,
-        Backend, Task,
+        Backend, BackendEvent, CompletedTaskPayload, Task,
     },
     config::Config,
 };
@@ -40,6 +40,31 @@ struct Args {
         help = "Specifies the minimum amount of Dash the loaded identity should have."
     )]
     dash: Option<u64>,
+
+    #[arg(long, help = "Name of the configuration profile to use.")]
+    profile: Option<String>,
+
+    #[arg(
+        long,
+        help = "Writes the run report to the given path, as CSV if it ends with .csv and as JSON otherwise."
+    )]
+    report: Option<PathBuf>,
+
+    #[arg(long, action = ArgAction::SetTrue, help = "Generates the state transitions of the test without broadcasting them.")]
+    dry_run: bool,
+
+    #[arg(
+        long,
+        requires = "dry_run",
+        help = "Writes the state transitions generated by a dry run to the given directory."
+    )]
+    dump_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Seed of the random generation, overrides the one stored for the strategy."
+    )]
+    seed: Option<u64>,
 }
 
 #[tokio::main]
@@ -96,7 +121,13 @@ async fn main() {
     }));
 
     // Load configuration
-    let config = Config::load();
+    let config = match Config::load(args.profile.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Setup Platform SDK
     let address_list = config.dapi_address_list();
@@ -120,7 +151,13 @@ async fn main() {
 
     let insight = InsightAPIClient::new(config.insight_api_uri());
 
-    let backend = Backend::new(&sdk, insight.clone(), config.clone()).await;
+    let backend = match Backend::new(&sdk, insight, config.clone()).await {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("Failed to load application state: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Create wallet if not initialized
     if backend.state().loaded_wallet.lock().await.is_none() {
@@ -209,19 +246,67 @@ async fn main() {
             }
         }
     }
-    if let Some(test_name) = args.test {
+    if let Some(test_name) = args.test.clone().filter(|_| args.dry_run) {
+        let event = backend
+            .run_task(Task::Strategy(
+                backend::strategies::StrategyTask::DryRunStrategy {
+                    strategy_name: test_name,
+                    blocks: args.blocks,
+                    dump_dir: args.dump_dir,
+                    seed: args.seed,
+                },
+            ))
+            .await;
+
+        match event {
+            BackendEvent::TaskCompleted {
+                execution_result: Ok(CompletedTaskPayload::StrategyDryRun(report)),
+                ..
+            } => {
+                println!("{}", report);
+                if let Some(report_path) = args.report {
+                    let json = serde_json::to_string_pretty(&report)
+                        .expect("dry run report is serializable");
+                    if let Err(e) = std::fs::write(&report_path, json) {
+                        eprintln!("Unable to write {}: {}", report_path.display(), e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            BackendEvent::StrategyError { error } => {
+                eprintln!("Dry run failed: {}", error);
+                std::process::exit(1);
+            }
+            _ => {}
+        }
+    } else if let Some(test_name) = args.test {
         let block_mode = if args.time_mode { false } else { true };
-        backend::strategies::run_strategy_task(
-            &sdk,
-            &backend.state(),
-            backend::strategies::StrategyTask::RunStrategy(
-                test_name.to_string(),
-                args.blocks,
-                args.prove,
-                block_mode,
-            ),
-            &insight,
-        )
-        .await;
+        let event = backend
+            .run_task(Task::Strategy(
+                backend::strategies::StrategyTask::RunStrategy(
+                    test_name.to_string(),
+                    args.blocks,
+                    args.prove,
+                    block_mode,
+                    args.seed,
+                ),
+            ))
+            .await;
+
+        if let Some(report_path) = args.report {
+            match event {
+                BackendEvent::StrategyCompleted { report, .. } => {
+                    if let Err(e) = report.write(&report_path) {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+                BackendEvent::StrategyError { error } => {
+                    eprintln!("Strategy failed, no report written: {}", error);
+                    std::process::exit(1);
+                }
+                _ => {}
+            }
+        }
     }
 }
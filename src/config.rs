@@ -1,15 +1,22 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    fs,
+    path::PathBuf,
+    str::FromStr,
+};
 
 use dash_sdk::sdk::Uri;
 use dpp::dashcore::Network;
 use rs_dapi_client::AddressList;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Deserialize, Clone)]
 /// Configuration for platform explorer.
 ///
-/// Content of this configuration is loaded from environment variables or `.env`
-/// file when the [Config::load()] is called.
+/// Content of this configuration is loaded from a named profile of the
+/// [profiles file](Config::PROFILES_FILE) if one exists, otherwise from
+/// environment variables or `.env` file when the [Config::load()] is called.
 /// Variable names in the enviroment and `.env` file must be prefixed with
 /// either [LOCAL_EXPLORER_](Config::CONFIG_PREFIX) or
 /// [TESTNET_EXPLORER_](Config::CONFIG_PREFIX) and written as
@@ -31,6 +38,112 @@ pub struct Config {
     pub network: String,
     /// Optional wallet private key to instantiate the wallet
     pub wallet_private_key: Option<String>,
+    /// Optional passphrase to encrypt the state file with, the TUI asks for it
+    /// if the state file is encrypted and no passphrase is set. Setting it is
+    /// the only way to encrypt a plain text state file
+    pub state_passphrase: Option<String>,
+    /// Optional path to the state file, defaults to `<network>_explorer.state`
+    pub state_file: Option<PathBuf>,
+    /// Name of the profile the configuration was loaded from
+    #[serde(skip)]
+    pub profile: Option<String>,
+}
+
+/// Secrets are never printed.
+impl Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn redacted(secret: &Option<String>) -> Option<&'static str> {
+            secret.as_ref().map(|_| REDACTED)
+        }
+
+        f.debug_struct("Config")
+            .field("dapi_addresses", &self.dapi_addresses)
+            .field("core_host", &self.core_host)
+            .field("core_rpc_port", &self.core_rpc_port)
+            .field("core_rpc_user", &self.core_rpc_user)
+            .field("core_rpc_password", &REDACTED)
+            .field("insight_api_url", &self.insight_api_url)
+            .field("network", &self.network)
+            .field("wallet_private_key", &redacted(&self.wallet_private_key))
+            .field("state_passphrase", &redacted(&self.state_passphrase))
+            .field("state_file", &self.state_file)
+            .field("profile", &self.profile)
+            .finish()
+    }
+}
+
+const REDACTED: &str = "<redacted>";
+
+/// A problem with a single configuration field.
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("unable to read configuration from the environment: {0}")]
+    Environment(#[from] envy::Error),
+    #[error("unable to read {file}: {message}")]
+    ProfilesFile { file: &'static str, message: String },
+    #[error("no profile requested and no default profile set in {0}")]
+    NoDefaultProfile(&'static str),
+    #[error("profile {profile} not found in {file}")]
+    ProfileNotFound { profile: String, file: &'static str },
+    #[error("profile {profile} requested, but {file} doesn't exist")]
+    NoProfilesFile { profile: String, file: &'static str },
+    #[error("invalid configuration{}:{}", profile_suffix(.profile), problems_list(.problems))]
+    Invalid {
+        profile: Option<String>,
+        problems: Vec<ConfigProblem>,
+    },
+}
+
+fn profile_suffix(profile: &Option<String>) -> String {
+    profile
+        .as_ref()
+        .map(|profile| format!(" of profile {}", profile))
+        .unwrap_or_default()
+}
+
+fn problems_list(problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n  {}", problem))
+        .collect()
+}
+
+/// Removes the `user:password@` part an address may carry.
+fn without_userinfo(address: &str) -> String {
+    let authority_start = address.find("://").map(|i| i + 3).unwrap_or(0);
+    let authority_end = address[authority_start..]
+        .find(['/', '?', '#'])
+        .map(|i| authority_start + i)
+        .unwrap_or(address.len());
+    match address[authority_start..authority_end].rfind('@') {
+        Some(at) => format!(
+            "{}{}",
+            &address[..authority_start],
+            &address[authority_start + at + 1..]
+        ),
+        None => address.to_owned(),
+    }
+}
+
+/// Content of the [profiles file](Config::PROFILES_FILE).
+#[derive(Debug, Deserialize)]
+struct Profiles {
+    /// Profile to use if none was requested explicitly
+    default: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Config>,
 }
 
 impl Config {
@@ -38,38 +151,169 @@ impl Config {
     /// file.
     const CONFIG_PREFIX: &'static str = "EXPLORER_";
 
-    /// Loads a local configuration from operating system environment variables
-    /// and `.env` file.
+    /// TOML file with named configuration profiles, each profile is a
+    /// `[profiles.<name>]` table with the same keys as [Config] fields.
+    pub const PROFILES_FILE: &'static str = "profiles.toml";
+
+    /// Loads the configuration.
     ///
-    /// Create new [Config] with data from environment variables and
-    /// `.env` file. Variable names in the
+    /// If the [profiles file](Config::PROFILES_FILE) exists, the requested
+    /// `profile` is used, or the file's `default` one if none was requested.
+    /// A state passphrase missing from the profile is taken from the
+    /// environment.
+    ///
+    /// Without the profiles file the configuration is created with data from
+    /// environment variables and `.env` file. Variable names in the
     /// environment and `.env` file must be converted to SCREAMING_SNAKE_CASE
     /// and prefixed with [LOCAL_EXPLORER_](Config::CONFIG_PREFIX).
-    pub fn load() -> Self {
+    pub fn load(profile: Option<&str>) -> Result<Self, ConfigError> {
         // load config from .env file
         if let Err(err) = dotenvy::from_path(".env") {
             tracing::warn!(?err, "failed to load config file");
         }
 
-        let config: Self = envy::prefixed(Self::CONFIG_PREFIX)
-            .from_env()
-            .expect("configuration error");
+        let config = match Self::read_profiles()? {
+            Some(profiles) => {
+                let Some(name) = profile.or(profiles.default.as_deref()) else {
+                    return Err(ConfigError::NoDefaultProfile(Self::PROFILES_FILE));
+                };
+                let Some(mut config) = profiles.profiles.get(name).cloned() else {
+                    return Err(ConfigError::ProfileNotFound {
+                        profile: name.to_owned(),
+                        file: Self::PROFILES_FILE,
+                    });
+                };
+                config.profile = Some(name.to_owned());
+                if config.state_passphrase.is_none() {
+                    config.state_passphrase =
+                        std::env::var(format!("{}STATE_PASSPHRASE", Self::CONFIG_PREFIX)).ok();
+                }
+                config
+            }
+            None => {
+                if let Some(profile) = profile {
+                    return Err(ConfigError::NoProfilesFile {
+                        profile: profile.to_owned(),
+                        file: Self::PROFILES_FILE,
+                    });
+                }
+                envy::prefixed(Self::CONFIG_PREFIX).from_env()?
+            }
+        };
 
-        if !config.is_valid() {
-            panic!("invalid configuration: {:?}", config);
-        }
+        config.validate().map_err(|problems| ConfigError::Invalid {
+            profile: config.profile.clone(),
+            problems,
+        })?;
+
+        Ok(config)
+    }
 
-        config
+    /// Names of the profiles available in the
+    /// [profiles file](Config::PROFILES_FILE).
+    pub fn profile_names() -> Result<Vec<String>, ConfigError> {
+        Ok(Self::read_profiles()?
+            .map(|profiles| profiles.profiles.into_keys().collect())
+            .unwrap_or_default())
     }
 
-    /// Check if configuration is set
-    pub fn is_valid(&self) -> bool {
-        !self.core_rpc_user.is_empty()
-            && !self.core_rpc_password.is_empty()
-            && self.core_rpc_port != 0
-            && !self.dapi_addresses.is_empty()
-            && Uri::from_str(&self.insight_api_url).is_ok()
-            && Network::from_str(&self.core_network_name()).is_ok()
+    fn read_profiles() -> Result<Option<Profiles>, ConfigError> {
+        let Ok(content) = fs::read_to_string(Self::PROFILES_FILE) else {
+            return Ok(None);
+        };
+
+        // TOML error's display quotes the offending line, which may hold a secret
+        toml::from_str(&content).map(Some).map_err(|e| {
+            let location = e
+                .span()
+                .map(|span| {
+                    let line = content[..span.start].matches('\n').count() + 1;
+                    format!("line {}: ", line)
+                })
+                .unwrap_or_default();
+            ConfigError::ProfilesFile {
+                file: Self::PROFILES_FILE,
+                message: format!("{}{}", location, e.message()),
+            }
+        })
+    }
+
+    /// Checks every field, collecting all problems found. Messages never
+    /// include secret values.
+    pub fn validate(&self) -> Result<(), Vec<ConfigProblem>> {
+        let mut problems = Vec::new();
+        let mut problem =
+            |field: &'static str, message: String| problems.push(ConfigProblem { field, message });
+
+        if self.dapi_addresses.trim().is_empty() {
+            problem("dapi_addresses", "no DAPI addresses set".to_owned());
+        }
+        for address in self
+            .dapi_addresses
+            .split(',')
+            .filter(|address| !address.trim().is_empty())
+        {
+            match Uri::from_str(address) {
+                Ok(uri) if uri.host().is_some() => {}
+                Ok(_) => problem(
+                    "dapi_addresses",
+                    format!("address {} has no host", without_userinfo(address)),
+                ),
+                Err(e) => problem(
+                    "dapi_addresses",
+                    format!(
+                        "unable to parse address {}: {}",
+                        without_userinfo(address),
+                        e
+                    ),
+                ),
+            }
+        }
+
+        if self.core_host.is_empty() {
+            problem("core_host", "must not be empty".to_owned());
+        }
+        if self.core_rpc_port == 0 {
+            problem("core_rpc_port", "must not be 0".to_owned());
+        }
+        if self.core_rpc_user.is_empty() {
+            problem("core_rpc_user", "must not be empty".to_owned());
+        }
+        if self.core_rpc_password.is_empty() {
+            problem("core_rpc_password", "must not be empty".to_owned());
+        }
+
+        match Uri::from_str(&self.insight_api_url) {
+            Ok(uri) if uri.host().is_some() => {}
+            Ok(_) => problem(
+                "insight_api_url",
+                format!("{} has no host", without_userinfo(&self.insight_api_url)),
+            ),
+            Err(e) => problem(
+                "insight_api_url",
+                format!(
+                    "invalid URI {}: {}",
+                    without_userinfo(&self.insight_api_url),
+                    e
+                ),
+            ),
+        }
+
+        if Network::from_str(self.core_network_name()).is_err() {
+            problem(
+                "network",
+                format!(
+                    "unknown network {}, expected local, regtest, devnet, testnet or dash",
+                    self.network
+                ),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
     }
 
     pub fn core_network(&self) -> Network {
@@ -88,7 +332,9 @@ impl Config {
 
     /// Returns path to the state file
     pub fn state_file_path(&self) -> PathBuf {
-        format!("{}_explorer.state", self.network).into()
+        self.state_file
+            .clone()
+            .unwrap_or_else(|| format!("{}_explorer.state", self.network).into())
     }
 
     fn core_network_name(&self) -> &str {
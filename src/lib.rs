@@ -1,5 +1,6 @@
 pub mod backend;
 pub mod config;
+pub mod load_testing;
 pub mod ui;
 
 use backend::BackendEvent;
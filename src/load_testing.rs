@@ -0,0 +1,5 @@
+//! Helpers shared by the load testing binaries.
+
+pub mod errors;
+pub mod latency;
+pub mod schedule;
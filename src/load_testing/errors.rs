@@ -0,0 +1,211 @@
+//! Categorization of the errors of load tests.
+//!
+//! Errors are grouped by endpoint and category and every group keeps the
+//! message of its first error as a sample, so a test failing for a single
+//! reason shows it at a glance instead of flooding the logs.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dapi_grpc::tonic::{Code, Status};
+use dashmap::DashMap;
+use dpp::{
+    consensus::{codes::ErrorWithCode, ConsensusError},
+    serialization::PlatformDeserializable,
+    ProtocolError,
+};
+use serde::{Serialize, Serializer};
+
+/// Metadata key DAPI puts serialized consensus errors under.
+const CONSENSUS_ERROR_METADATA: &str = "dash-serialized-consensus-error-bin";
+
+/// Endpoint of errors that don't tell where they come from.
+pub const UNKNOWN_ENDPOINT: &str = "unknown endpoint";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    Timeout,
+    ConnectionRefused,
+    /// Any other gRPC status
+    Grpc(Code),
+    ProofVerification,
+    /// State transition rejected by Platform with this consensus error code
+    Consensus(u32),
+    Other,
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCategory::Timeout => write!(f, "timeout"),
+            ErrorCategory::ConnectionRefused => write!(f, "connection refused"),
+            ErrorCategory::Grpc(code) => write!(f, "gRPC {:?}", code),
+            ErrorCategory::ProofVerification => write!(f, "proof verification"),
+            ErrorCategory::Consensus(code) => write!(f, "consensus error {}", code),
+            ErrorCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl Serialize for ErrorCategory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+fn is_timeout(message: &str) -> bool {
+    message.contains("Timeout expired") || message.contains("timed out")
+}
+
+fn is_connection_refused(message: &str) -> bool {
+    message.contains("Connection refused") || message.contains("connection refused")
+}
+
+impl ErrorCategory {
+    /// Category of a gRPC status returned by DAPI.
+    pub fn of_status(status: &Status) -> Self {
+        if let Some(code) = status
+            .metadata()
+            .get_bin(CONSENSUS_ERROR_METADATA)
+            .and_then(|value| value.to_bytes().ok())
+            .and_then(|bytes| ConsensusError::deserialize_from_bytes(&bytes).ok())
+            .map(|error| error.code())
+        {
+            return ErrorCategory::Consensus(code);
+        }
+
+        match status.code() {
+            Code::DeadlineExceeded => ErrorCategory::Timeout,
+            _ if is_timeout(status.message()) => ErrorCategory::Timeout,
+            _ if is_connection_refused(status.message()) => ErrorCategory::ConnectionRefused,
+            code => ErrorCategory::Grpc(code),
+        }
+    }
+
+    /// Category of an SDK error.
+    pub fn of_sdk_error(error: &dash_sdk::Error) -> Self {
+        match error {
+            dash_sdk::Error::Protocol(ProtocolError::ConsensusError(error)) => {
+                ErrorCategory::Consensus(error.code())
+            }
+            dash_sdk::Error::Proof(_) | dash_sdk::Error::InvalidProvedResponse(_) => {
+                ErrorCategory::ProofVerification
+            }
+            // The SDK only keeps the debug output of DAPI client errors
+            dash_sdk::Error::DapiClientError(message) => {
+                if is_timeout(message) {
+                    ErrorCategory::Timeout
+                } else if is_connection_refused(message) {
+                    ErrorCategory::ConnectionRefused
+                } else {
+                    grpc_code_in(message)
+                        .map(ErrorCategory::Grpc)
+                        .unwrap_or(ErrorCategory::Other)
+                }
+            }
+            _ => ErrorCategory::Other,
+        }
+    }
+}
+
+/// Code of the gRPC status in the debug output of a DAPI client error.
+fn grpc_code_in(message: &str) -> Option<Code> {
+    let name = message
+        .split_once("code: ")?
+        .1
+        .split(|c: char| !c.is_alphanumeric())
+        .next()?;
+    (0..=16)
+        .map(Code::from)
+        .find(|code| format!("{:?}", code) == name)
+}
+
+/// Endpoint in the debug output of a DAPI client error, if any.
+pub fn endpoint_in(error: &dash_sdk::Error) -> Option<&str> {
+    let dash_sdk::Error::DapiClientError(message) = error else {
+        return None;
+    };
+    message
+        .split_once("uri: ")?
+        .1
+        .split([',', ' ', '}'])
+        .next()
+        .filter(|uri| !uri.is_empty())
+}
+
+struct ErrorGroup {
+    count: AtomicU64,
+    sample: String,
+}
+
+/// Errors of a test grouped by endpoint and category.
+#[derive(Default)]
+pub struct ErrorTally {
+    groups: DashMap<(String, ErrorCategory), ErrorGroup>,
+}
+
+/// Number of errors of a category at an endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCount {
+    pub endpoint: String,
+    pub category: ErrorCategory,
+    pub count: u64,
+    /// Message of the first error of the group
+    pub sample: String,
+}
+
+impl ErrorTally {
+    /// Counts an error, `message` is only rendered for the first error of its
+    /// group.
+    pub fn add(&self, endpoint: &str, category: ErrorCategory, message: impl FnOnce() -> String) {
+        self.groups
+            .entry((endpoint.to_owned(), category))
+            .or_insert_with(|| ErrorGroup {
+                count: AtomicU64::new(0),
+                sample: message(),
+            })
+            .count
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.groups
+            .iter()
+            .map(|group| group.count.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Error groups by endpoint and category.
+    pub fn counts(&self) -> Vec<ErrorCount> {
+        let mut counts: Vec<ErrorCount> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let (endpoint, category) = group.key().clone();
+                ErrorCount {
+                    endpoint,
+                    category,
+                    count: group.count.load(Ordering::Relaxed),
+                    sample: group.sample.clone(),
+                }
+            })
+            .collect();
+        counts.sort_by_cached_key(|error| (error.endpoint.clone(), error.category.to_string()));
+        counts
+    }
+
+    /// One line per error group, empty if there were no errors.
+    pub fn report_message(&self) -> String {
+        self.counts()
+            .into_iter()
+            .map(|error| {
+                format!(
+                    "\n  {} {}: {} (e.g. {})",
+                    error.endpoint, error.category, error.count, error.sample
+                )
+            })
+            .collect()
+    }
+}
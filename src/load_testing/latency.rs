@@ -0,0 +1,130 @@
+//! Latency histograms of load tests.
+//!
+//! Latencies are recorded in microseconds into log-linear buckets: values
+//! below 128µs get a bucket each, above that every power of two is split into
+//! 64 buckets, so reported percentiles are within 1.6% of the actual value.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+/// Number of linear sub-buckets of every power of two.
+const SUB_BUCKETS: u64 = 64;
+/// Latencies above are recorded as this value, about 73 minutes.
+const MAX_TRACKED_MICROS: u64 = (1 << 32) - 1;
+const BUCKETS: usize = bucket_index(MAX_TRACKED_MICROS) + 1;
+
+const fn bucket_index(micros: u64) -> usize {
+    let bits = u64::BITS - micros.leading_zeros();
+    let shift = bits.saturating_sub(7) as u64;
+    (SUB_BUCKETS * shift + (micros >> shift)) as usize
+}
+
+/// Highest value recorded in the bucket.
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < 2 * SUB_BUCKETS {
+        index
+    } else {
+        let shift = index / SUB_BUCKETS - 1;
+        let mantissa = index - SUB_BUCKETS * shift;
+        ((mantissa + 1) << shift) - 1
+    }
+}
+
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            count: Default::default(),
+            sum_micros: Default::default(),
+            max_micros: Default::default(),
+        }
+    }
+}
+
+impl Histogram {
+    pub fn record(&self, latency: Duration) {
+        let micros = (latency.as_micros() as u64).min(MAX_TRACKED_MICROS);
+
+        self.buckets[bucket_index(micros)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+        self.max_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the recorded latencies, histograms are written to while
+    /// they are summarized so the figures may be slightly off.
+    pub fn summary(&self) -> LatencySummary {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        let count: u64 = counts.iter().sum();
+        let max_micros = self.max_micros.load(Ordering::Relaxed);
+
+        let percentile = |quantile: f64| {
+            if count == 0 {
+                return 0;
+            }
+            let rank = ((quantile * count as f64).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (index, bucket_count) in counts.iter().enumerate() {
+                seen += bucket_count;
+                if seen >= rank {
+                    return bucket_upper_bound(index).min(max_micros);
+                }
+            }
+            max_micros
+        };
+
+        LatencySummary {
+            count,
+            mean_ms: millis(
+                self.sum_micros
+                    .load(Ordering::Relaxed)
+                    .checked_div(self.count.load(Ordering::Relaxed))
+                    .unwrap_or(0),
+            ),
+            p50_ms: millis(percentile(0.5)),
+            p90_ms: millis(percentile(0.9)),
+            p99_ms: millis(percentile(0.99)),
+            max_ms: millis(max_micros),
+        }
+    }
+}
+
+fn millis(micros: u64) -> f64 {
+    micros as f64 / 1000.0
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl std::fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p50 {:.1}ms, p90 {:.1}ms, p99 {:.1}ms, max {:.1}ms",
+            self.p50_ms, self.p90_ms, self.p99_ms, self.max_ms
+        )
+    }
+}
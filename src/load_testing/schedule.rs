@@ -0,0 +1,244 @@
+//! Open-loop scheduling of load test requests.
+//!
+//! Requests are sent at the times a rate profile dictates whether or not
+//! earlier ones have completed. When the tested system stalls, the requests
+//! that should have been sent meanwhile go out as soon as possible and their
+//! latency is measured from the time they were intended to be sent, so
+//! stalls show up in the latencies instead of silently lowering the rate.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use tokio::time::Instant;
+
+/// Time to wait before looking at the rate again while it is below
+/// [MIN_RATE].
+const IDLE_STEP: Duration = Duration::from_millis(1);
+
+/// Lowest nonzero rate accepted, one request a day.
+const MIN_RATE: f64 = 1.0 / 86_400.0;
+
+/// Request rate over the course of a test, in requests per second.
+///
+/// Profiles are written on the command line as:
+/// - `constant:RATE`
+/// - `ramp:FROM..TO/SECS`: rate going linearly from `FROM` to `TO` in `SECS`
+///   seconds, then staying at `TO`
+/// - `step:RATE/SECS,RATE/SECS,...`: every rate held for its seconds, the last
+///   one staying until the end of the test
+/// - `spike:BASE,PEAK@START/SECS`: `BASE` rate jumping to `PEAK` for `SECS`
+///   seconds `START` seconds into the test
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateProfile {
+    Constant(f64),
+    Ramp {
+        from: f64,
+        to: f64,
+        over: Duration,
+    },
+    Step(Vec<(f64, Duration)>),
+    Spike {
+        base: f64,
+        peak: f64,
+        at: Duration,
+        lasting: Duration,
+    },
+}
+
+impl RateProfile {
+    /// Rate `elapsed` into the test.
+    pub fn rate_at(&self, elapsed: Duration) -> f64 {
+        match self {
+            RateProfile::Constant(rate) => *rate,
+            RateProfile::Ramp { from, to, over } => {
+                if elapsed >= *over {
+                    *to
+                } else {
+                    from + (to - from) * elapsed.as_secs_f64() / over.as_secs_f64()
+                }
+            }
+            RateProfile::Step(steps) => {
+                let mut step_end = Duration::ZERO;
+                for (rate, duration) in steps {
+                    step_end = step_end.saturating_add(*duration);
+                    if elapsed < step_end {
+                        return *rate;
+                    }
+                }
+                steps.last().map(|(rate, _)| *rate).unwrap_or_default()
+            }
+            RateProfile::Spike {
+                base,
+                peak,
+                at,
+                lasting,
+            } => {
+                if elapsed >= *at && elapsed < at.saturating_add(*lasting) {
+                    *peak
+                } else {
+                    *base
+                }
+            }
+        }
+    }
+
+    /// Rate the profile settles on.
+    fn final_rate(&self) -> f64 {
+        match self {
+            RateProfile::Constant(rate) => *rate,
+            RateProfile::Ramp { to, .. } => *to,
+            RateProfile::Step(steps) => steps.last().map(|(rate, _)| *rate).unwrap_or_default(),
+            RateProfile::Spike { base, .. } => *base,
+        }
+    }
+}
+
+impl fmt::Display for RateProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateProfile::Constant(rate) => write!(f, "{} per second", rate),
+            RateProfile::Ramp { from, to, over } => write!(
+                f,
+                "{} to {} per second over {} seconds",
+                from,
+                to,
+                over.as_secs_f64()
+            ),
+            RateProfile::Step(steps) => {
+                let steps: Vec<String> = steps
+                    .iter()
+                    .map(|(rate, duration)| format!("{} for {}s", rate, duration.as_secs_f64()))
+                    .collect();
+                write!(f, "steps of {} per second", steps.join(", "))
+            }
+            RateProfile::Spike {
+                base,
+                peak,
+                at,
+                lasting,
+            } => write!(
+                f,
+                "{} per second with a spike to {} at {}s for {}s",
+                base,
+                peak,
+                at.as_secs_f64(),
+                lasting.as_secs_f64()
+            ),
+        }
+    }
+}
+
+fn parse_rate(rate: &str) -> Result<f64, String> {
+    rate.trim()
+        .parse()
+        .ok()
+        .filter(|rate: &f64| rate.is_finite() && (*rate == 0.0 || *rate >= MIN_RATE))
+        .ok_or_else(|| {
+            format!(
+                "invalid rate {}, expected 0 or at least {} per second",
+                rate, MIN_RATE
+            )
+        })
+}
+
+fn parse_secs(secs: &str) -> Result<Duration, String> {
+    secs.trim()
+        .parse()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("invalid number of seconds {}", secs))
+}
+
+fn split_pair<'s>(
+    value: &'s str,
+    separator: &str,
+    expected: &str,
+) -> Result<(&'s str, &'s str), String> {
+    value
+        .split_once(separator)
+        .ok_or_else(|| format!("expected {}, got {}", expected, value))
+}
+
+impl FromStr for RateProfile {
+    type Err = String;
+
+    fn from_str(profile: &str) -> Result<Self, Self::Err> {
+        let (kind, params) = split_pair(profile, ":", "KIND:PARAMETERS")?;
+        let profile = match kind {
+            "constant" => RateProfile::Constant(parse_rate(params)?),
+            "ramp" => {
+                let (rates, over) = split_pair(params, "/", "FROM..TO/SECS")?;
+                let (from, to) = split_pair(rates, "..", "FROM..TO/SECS")?;
+                RateProfile::Ramp {
+                    from: parse_rate(from)?,
+                    to: parse_rate(to)?,
+                    over: parse_secs(over)?,
+                }
+            }
+            "step" => RateProfile::Step(
+                params
+                    .split(',')
+                    .map(|step| {
+                        let (rate, duration) = split_pair(step, "/", "RATE/SECS")?;
+                        Ok((parse_rate(rate)?, parse_secs(duration)?))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            "spike" => {
+                let (rates, window) = split_pair(params, "@", "BASE,PEAK@START/SECS")?;
+                let (base, peak) = split_pair(rates, ",", "BASE,PEAK@START/SECS")?;
+                let (at, lasting) = split_pair(window, "/", "BASE,PEAK@START/SECS")?;
+                RateProfile::Spike {
+                    base: parse_rate(base)?,
+                    peak: parse_rate(peak)?,
+                    at: parse_secs(at)?,
+                    lasting: parse_secs(lasting)?,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "unknown rate profile {}, expected constant, ramp, step or spike",
+                    kind
+                ))
+            }
+        };
+
+        if profile.final_rate() > 0.0 {
+            Ok(profile)
+        } else {
+            Err("the rate must end up above zero".to_owned())
+        }
+    }
+}
+
+/// Intended send times of the requests of a test following a rate profile.
+pub struct OpenLoopSchedule {
+    profile: RateProfile,
+    start: Instant,
+    next: Duration,
+}
+
+impl OpenLoopSchedule {
+    pub fn new(profile: RateProfile, start: Instant) -> Self {
+        Self {
+            profile,
+            start,
+            next: Duration::ZERO,
+        }
+    }
+
+    /// Time the next request is intended to be sent at, regardless of how
+    /// long previous requests take.
+    pub fn next_send_time(&mut self) -> Instant {
+        let mut rate = self.profile.rate_at(self.next);
+        // A ramp starting from zero passes through rates low enough to put the
+        // next request days away
+        while rate < MIN_RATE {
+            self.next += IDLE_STEP;
+            rate = self.profile.rate_at(self.next);
+        }
+
+        let send_time = self.start + self.next;
+        self.next += Duration::from_secs_f64(1.0 / rate);
+        send_time
+    }
+}
@@ -1,18 +1,39 @@
 use std::{fs::File, panic, time::Duration};
 
+use clap::{ArgAction, Parser};
 use crossterm::event::{Event as TuiEvent, EventStream};
-use dash_sdk::{RequestSettings, SdkBuilder};
+use dash_sdk::{RequestSettings, Sdk, SdkBuilder};
 use dpp::{identity::accessors::IdentityGettersV0, version::PlatformVersion};
-use futures::{future::OptionFuture, select, FutureExt, StreamExt};
+use futures::{
+    future::{FusedFuture, OptionFuture},
+    select,
+    stream::Fuse,
+    FutureExt, StreamExt,
+};
 use rs_platform_explorer::{
-    backend::{insight::InsightAPIClient, Backend},
+    backend::{
+        insight::InsightAPIClient, scheduler::TaskScheduler, state::AppState, AppStateError,
+        Backend, BackendEvent,
+    },
     config::Config,
     ui::{IdentityBalance, Ui, UiFeedback},
     Event,
 };
 
+#[derive(Parser, Debug)]
+#[clap(about, long_about = None)]
+struct Args {
+    #[arg(long, action = ArgAction::SetTrue, help = "Upgrades the state file to the current schema version, reports what was carried over and exits.")]
+    migrate_state: bool,
+
+    #[arg(long, help = "Name of the configuration profile to use.")]
+    profile: Option<String>,
+}
+
 #[tokio::main]
 async fn main() {
+    let args = Args::parse();
+
     // Initialize logger
     let log_file = File::create("explorer.log").expect("create log file");
 
@@ -51,9 +72,128 @@ async fn main() {
     }));
 
     // Load configuration
-    let config = Config::load();
+    let config = Config::load(args.profile.as_deref());
+
+    if args.migrate_state {
+        let config = match config {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+        match AppState::migrate_file(&config) {
+            Ok(report) => println!("{}", report),
+            Err(e) => {
+                eprintln!("Failed to migrate application state: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut ui = Ui::new(None);
+    let mut terminal_event_stream = EventStream::new().fuse();
+
+    let mut config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            ui.show_error(
+                &mut terminal_event_stream,
+                "Configuration error",
+                &e.to_string(),
+            )
+            .await;
+            drop(ui);
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Every profile switch restarts the backend with a new SDK and state file
+    loop {
+        let sdk = build_sdk(&config);
+        let insight = InsightAPIClient::new(config.insight_api_uri());
+
+        // Load the state, asking for a passphrase if the state file is encrypted
+        let backend = loop {
+            let prompt_error = match Backend::new(&sdk, insight.clone(), config.clone()).await {
+                Ok(backend) => break backend,
+                Err(AppStateError::PassphraseRequired(_)) => None,
+                Err(e @ AppStateError::Decryption(..)) => Some(e.to_string()),
+                Err(e) => {
+                    drop(ui);
+                    eprintln!("Failed to load application state: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match ui
+                .prompt_passphrase(&mut terminal_event_stream, prompt_error.as_deref())
+                .await
+            {
+                Some(passphrase) => config.state_passphrase = Some(passphrase),
+                None => return,
+            }
+        };
+
+        // Add loaded identity to known identities if it's not already there
+        // And set selected_strategy to None
+        {
+            let state = backend.state();
+            let loaded_identity = state.loaded_identity.lock().await;
+            let mut selected_strategy = state.selected_strategy.lock().await;
+            let mut known_identities = state.known_identities.lock().await;
+
+            if let Some(loaded_identity) = loaded_identity.as_ref() {
+                known_identities
+                    .entry(loaded_identity.id())
+                    .or_insert_with(|| loaded_identity.clone());
+            }
+
+            *selected_strategy = None;
+        }
+
+        let initial_identity_balance = backend
+            .state()
+            .loaded_identity
+            .lock()
+            .await
+            .as_ref()
+            .map(|identity| IdentityBalance::from_credits(identity.balance()));
+
+        ui.set_identity_balance(initial_identity_balance);
 
-    // Setup Platform SDK
+        // Keep the current profile if the requested one is misconfigured
+        let next_config = loop {
+            let Some(profile) = run_ui(&mut ui, &backend, &mut terminal_event_stream).await else {
+                return;
+            };
+
+            match Config::load(Some(&profile)) {
+                Ok(next_config) => {
+                    tracing::info!("Switching to profile {}", profile);
+                    break next_config;
+                }
+                Err(e) => {
+                    ui.show_error(
+                        &mut terminal_event_stream,
+                        "Unable to switch profile",
+                        &e.to_string(),
+                    )
+                    .await
+                }
+            }
+        };
+
+        // Save the current state before the next profile is loaded
+        drop(backend);
+        config = next_config;
+        ui.reset();
+    }
+}
+
+fn build_sdk(config: &Config) -> Sdk {
     let address_list = config.dapi_address_list();
     let request_settings = RequestSettings {
         connect_timeout: Some(Duration::from_secs(10)),
@@ -61,7 +201,7 @@ async fn main() {
         retries: None,
         ban_failed_address: Some(false),
     };
-    let sdk = SdkBuilder::new(address_list)
+    SdkBuilder::new(address_list)
         .with_version(PlatformVersion::get(1).unwrap())
         .with_core(
             &config.core_host,
@@ -71,46 +211,23 @@ async fn main() {
         )
         .with_settings(request_settings)
         .build()
-        .expect("expected to build sdk");
-
-    let insight = InsightAPIClient::new(config.insight_api_uri());
-
-    let backend = Backend::new(&sdk, insight, config).await;
-
-    // Add loaded identity to known identities if it's not already there
-    // And set selected_strategy to None
-    {
-        let state = backend.state();
-        let loaded_identity = state.loaded_identity.lock().await;
-        let mut selected_strategy = state.selected_strategy.lock().await;
-        let mut known_identities = state.known_identities.lock().await;
-
-        if let Some(loaded_identity) = loaded_identity.as_ref() {
-            known_identities
-                .entry(loaded_identity.id())
-                .or_insert_with(|| loaded_identity.clone());
-        }
-
-        *selected_strategy = None;
-    }
-
-    let initial_identity_balance = backend
-        .state()
-        .loaded_identity
-        .lock()
-        .await
-        .as_ref()
-        .map(|identity| IdentityBalance::from_credits(identity.balance()));
-
-    let mut ui = Ui::new(initial_identity_balance);
-
-    let mut active = true;
+        .expect("expected to build sdk")
+}
 
-    let mut terminal_event_stream = EventStream::new().fuse();
-    let mut backend_task: OptionFuture<_> = None.into();
+/// Runs the UI event loop until the user quits, returns the profile to switch
+/// to if it was requested. Running tasks are dropped on return.
+async fn run_ui(
+    ui: &mut Ui,
+    backend: &Backend<'_>,
+    terminal_event_stream: &mut Fuse<EventStream>,
+) -> Option<String> {
+    let mut scheduler = TaskScheduler::new();
+    let mut strategy_progress = backend.strategy_progress();
     let mut ui_debounced_redraw: OptionFuture<_> = None.into();
 
-    while active {
+    loop {
+        let mut finished_task = None;
+
         let event = select! {
             terminal_event = terminal_event_stream.next() => match terminal_event {
                 None => panic!("terminal event stream closed unexpectedly"),
@@ -119,10 +236,23 @@ async fn main() {
                 Some(Ok(TuiEvent::Key(key_event))) => Some(Event::Key(key_event.into())),
                 _ => None
             },
-            backend_task_finished = backend_task => backend_task_finished.map(Event::Backend),
+            finished = scheduler.next_finished() => {
+                finished_task = Some(finished.task_id);
+                Some(Event::Backend(finished.event))
+            },
+            progress = strategy_progress.recv().fuse() => match progress {
+                Ok(progress) => Some(Event::Backend(BackendEvent::StrategyProgress(progress))),
+                // Lagged behind, the newer snapshots are still to be received
+                Err(_) => None,
+            },
             ui_redraw = ui_debounced_redraw => ui_redraw.map(|_| Event::RedrawDebounceTimeout),
         };
 
+        if let Some(task_id) = finished_task {
+            scheduler.finish(task_id);
+            ui.update_tasks(&scheduler);
+        }
+
         let ui_feedback = match event {
             Some(event @ (Event::Backend(_) | Event::Key(_))) => {
                 ui.on_event(backend.state(), event).await
@@ -135,9 +265,18 @@ async fn main() {
         };
 
         match ui_feedback {
-            UiFeedback::Quit => active = false,
+            UiFeedback::Quit => return None,
+            UiFeedback::SwitchProfile(profile) => return Some(profile),
             UiFeedback::ExecuteTask(task) => {
-                backend_task = Some(backend.run_task(task.clone()).boxed_local().fuse()).into();
+                let task_id = scheduler.spawn(backend, task);
+                tracing::info!("Started task #{}", task_id);
+                ui.update_tasks(&scheduler);
+                ui.redraw();
+            }
+            UiFeedback::CancelTask(task_id) => {
+                if scheduler.cancel(task_id) {
+                    tracing::info!("Cancelling task #{}", task_id);
+                }
                 ui.redraw();
             }
             UiFeedback::Redraw => {
@@ -150,5 +289,15 @@ async fn main() {
             }
             UiFeedback::None => (),
         }
+
+        // Keep running tasks' elapsed time ticking
+        if !scheduler.is_idle() && ui_debounced_redraw.is_terminated() {
+            ui_debounced_redraw = Some(
+                tokio::time::sleep(Duration::from_secs(1))
+                    .boxed_local()
+                    .fuse(),
+            )
+            .into();
+        }
     }
 }
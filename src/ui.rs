@@ -7,24 +7,36 @@
 mod form;
 mod screen;
 mod status_bar;
+mod tasks;
 pub(crate) mod views;
 
-use std::{mem, ops::Deref, time::Instant};
+use std::{io, mem, ops::Deref, time::Instant};
 
+use crossterm::event::Event as TuiEvent;
 use dpp::identity::accessors::IdentityGettersV0;
+use futures::{Stream, StreamExt};
 use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderSides},
     terminal::TerminalBridge,
-    tui::prelude::{Constraint, Direction, Layout},
+    tui::{
+        prelude::{Constraint, Direction, Layout},
+        widgets::{Block, Paragraph, Wrap},
+    },
 };
 
 use self::{
-    form::{Form, FormController, FormStatus},
+    form::{Form, FormController, FormStatus, Input, InputStatus, TextInput},
     screen::{Screen, ScreenController, ScreenFeedback},
     status_bar::StatusBarState,
+    tasks::TasksPane,
     views::{main::MainScreenController, strategies::StrategiesScreenController},
 };
 use crate::{
-    backend::{AppState, AppStateUpdate, BackendEvent, Task},
+    backend::{
+        scheduler::{TaskId, TaskScheduler},
+        AppState, AppStateUpdate, BackendEvent, Task,
+    },
     Event,
 };
 
@@ -51,7 +63,7 @@ pub struct Ui {
     status_bar_state: StatusBarState,
     screen: Screen<Box<dyn ScreenController>>,
     form: Option<Form<Box<dyn FormController>>>,
-    blocked: bool,
+    tasks_pane: TasksPane,
     screen_stack: Vec<Screen<Box<dyn ScreenController>>>,
 }
 
@@ -60,6 +72,9 @@ pub enum UiFeedback {
     Redraw,
     Quit,
     ExecuteTask(Task),
+    CancelTask(TaskId),
+    /// The backend shall be restarted with the named configuration profile
+    SwitchProfile(String),
     None,
 }
 
@@ -70,7 +85,14 @@ impl Ui {
             .draw(|frame| {
                 let layout = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Min(10), Constraint::Max(3)].as_ref())
+                    .constraints(
+                        [
+                            Constraint::Min(10),
+                            Constraint::Length(self.tasks_pane.height()),
+                            Constraint::Max(3),
+                        ]
+                        .as_ref(),
+                    )
                     .split(frame.size());
 
                 if let Some(form) = &mut self.form {
@@ -78,7 +100,10 @@ impl Ui {
                 } else {
                     self.screen.view(frame, layout[0])
                 };
-                self.status_bar_state.view(frame, layout[1]);
+                if !self.tasks_pane.is_empty() {
+                    self.tasks_pane.view(frame, layout[1]);
+                }
+                self.status_bar_state.view(frame, layout[2]);
             })
             .expect("unable to draw to terminal");
     }
@@ -108,7 +133,7 @@ impl Ui {
             status_bar_state,
             screen,
             form: None,
-            blocked: false,
+            tasks_pane: TasksPane::default(),
             screen_stack: Vec::new(),
         };
 
@@ -116,6 +141,121 @@ impl Ui {
         ui
     }
 
+    /// Brings the UI back to the main screen, used once the backend was
+    /// restarted and screens hold outdated data.
+    pub fn reset(&mut self) {
+        let main_screen_controller = MainScreenController::new();
+
+        self.status_bar_state = StatusBarState::default();
+        self.status_bar_state
+            .add_child(main_screen_controller.name());
+        self.screen = Screen::new(Box::new(main_screen_controller) as Box<dyn ScreenController>);
+        self.screen_stack.clear();
+        self.form = None;
+        self.tasks_pane = TasksPane::default();
+
+        self.redraw();
+    }
+
+    /// Refreshes the running tasks pane.
+    pub fn update_tasks(&mut self, scheduler: &TaskScheduler) {
+        self.tasks_pane.update(scheduler);
+    }
+
+    pub fn set_identity_balance(&mut self, balance: Option<IdentityBalance>) {
+        match balance {
+            Some(balance) => self.status_bar_state.update_balance(balance),
+            None => self.status_bar_state.clear_balance(),
+        }
+        self.redraw();
+    }
+
+    /// Asks for the state file passphrase before the backend is started.
+    /// Returns `None` if the user exits the prompt.
+    ///
+    /// Only encrypted state files are unlocked this way, encryption of a plain
+    /// text state file is turned on with the `state_passphrase` setting.
+    pub async fn prompt_passphrase<S>(
+        &mut self,
+        terminal_events: &mut S,
+        error: Option<&str>,
+    ) -> Option<String>
+    where
+        S: Stream<Item = io::Result<TuiEvent>> + Unpin,
+    {
+        let mut input = TextInput::new_password("State file passphrase");
+
+        loop {
+            self.terminal
+                .raw_mut()
+                .draw(|frame| {
+                    let block = Block::new()
+                        .borders(BorderSides::ALL)
+                        .title("Unlock state file")
+                        .title_alignment(Alignment::Left);
+                    let layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Max(2), Constraint::Min(4)].as_ref())
+                        .split(block.inner(frame.size()));
+
+                    if let Some(error) = error {
+                        frame.render_widget(Paragraph::new(error), layout[0]);
+                    }
+                    input.view(frame, layout[1]);
+                    frame.render_widget(block, frame.size());
+                })
+                .expect("unable to draw to terminal");
+
+            match terminal_events.next().await {
+                Some(Ok(TuiEvent::Key(key_event))) => match input.on_event(key_event.into()) {
+                    InputStatus::Done(passphrase) => return Some(passphrase),
+                    InputStatus::Exit => return None,
+                    InputStatus::Redraw | InputStatus::None => {}
+                },
+                Some(Ok(_)) => {}
+                _ => panic!("terminal event stream closed unexpectedly"),
+            }
+        }
+    }
+
+    /// Shows an error that doesn't belong to any screen, such as a startup
+    /// error, and waits for a key press.
+    pub async fn show_error<S>(&mut self, terminal_events: &mut S, title: &str, message: &str)
+    where
+        S: Stream<Item = io::Result<TuiEvent>> + Unpin,
+    {
+        loop {
+            self.terminal
+                .raw_mut()
+                .draw(|frame| {
+                    let block = Block::new()
+                        .borders(BorderSides::ALL)
+                        .title(title)
+                        .title_alignment(Alignment::Left);
+                    let layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(1), Constraint::Max(1)].as_ref())
+                        .split(block.inner(frame.size()));
+
+                    frame.render_widget(
+                        Paragraph::new(message).wrap(Wrap { trim: false }),
+                        layout[0],
+                    );
+                    frame.render_widget(Paragraph::new("Press any key to continue"), layout[1]);
+                    frame.render_widget(block, frame.size());
+                })
+                .expect("unable to draw to terminal");
+
+            match terminal_events.next().await {
+                Some(Ok(TuiEvent::Key(_))) => break,
+                Some(Ok(_)) => {}
+                _ => panic!("terminal event stream closed unexpectedly"),
+            }
+        }
+
+        self.redraw();
+    }
+
     pub async fn on_event<'s>(
         &mut self,
         app_state: impl Deref<Target = AppState>,
@@ -123,17 +263,15 @@ impl Ui {
     ) -> UiFeedback {
         let mut redraw = false;
 
-        // On task completion we shall unfreeze the screen and update status bar
-        // "blocked" message
+        // On task completion the running tasks pane shall be updated
         if let Event::Backend(
             BackendEvent::TaskCompleted { .. }
             | BackendEvent::TaskCompletedStateChange { .. }
             | BackendEvent::StrategyCompleted { .. }
-            | BackendEvent::StrategyError { .. },
+            | BackendEvent::StrategyError { .. }
+            | BackendEvent::TaskCancelled { .. },
         ) = &event
         {
-            self.status_bar_state.unblock();
-            self.blocked = false;
             redraw = true;
         }
 
@@ -200,20 +338,32 @@ impl Ui {
             }
         }
 
-        if self.blocked {
-            return UiFeedback::None;
+        // Running tasks can be cancelled from anywhere
+        if let Event::Key(KeyEvent {
+            code: Key::Char('t'),
+            modifiers: KeyModifiers::CONTROL,
+        }) = &event
+        {
+            if !self.tasks_pane.is_empty() {
+                self.form = Some(Form::new(Box::new(self.tasks_pane.cancel_form())));
+                return UiFeedback::Redraw;
+            }
         }
 
         let ui_feedback = if let (Some(form), Event::Key(event)) = (&mut self.form, &event) {
             match form.on_event(*event) {
-                FormStatus::Done { task, block } => {
+                FormStatus::Done { task } => {
                     self.form = None;
-                    if block {
-                        self.status_bar_state.block();
-                        self.blocked = true;
-                    }
                     UiFeedback::ExecuteTask(task)
                 }
+                FormStatus::CancelTask(task_id) => {
+                    self.form = None;
+                    UiFeedback::CancelTask(task_id)
+                }
+                FormStatus::SwitchProfile(profile) => {
+                    self.form = None;
+                    UiFeedback::SwitchProfile(profile)
+                }
                 FormStatus::NextScreen(controller_builder) => {
                     self.form = None;
                     let controller = controller_builder(app_state.deref()).await;
@@ -291,13 +441,7 @@ impl Ui {
 
                     UiFeedback::Redraw
                 }
-                ScreenFeedback::Task { task, block } => {
-                    if block {
-                        self.status_bar_state.block();
-                        self.blocked = true;
-                    }
-                    UiFeedback::ExecuteTask(task)
-                }
+                ScreenFeedback::Task { task } => UiFeedback::ExecuteTask(task),
                 ScreenFeedback::Redraw => UiFeedback::Redraw,
                 ScreenFeedback::Quit => UiFeedback::Quit,
                 ScreenFeedback::None => UiFeedback::None,
@@ -20,7 +20,7 @@ pub(crate) use self::{
     },
 };
 use super::screen::ScreenControllerBuilder;
-use crate::backend::Task;
+use crate::backend::{scheduler::TaskId, Task};
 
 /// Trait of every component suitable for processing user input.
 /// Serves as a building block of a form as one may require several of them
@@ -96,7 +96,13 @@ impl<C: FormController> Form<C> {
 /// [FormController] to process all of the results to produce a [Task] to
 /// return, since a user's input precedes some action.
 pub(crate) enum FormStatus {
-    Done { task: Task, block: bool },
+    Done {
+        task: Task,
+    },
+    /// Restart the backend with another configuration profile
+    SwitchProfile(String),
+    /// Cancel a running backend task
+    CancelTask(TaskId),
     NextScreen(ScreenControllerBuilder),
     PreviousScreen,
     Redraw,
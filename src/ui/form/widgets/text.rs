@@ -7,7 +7,7 @@ use std::{fmt::Display, str::FromStr};
 use tuirealm::{
     command::{self, Cmd},
     event::{Key, KeyEvent, KeyModifiers},
-    props::{BorderSides, Borders, Color, Style},
+    props::{BorderSides, Borders, Color, InputType, Style},
     tui::{
         prelude::{Constraint, Direction, Layout, Rect},
         widgets::Paragraph,
@@ -46,6 +46,15 @@ where
     }
 }
 
+impl TextInput<DefaultTextInputParser<String>> {
+    /// Text input that doesn't show typed characters.
+    pub(crate) fn new_password(placeholder: &'static str) -> Self {
+        let mut text_input = Self::new(placeholder);
+        text_input.input = text_input.input.input_type(InputType::Password('*'));
+        text_input
+    }
+}
+
 impl<P> TextInput<P>
 where
     P: TextInputParser,
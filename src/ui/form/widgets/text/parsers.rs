@@ -30,6 +30,32 @@ impl<T: FromStr> TextInputParser for DefaultTextInputParser<T> {
     }
 }
 
+/// Parses a value that can be left out, empty input gives `None`.
+pub(crate) struct OptionalTextInputParser<T: FromStr> {
+    _t: PhantomData<T>,
+}
+
+impl<T: FromStr> OptionalTextInputParser<T> {
+    pub(crate) fn new() -> Self {
+        OptionalTextInputParser { _t: PhantomData }
+    }
+}
+
+impl<T: FromStr> TextInputParser for OptionalTextInputParser<T> {
+    type Output = Option<T>;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        input
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("Cannot parse as a {}", std::any::type_name::<T>()))
+    }
+}
+
 pub(crate) struct DocumentQueryTextInputParser {
     data_contract: DataContract,
 }
@@ -132,8 +132,7 @@ pub(crate) enum ScreenFeedback {
     },
     Task {
         task: Task,
-        block: bool,
-    }, // TODO task should define whether it blocks or not
+    },
     Redraw,
     Quit,
     None,
@@ -6,7 +6,7 @@ use tui_realm_stdlib::Label;
 use tuirealm::{
     props::BorderSides,
     tui::{
-        prelude::{Constraint, Direction, Layout, Modifier, Rect},
+        prelude::{Constraint, Direction, Layout, Rect},
         widgets::Block,
     },
     Frame, MockComponent,
@@ -17,7 +17,6 @@ use super::IdentityBalance;
 #[derive(Default)]
 pub(crate) struct StatusBarState {
     breadcrumbs: Vec<&'static str>,
-    blocked: bool,
     identity_loaded_balance: IdentityBalanceStatus,
 }
 
@@ -65,14 +64,6 @@ impl StatusBarState {
         self.identity_loaded_balance = IdentityBalanceStatus::RefreshError;
     }
 
-    pub(crate) fn block(&mut self) {
-        self.blocked = true;
-    }
-
-    pub(crate) fn unblock(&mut self) {
-        self.blocked = false;
-    }
-
     pub(crate) fn add_child(&mut self, name: &'static str) {
         self.breadcrumbs.push(name);
     }
@@ -92,14 +83,9 @@ impl StatusBarState {
 
         let breadcrumbs_str = self.breadcrumbs.join(" / ");
 
-        if self.blocked {
-            Label::default()
-                .text("Executing a task, please wait")
-                .modifiers(Modifier::RAPID_BLINK) // TODO: doesn't work lol
-        } else {
-            Label::default().text(&breadcrumbs_str)
-        }
-        .view(frame, layout[0]);
+        Label::default()
+            .text(&breadcrumbs_str)
+            .view(frame, layout[0]);
 
         Label::default()
             .text(&self.identity_loaded_balance.to_string())
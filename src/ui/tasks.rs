@@ -0,0 +1,142 @@
+//! Running backend tasks pane and task cancellation.
+
+use std::{
+    fmt::{self, Display},
+    time::Instant,
+};
+
+use tuirealm::{
+    event::KeyEvent,
+    props::{Alignment, BorderSides},
+    tui::{
+        prelude::Rect,
+        widgets::{Block, Paragraph},
+    },
+    Frame,
+};
+
+use super::form::{FormController, FormStatus, Input, InputStatus, SelectInput};
+use crate::backend::scheduler::{TaskId, TaskScheduler};
+
+/// Task descriptions are cut to this length.
+const TASK_LABEL_LENGTH: usize = 60;
+
+/// At most this many tasks are listed in the pane.
+const PANE_TASKS: usize = 5;
+
+#[derive(Clone)]
+pub(super) struct TaskEntry {
+    id: TaskId,
+    label: String,
+    started: Instant,
+}
+
+impl Display for TaskEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} ({}s)",
+            self.id,
+            self.label,
+            self.started.elapsed().as_secs()
+        )
+    }
+}
+
+/// Snapshot of the scheduler's running tasks for display.
+#[derive(Default)]
+pub(super) struct TasksPane {
+    tasks: Vec<TaskEntry>,
+}
+
+impl TasksPane {
+    pub(super) fn update(&mut self, scheduler: &TaskScheduler) {
+        self.tasks = scheduler
+            .running_tasks()
+            .map(|running_task| {
+                let mut label = running_task.task.to_string();
+                if let Some((cut, _)) = label.char_indices().nth(TASK_LABEL_LENGTH) {
+                    label.truncate(cut);
+                    label.push('…');
+                }
+                TaskEntry {
+                    id: running_task.id,
+                    label,
+                    started: running_task.started,
+                }
+            })
+            .collect();
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Height required to draw the pane.
+    pub(super) fn height(&self) -> u16 {
+        if self.tasks.is_empty() {
+            0
+        } else {
+            self.tasks.len().min(PANE_TASKS) as u16 + 2
+        }
+    }
+
+    pub(super) fn view(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::new()
+            .borders(BorderSides::ALL)
+            .title(format!(
+                "Running tasks: {} (Ctrl+t to cancel)",
+                self.tasks.len()
+            ))
+            .title_alignment(Alignment::Left);
+
+        let lines = self
+            .tasks
+            .iter()
+            .take(PANE_TASKS)
+            .map(|task| task.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    pub(super) fn cancel_form(&self) -> CancelTaskFormController {
+        CancelTaskFormController {
+            input: SelectInput::new(self.tasks.clone()),
+        }
+    }
+}
+
+pub(super) struct CancelTaskFormController {
+    input: SelectInput<TaskEntry>,
+}
+
+impl FormController for CancelTaskFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(task) => FormStatus::CancelTask(task.id),
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Cancel task"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Task"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
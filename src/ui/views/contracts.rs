@@ -218,7 +218,6 @@ impl FormController for RemoveContractFormController {
         match self.input.on_event(event) {
             InputStatus::Done(contract_name) => FormStatus::Done {
                 task: Task::Contract(ContractTask::RemoveContract(contract_name)),
-                block: false,
             },
             InputStatus::Exit => FormStatus::Exit,
             status => status.into(),
@@ -285,7 +285,6 @@ impl FormController for QueryDocumentTypeFormController {
         match self.input.on_event(event) {
             InputStatus::Done(query) => FormStatus::Done {
                 task: Task::Document(DocumentTask::QueryDocuments(query)),
-                block: true,
             },
             status => status.into(),
         }
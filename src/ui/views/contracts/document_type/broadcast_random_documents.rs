@@ -5,12 +5,16 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 use crate::{
     backend::{documents::DocumentTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus, TextInput,
+        parsers::{DefaultTextInputParser, OptionalTextInputParser},
+        ComposedInput, Field, FormController, FormStatus, Input, InputStatus, TextInput,
     },
 };
 
 pub(super) struct BroadcastRandomDocumentsCountForm {
-    input: TextInput<DefaultTextInputParser<u16>>,
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u16>>>,
+        Field<TextInput<OptionalTextInputParser<u64>>>,
+    )>,
     data_contract_name: String,
     document_type_name: String,
 }
@@ -18,7 +22,19 @@ pub(super) struct BroadcastRandomDocumentsCountForm {
 impl BroadcastRandomDocumentsCountForm {
     pub fn new(data_contract_name: String, document_type_name: String) -> Self {
         BroadcastRandomDocumentsCountForm {
-            input: TextInput::new_init_value("Number of random documents", 1),
+            input: ComposedInput::new((
+                Field::new(
+                    "Documents count",
+                    TextInput::new_init_value("Number of random documents", 1),
+                ),
+                Field::new(
+                    "Seed",
+                    TextInput::new_with_parser(
+                        OptionalTextInputParser::new(),
+                        "Leave empty for a random seed",
+                    ),
+                ),
+            )),
             data_contract_name,
             document_type_name,
         }
@@ -28,13 +44,13 @@ impl BroadcastRandomDocumentsCountForm {
 impl FormController for BroadcastRandomDocumentsCountForm {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
-            InputStatus::Done(count) => FormStatus::Done {
+            InputStatus::Done((count, seed)) => FormStatus::Done {
                 task: Task::Document(DocumentTask::BroadcastRandomDocuments {
                     data_contract_name: self.data_contract_name.clone(),
                     document_type_name: self.document_type_name.clone(),
                     count,
+                    seed,
                 }),
-                block: true,
             },
             status => status.into(),
         }
@@ -49,14 +65,14 @@ impl FormController for BroadcastRandomDocumentsCountForm {
     }
 
     fn step_name(&self) -> &'static str {
-        "Documents count"
+        self.input.step_name()
     }
 
     fn step_index(&self) -> u8 {
-        0
+        self.input.step_index()
     }
 
     fn steps_number(&self) -> u8 {
-        1
+        self.input.steps_number()
     }
 }
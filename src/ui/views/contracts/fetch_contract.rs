@@ -106,7 +106,6 @@ impl FormController for GetContractByIdFormController {
         match self.input.on_event(event) {
             InputStatus::Done(value) => FormStatus::Done {
                 task: Task::Contract(FetchContract(value)),
-                block: true,
             },
             status => status.into(),
         }
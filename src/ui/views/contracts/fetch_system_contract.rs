@@ -64,7 +64,6 @@ impl ScreenController for FetchSystemContractScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Task {
                 task: Task::Contract(ContractTask::FetchDashpayContract),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -72,7 +71,6 @@ impl ScreenController for FetchSystemContractScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Task {
                 task: Task::Contract(ContractTask::FetchDPNSContract),
-                block: true,
             },
 
             Event::Backend(
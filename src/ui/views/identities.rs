@@ -164,7 +164,6 @@ impl FormController for GetIdentityByIdFormController {
         match self.input.on_event(event) {
             InputStatus::Done(value) => FormStatus::Done {
                 task: Task::FetchIdentityById(value, false),
-                block: true,
             },
             status => status.into(),
         }
@@ -217,7 +216,6 @@ impl FormController for TransferCreditsFormController {
         match self.input.on_event(event) {
             InputStatus::Done((recipient, amount)) => FormStatus::Done {
                 task: Task::Identity(IdentityTask::TransferCredits(recipient, amount)),
-                block: true,
             },
             status => status.into(),
         }
@@ -263,7 +261,6 @@ impl FormController for RegisterDPNSNameFormController {
         match self.input.on_event(event) {
             InputStatus::Done(value) => FormStatus::Done {
                 task: Task::Identity(IdentityTask::RegisterDPNSName(value)),
-                block: true,
             },
             status => status.into(),
         }
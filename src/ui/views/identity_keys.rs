@@ -40,12 +40,11 @@ impl FormController for ViewIdentityKeysScreenController {
                     operation: Operation {
                         op_type: OperationType::IdentityTopUp,
                         frequency: Frequency {
-                            times_per_block_range: 1..times_per_block+1,
+                            times_per_block_range: 1..times_per_block + 1,
                             chance_per_block: Some(chance_per_block),
                         },
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
@@ -9,7 +9,13 @@ use tuirealm::{
 use super::{contracts::ContractsScreenController, wallet::WalletScreenController};
 use crate::ui::views::strategies::StrategiesScreenController;
 use crate::{
+    backend::{BackendEvent, StateTask, Task},
+    config::Config,
     ui::{
+        form::{
+            parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus,
+            Input, InputStatus, SelectInput, TextInput,
+        },
         screen::{
             utils::impl_builder_no_args, widgets::info::Info, ScreenCommandKey, ScreenController,
             ScreenFeedback, ScreenToggleKey,
@@ -23,7 +29,7 @@ use crate::{
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 7] = [
+const COMMAND_KEYS: [ScreenCommandKey; 10] = [
     ScreenCommandKey::new("q", "Quit"),
     ScreenCommandKey::new("i", "Identities"),
     ScreenCommandKey::new("c", "Contracts"),
@@ -31,6 +37,9 @@ const COMMAND_KEYS: [ScreenCommandKey; 7] = [
     ScreenCommandKey::new("w", "Wallet"),
     ScreenCommandKey::new("v", "Version Upgrade"),
     ScreenCommandKey::new("p", "Platform information"),
+    ScreenCommandKey::new("e", "Export state to JSON"),
+    ScreenCommandKey::new("m", "Import state from JSON"),
+    ScreenCommandKey::new("o", "Switch profile"),
 ];
 
 pub(crate) struct MainScreenController {
@@ -102,6 +111,40 @@ impl ScreenController for MainScreenController {
                 code: Key::Char('p'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::NextScreen(PlatformInfoScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ExportStateFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ImportStateFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('o'),
+                modifiers: KeyModifiers::NONE,
+            }) => match Config::profile_names() {
+                Ok(profiles) if !profiles.is_empty() => {
+                    ScreenFeedback::Form(Box::new(SwitchProfileFormController::new(profiles)))
+                }
+                Ok(_) => {
+                    self.info = Info::new_error(&format!(
+                        "No profiles found, create {} to use them",
+                        Config::PROFILES_FILE
+                    ));
+                    ScreenFeedback::Redraw
+                }
+                Err(e) => {
+                    self.info = Info::new_error(&e.to_string());
+                    ScreenFeedback::Redraw
+                }
+            },
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::State(_),
+                execution_result,
+            }) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
             _ => ScreenFeedback::None,
         }
     }
@@ -110,3 +153,142 @@ impl ScreenController for MainScreenController {
         self.info.view(frame, area)
     }
 }
+
+struct ExportStateFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>,
+        Field<SelectInput<String>>,
+    )>,
+}
+
+impl ExportStateFormController {
+    fn new() -> Self {
+        ExportStateFormController {
+            input: ComposedInput::new((
+                Field::new("File path", TextInput::new("explorer_state.json")),
+                Field::new(
+                    "Redact private keys, wallet secrets and strategies",
+                    SelectInput::new(vec!["Yes".to_string(), "No".to_string()]),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for ExportStateFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((path, redact_secrets)) => FormStatus::Done {
+                task: Task::State(StateTask::ExportJson {
+                    path,
+                    redact_secrets: redact_secrets == "Yes",
+                }),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Export state"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        2
+    }
+}
+
+struct ImportStateFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl ImportStateFormController {
+    fn new() -> Self {
+        ImportStateFormController {
+            input: TextInput::new("explorer_state.json"),
+        }
+    }
+}
+
+impl FormController for ImportStateFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::State(StateTask::ImportJson(path)),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import state"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "File path"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+struct SwitchProfileFormController {
+    input: SelectInput<String>,
+}
+
+impl SwitchProfileFormController {
+    fn new(profiles: Vec<String>) -> Self {
+        SwitchProfileFormController {
+            input: SelectInput::new(profiles),
+        }
+    }
+}
+
+impl FormController for SwitchProfileFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(profile) => FormStatus::SwitchProfile(profile),
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Switch profile"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Profile"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
@@ -72,7 +72,6 @@ impl ScreenController for PlatformInfoScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Task {
                 task: Task::PlatformInfo(FetchCurrentEpochInfo),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -80,7 +79,6 @@ impl ScreenController for PlatformInfoScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Task {
                 task: Task::PlatformInfo(FetchCurrentVersionVotingState),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -121,7 +119,6 @@ impl FormController for EpochNumberChooserFormController {
         match self.input.on_event(event) {
             InputStatus::Done(epoch) => FormStatus::Done {
                 task: Task::PlatformInfo(FetchSpecificEpochInfo(epoch)),
-                block: true,
             },
             InputStatus::Redraw => FormStatus::Redraw,
             InputStatus::None => FormStatus::None,
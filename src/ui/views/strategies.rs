@@ -1,5 +1,6 @@
 //! Screens and forms related to strategies manipulation.
 
+mod entries;
 mod identity_inserts;
 mod operations;
 mod run_strategy;
@@ -7,6 +8,9 @@ pub mod selected_strategy;
 mod start_contracts;
 mod start_identities;
 
+use std::{fmt, fs};
+
+use itertools::Itertools;
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
     tui::prelude::Rect,
@@ -24,17 +28,21 @@ use crate::{
 };
 
 use crate::{
-    backend::{StrategyTask, Task},
+    backend::{
+        strategies::{strategy_name_from_source, EXPORTS_DIR},
+        StrategyTask, Task,
+    },
     ui::form::{
         parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
         SelectInput, TextInput,
     },
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 6] = [
+const COMMAND_KEYS: [ScreenCommandKey; 7] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("n", "New strategy"),
     ScreenCommandKey::new("i", "Import a strategy"),
+    ScreenCommandKey::new("l", "Load a strategy definition file"),
     ScreenCommandKey::new("e", "Export a strategy"),
     ScreenCommandKey::new("s", "Select a strategy"),
     ScreenCommandKey::new("d", "Delete a strategy"),
@@ -78,7 +86,7 @@ impl ScreenController for StrategiesScreenController {
 
     fn command_keys(&self) -> &[ScreenCommandKey] {
         if self.available_strategies.is_empty() {
-            &COMMAND_KEYS[..3] // Exclude certain operations when there are no available strategies
+            &COMMAND_KEYS[..4] // Exclude certain operations when there are no available strategies
         } else {
             COMMAND_KEYS.as_ref()
         }
@@ -105,7 +113,16 @@ impl ScreenController for StrategiesScreenController {
                 code: Key::Char('i'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::FormThenNextScreen {
-                form: Box::new(ImportStrategyFormController::new()),
+                form: Box::new(ImportStrategyFormController::new(
+                    self.available_strategies.clone(),
+                )),
+                screen: SelectedStrategyScreenController::builder(),
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('l'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::FormThenNextScreen {
+                form: Box::new(LoadStrategyDefinitionFormController::new()),
                 screen: SelectedStrategyScreenController::builder(),
             },
             Event::Key(KeyEvent {
@@ -217,7 +234,6 @@ impl FormController for NewStrategyFormController {
         match self.input.on_event(event) {
             InputStatus::Done(strategy_name) => FormStatus::Done {
                 task: Task::Strategy(StrategyTask::CreateStrategy(strategy_name)),
-                block: false,
             },
             status => status.into(),
         }
@@ -244,31 +260,213 @@ impl FormController for NewStrategyFormController {
     }
 }
 
+/// Entry of the import source list to type a URL or path instead.
+const OTHER_SOURCE: &str = "Other URL or path";
+
+#[derive(Clone, Copy, PartialEq)]
+enum ImportStep {
+    Source,
+    Location,
+    Conflict,
+    Name,
+}
+
+#[derive(Clone)]
+enum ConflictChoice {
+    Rename,
+    Overwrite(String),
+    Cancel,
+}
+
+impl fmt::Display for ConflictChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictChoice::Rename => write!(f, "Import under another name"),
+            ConflictChoice::Overwrite(name) => write!(f, "Overwrite strategy {}", name),
+            ConflictChoice::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
+
 pub(crate) struct ImportStrategyFormController {
-    input: TextInput<DefaultTextInputParser<String>>,
+    step: ImportStep,
+    source_input: SelectInput<String>,
+    location_input: TextInput<DefaultTextInputParser<String>>,
+    conflict_input: SelectInput<ConflictChoice>,
+    name_input: TextInput<DefaultTextInputParser<String>>,
+    existing_strategies: Vec<String>,
+    source: String,
+    strategy_name: String,
 }
 
 impl ImportStrategyFormController {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(existing_strategies: Vec<String>) -> Self {
+        let mut sources = exported_strategy_files();
+        let step = if sources.is_empty() {
+            ImportStep::Location
+        } else {
+            ImportStep::Source
+        };
+        sources.push(OTHER_SOURCE.to_owned());
+
         Self {
-            input: TextInput::new("Raw Github file URL (ex: https://raw.githubusercontent.com/pauldelucia/dash-platform-strategy-tests/main/example)"),
+            step,
+            source_input: SelectInput::new(sources),
+            location_input: TextInput::new("URL, file:// URL or path (ex: https://raw.githubusercontent.com/pauldelucia/dash-platform-strategy-tests/main/example)"),
+            conflict_input: SelectInput::new(Vec::new()),
+            name_input: TextInput::new("strategy name"),
+            existing_strategies,
+            source: String::new(),
+            strategy_name: String::new(),
+        }
+    }
+
+    fn source_chosen(&mut self, source: String) -> FormStatus {
+        let strategy_name = strategy_name_from_source(&source);
+        self.source = source;
+        match strategy_name {
+            Some(strategy_name) => self.name_chosen(strategy_name),
+            None => {
+                self.step = ImportStep::Name;
+                FormStatus::Redraw
+            }
+        }
+    }
+
+    fn name_chosen(&mut self, strategy_name: String) -> FormStatus {
+        if self.existing_strategies.contains(&strategy_name) {
+            self.conflict_input = SelectInput::new(vec![
+                ConflictChoice::Rename,
+                ConflictChoice::Overwrite(strategy_name.clone()),
+                ConflictChoice::Cancel,
+            ]);
+            self.strategy_name = strategy_name;
+            self.step = ImportStep::Conflict;
+            FormStatus::Redraw
+        } else {
+            self.strategy_name = strategy_name;
+            self.import(false)
+        }
+    }
+
+    fn import(&self, overwrite: bool) -> FormStatus {
+        FormStatus::Done {
+            task: Task::Strategy(StrategyTask::ImportStrategy {
+                source: self.source.clone(),
+                strategy_name: self.strategy_name.clone(),
+                overwrite,
+            }),
         }
     }
 }
 
+/// Files of previously exported strategies, sorted by name.
+fn exported_strategy_files() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(EXPORTS_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .map(|path| path.display().to_string())
+        .sorted()
+        .collect()
+}
+
 impl FormController for ImportStrategyFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.step {
+            ImportStep::Source => match self.source_input.on_event(event) {
+                InputStatus::Done(source) if source == OTHER_SOURCE => {
+                    self.step = ImportStep::Location;
+                    FormStatus::Redraw
+                }
+                InputStatus::Done(source) => self.source_chosen(source),
+                status => status.into(),
+            },
+            ImportStep::Location => match self.location_input.on_event(event) {
+                InputStatus::Done(location) => self.source_chosen(location),
+                status => status.into(),
+            },
+            ImportStep::Conflict => match self.conflict_input.on_event(event) {
+                InputStatus::Done(ConflictChoice::Rename) => {
+                    self.step = ImportStep::Name;
+                    FormStatus::Redraw
+                }
+                InputStatus::Done(ConflictChoice::Overwrite(_)) => self.import(true),
+                InputStatus::Done(ConflictChoice::Cancel) => FormStatus::Exit,
+                status => status.into(),
+            },
+            ImportStep::Name => match self.name_input.on_event(event) {
+                InputStatus::Done(strategy_name) => self.name_chosen(strategy_name),
+                status => status.into(),
+            },
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import strategy"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match self.step {
+            ImportStep::Source => self.source_input.view(frame, area),
+            ImportStep::Location => self.location_input.view(frame, area),
+            ImportStep::Conflict => self.conflict_input.view(frame, area),
+            ImportStep::Name => self.name_input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match self.step {
+            ImportStep::Source => "Exported strategy",
+            ImportStep::Location => "Url or path",
+            ImportStep::Conflict => "A strategy with this name already exists",
+            ImportStep::Name => "Strategy name",
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match self.step {
+            ImportStep::Source => 0,
+            ImportStep::Location => 1,
+            ImportStep::Conflict => 2,
+            ImportStep::Name => 3,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        4
+    }
+}
+
+pub(crate) struct LoadStrategyDefinitionFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl LoadStrategyDefinitionFormController {
+    pub(crate) fn new() -> Self {
+        Self {
+            input: TextInput::new(
+                "Path to a .toml or .json file (ex: supporting_files/strategy_definitions/example.toml)",
+            ),
+        }
+    }
+}
+
+impl FormController for LoadStrategyDefinitionFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
-            InputStatus::Done(url) => FormStatus::Done {
-                task: Task::Strategy(StrategyTask::ImportStrategy(url)),
-                block: false,
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::LoadStrategyDefinition(path.into())),
             },
             status => status.into(),
         }
     }
 
     fn form_name(&self) -> &'static str {
-        "Import strategy"
+        "Load strategy definition"
     }
 
     fn step_view(&mut self, frame: &mut Frame, area: Rect) {
@@ -276,7 +474,7 @@ impl FormController for ImportStrategyFormController {
     }
 
     fn step_name(&self) -> &'static str {
-        "Url"
+        "Path"
     }
 
     fn step_index(&self) -> u8 {
@@ -305,7 +503,6 @@ impl FormController for ExportStrategyFormController {
         match self.input.on_event(event) {
             InputStatus::Done(strategy_name) => FormStatus::Done {
                 task: Task::Strategy(StrategyTask::ExportStrategy(strategy_name)),
-                block: false,
             },
             InputStatus::Exit => FormStatus::Exit,
             status => status.into(),
@@ -350,7 +547,6 @@ impl FormController for SelectStrategyFormController {
         match self.input.on_event(event) {
             InputStatus::Done(strategy_name) => FormStatus::Done {
                 task: Task::Strategy(StrategyTask::SelectStrategy(strategy_name)),
-                block: false,
             },
             InputStatus::Exit => FormStatus::Exit,
             status => status.into(),
@@ -414,7 +610,6 @@ impl FormController for DeleteStrategyFormController {
                             task: Task::Strategy(StrategyTask::DeleteStrategy(
                                 self.selected_strategy.clone().unwrap(),
                             )),
-                            block: false,
                         }
                     } else {
                         FormStatus::Exit
@@ -0,0 +1,181 @@
+//! Forms to pick an entry of a strategy list (operations or start contracts)
+//! and remove, duplicate or move it by index.
+
+use std::fmt::{self, Display};
+
+use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+
+use crate::{
+    backend::{StrategyTask, Task},
+    ui::form::{FormController, FormStatus, Input, InputStatus, SelectInput},
+};
+
+/// Entry of a strategy list as shown in selection inputs.
+#[derive(Clone)]
+pub(super) struct Entry {
+    pub(super) index: usize,
+    label: String,
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}. {}", self.index + 1, self.label)
+    }
+}
+
+/// Numbers labels in the order of the list.
+pub(super) fn entries(labels: impl IntoIterator<Item = String>) -> Vec<Entry> {
+    labels
+        .into_iter()
+        .enumerate()
+        .map(|(index, label)| Entry { index, label })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum EntryList {
+    Operations,
+    Contracts,
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum EntryAction {
+    Remove,
+    Duplicate,
+    Move,
+}
+
+pub(super) struct EntryActionFormController {
+    strategy_name: String,
+    list: EntryList,
+    action: EntryAction,
+    entries_count: usize,
+    entry_input: SelectInput<Entry>,
+    /// Set once the entry to move is selected
+    position_input: Option<(usize, SelectInput<usize>)>,
+}
+
+impl EntryActionFormController {
+    pub(super) fn new(
+        strategy_name: String,
+        list: EntryList,
+        action: EntryAction,
+        entries: Vec<Entry>,
+    ) -> Self {
+        Self {
+            strategy_name,
+            list,
+            action,
+            entries_count: entries.len(),
+            entry_input: SelectInput::new(entries),
+            position_input: None,
+        }
+    }
+
+    fn task(&self, index: usize) -> Task {
+        let strategy_name = self.strategy_name.clone();
+        Task::Strategy(match (self.list, self.action) {
+            (EntryList::Operations, EntryAction::Remove) => StrategyTask::RemoveOperation {
+                strategy_name,
+                index,
+            },
+            (EntryList::Operations, EntryAction::Duplicate) => StrategyTask::DuplicateOperation {
+                strategy_name,
+                index,
+            },
+            (EntryList::Contracts, EntryAction::Remove) => StrategyTask::RemoveContract {
+                strategy_name,
+                index,
+            },
+            (EntryList::Contracts, EntryAction::Duplicate) => StrategyTask::DuplicateContract {
+                strategy_name,
+                index,
+            },
+            (_, EntryAction::Move) => unreachable!("moves are built with a target position"),
+        })
+    }
+
+    fn move_task(&self, from: usize, to: usize) -> Task {
+        let strategy_name = self.strategy_name.clone();
+        Task::Strategy(match self.list {
+            EntryList::Operations => StrategyTask::MoveOperation {
+                strategy_name,
+                from,
+                to,
+            },
+            EntryList::Contracts => StrategyTask::MoveContract {
+                strategy_name,
+                from,
+                to,
+            },
+        })
+    }
+}
+
+impl FormController for EntryActionFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        if let Some((from, position_input)) = &mut self.position_input {
+            let from = *from;
+            return match position_input.on_event(event) {
+                InputStatus::Done(position) => FormStatus::Done {
+                    task: self.move_task(from, position - 1),
+                },
+                status => status.into(),
+            };
+        }
+
+        match self.entry_input.on_event(event) {
+            InputStatus::Done(entry) => match self.action {
+                EntryAction::Move => {
+                    self.position_input = Some((
+                        entry.index,
+                        SelectInput::new_init_line((1..=self.entries_count).collect(), entry.index),
+                    ));
+                    FormStatus::Redraw
+                }
+                _ => FormStatus::Done {
+                    task: self.task(entry.index),
+                },
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        match (self.list, self.action) {
+            (EntryList::Operations, EntryAction::Remove) => "Remove operation",
+            (EntryList::Operations, EntryAction::Duplicate) => "Duplicate operation",
+            (EntryList::Operations, EntryAction::Move) => "Move operation",
+            (EntryList::Contracts, EntryAction::Remove) => "Remove start contract",
+            (EntryList::Contracts, EntryAction::Duplicate) => "Duplicate start contract",
+            (EntryList::Contracts, EntryAction::Move) => "Move start contract",
+        }
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some((_, position_input)) = &mut self.position_input {
+            position_input.view(frame, area)
+        } else {
+            self.entry_input.view(frame, area)
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        if self.position_input.is_some() {
+            "New position"
+        } else {
+            "Select entry"
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        self.position_input.is_some() as u8
+    }
+
+    fn steps_number(&self) -> u8 {
+        match self.action {
+            EntryAction::Move => 2,
+            _ => 1,
+        }
+    }
+}
@@ -97,7 +97,6 @@ impl ScreenController for IdentityInsertsScreenController {
                 task: Task::Strategy(StrategyTask::RemoveIdentityInserts(
                     self.strategy_name.clone().unwrap(),
                 )),
-                block: false,
             },
             Event::Backend(BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                 strategy_name,
@@ -222,7 +221,6 @@ impl FormController for StrategyIdentityInsertsFormController {
                         chance_per_block: Some(chance),
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
@@ -4,10 +4,12 @@ mod contract_create;
 mod contract_update_doc_types;
 mod contract_update_new_fields;
 mod document;
+mod edit;
 mod identity_top_up;
 mod identity_transfer;
 mod identity_update;
 mod identity_withdrawal;
+mod keys;
 
 use std::collections::BTreeMap;
 
@@ -15,20 +17,26 @@ use dash_sdk::platform::DataContract;
 use dpp::data_contract::document_type::random_document::{
     DocumentFieldFillSize, DocumentFieldFillType,
 };
+use rand::rngs::StdRng;
 use tracing::error;
 use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 
 use self::{
     contract_create::StrategyOpContractCreateFormController,
     contract_update_doc_types::StrategyOpContractUpdateDocTypesFormController,
-    document::StrategyOpDocumentFormController,
+    document::StrategyOpDocumentFormController, edit::StrategyEditOperationFormController,
     identity_top_up::StrategyOpIdentityTopUpFormController,
     identity_transfer::StrategyOpIdentityTransferFormController,
     identity_update::StrategyOpIdentityUpdateFormController,
     identity_withdrawal::StrategyOpIdentityWithdrawalFormController,
+    keys::StrategyOperationKeyFormController,
 };
+use super::entries::{entries, Entry, EntryAction, EntryActionFormController, EntryList};
 use crate::{
-    backend::{StrategyContractNames, StrategyTask, Task},
+    backend::{
+        strategies::{strategy_rng, OPERATIONS_RNG_STREAM},
+        StrategyContractNames, StrategyOperationKeys, StrategyTask, Task,
+    },
     ui::form::{ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput},
 };
 
@@ -36,6 +44,7 @@ use dpp::{
     data_contract::{
         accessors::v0::DataContractV0Getters, created_data_contract::CreatedDataContract,
     },
+    identity::{accessors::IdentityGettersV0, Identity},
     platform_value::string_encoding::Encoding,
     tests::json_document::json_document_to_contract,
     version::PlatformVersion,
@@ -59,11 +68,16 @@ use crate::{
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const COMMAND_KEYS: [ScreenCommandKey; 10] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("a", "Add"),
+    ScreenCommandKey::new("e", "Edit"),
+    ScreenCommandKey::new("d", "Delete"),
+    ScreenCommandKey::new("u", "Duplicate"),
+    ScreenCommandKey::new("m", "Move"),
     ScreenCommandKey::new("r", "Remove last"),
     ScreenCommandKey::new("c", "Clear all"),
+    ScreenCommandKey::new("k", "Signing key"),
     ScreenCommandKey::new("x", "Register x documents to all contracts"),
 ];
 
@@ -78,6 +92,9 @@ pub struct OperationsScreenController {
     known_contracts: BTreeMap<String, DataContract>,
     supporting_contracts: BTreeMap<String, DataContract>,
     strategy_contract_names: BTreeMap<String, Vec<(String, Option<BTreeMap<u64, String>>)>>,
+    operation_keys: StrategyOperationKeys,
+    seed: Option<u64>,
+    loaded_identity: Option<Identity>,
 }
 
 impl_builder!(OperationsScreenController);
@@ -85,11 +102,13 @@ impl_builder!(OperationsScreenController);
 impl OperationsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
         let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
         let known_contracts_lock = app_state.known_contracts.lock().await;
         let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
         let strategy_contract_names_lock =
             app_state.available_strategies_contract_names.lock().await;
+        let selected_strategy_lock = app_state.selected_strategy.lock().await;
+        let operation_keys_lock = app_state.available_strategies_operation_keys.lock().await;
+        let seeds_lock = app_state.available_strategies_seeds.lock().await;
 
         let (info_text, current_strategy, current_start_contracts) =
             if let Some(selected_strategy_name) = &*selected_strategy_lock {
@@ -116,6 +135,16 @@ impl OperationsScreenController {
             known_contracts: known_contracts_lock.clone(),
             supporting_contracts: supporting_contracts_lock.clone(),
             strategy_contract_names: strategy_contract_names_lock.clone(),
+            operation_keys: selected_strategy_lock
+                .as_ref()
+                .and_then(|strategy_name| operation_keys_lock.get(strategy_name))
+                .cloned()
+                .unwrap_or_default(),
+            seed: selected_strategy_lock
+                .as_ref()
+                .and_then(|strategy_name| seeds_lock.get(strategy_name))
+                .copied(),
+            loaded_identity: app_state.loaded_identity.lock().await.clone(),
         }
     }
 
@@ -138,6 +167,36 @@ impl OperationsScreenController {
         }
     }
 
+    /// Numbered operations of the selected strategy, `None` if there are
+    /// none to pick from.
+    fn operation_entries(&self) -> Option<(String, Vec<Entry>)> {
+        let strategy_name = self.strategy_name.clone()?;
+        let strategy = self.selected_strategy.as_ref()?;
+        if strategy.operations.is_empty() {
+            return None;
+        }
+        let operation_entries = entries(
+            strategy
+                .operations
+                .iter()
+                .map(|op| format_operation_name(&op.op_type)),
+        );
+        Some((strategy_name, operation_entries))
+    }
+
+    fn entry_action_form(&self, action: EntryAction) -> ScreenFeedback {
+        if let Some((strategy_name, operation_entries)) = self.operation_entries() {
+            ScreenFeedback::Form(Box::new(EntryActionFormController::new(
+                strategy_name,
+                EntryList::Operations,
+                action,
+                operation_entries,
+            )))
+        } else {
+            ScreenFeedback::None
+        }
+    }
+
     fn update_supporting_contracts_sync(&mut self) {
         // Use block_in_place to wait for the async operation to complete
         tokio::task::block_in_place(|| {
@@ -182,16 +241,52 @@ impl ScreenController for OperationsScreenController {
                         .get(&strategy_name)
                         .expect("Expected to get strategy contract names in operations screen");
 
+                    // Random parameters of the new operation follow the strategy seed
+                    let position = self
+                        .selected_strategy
+                        .as_ref()
+                        .map_or(0, |strategy| strategy.operations.len());
+                    let rng = strategy_rng(self.seed, OPERATIONS_RNG_STREAM + position as u64);
+
                     ScreenFeedback::Form(Box::new(StrategyAddOperationFormController::new(
                         strategy_name.clone(),
                         self.known_contracts.clone(),
                         self.supporting_contracts.clone(),
                         strategy_contract_names.to_vec(),
+                        rng,
                     )))
                 } else {
                     ScreenFeedback::None
                 }
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let (Some((strategy_name, operation_entries)), Some(strategy)) =
+                    (self.operation_entries(), &self.selected_strategy)
+                {
+                    ScreenFeedback::Form(Box::new(StrategyEditOperationFormController::new(
+                        strategy_name,
+                        strategy.operations.clone(),
+                        operation_entries,
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Remove),
+            Event::Key(KeyEvent {
+                code: Key::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Duplicate),
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Move),
             Event::Key(KeyEvent {
                 code: Key::Char('r'),
                 modifiers: KeyModifiers::NONE,
@@ -199,7 +294,6 @@ impl ScreenController for OperationsScreenController {
                 task: Task::Strategy(StrategyTask::RemoveLastOperation(
                     self.strategy_name.clone().unwrap(),
                 )),
-                block: false,
             },
             Event::Key(KeyEvent {
                 code: Key::Char('c'),
@@ -208,12 +302,27 @@ impl ScreenController for OperationsScreenController {
                 if let Some(strategy_name) = &self.strategy_name {
                     ScreenFeedback::Task {
                         task: Task::Strategy(StrategyTask::ClearOperations(strategy_name.clone())),
-                        block: false,
                     }
                 } else {
                     ScreenFeedback::None
                 }
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('k'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some((strategy_name, operation_entries)) = self.operation_entries() {
+                    ScreenFeedback::Form(Box::new(StrategyOperationKeyFormController::new(
+                        strategy_name,
+                        operation_entries,
+                        self.loaded_identity
+                            .iter()
+                            .flat_map(|identity| identity.public_keys().values()),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
             Event::Key(KeyEvent {
                 code: Key::Char('x'),
                 modifiers: KeyModifiers::NONE,
@@ -238,6 +347,10 @@ impl ScreenController for OperationsScreenController {
                     ..
                 },
             ) => {
+                if self.strategy_name.as_ref() != Some(strategy_name) {
+                    self.operation_keys.clear();
+                }
+                self.operation_keys.truncate(strategy.operations.len());
                 self.selected_strategy = Some((*strategy).clone());
                 self.strategy_name = Some(strategy_name.clone());
                 self.start_contracts = strategy.start_contracts.clone();
@@ -250,6 +363,29 @@ impl ScreenController for OperationsScreenController {
 
                 ScreenFeedback::Redraw
             }
+            Event::Backend(BackendEvent::AppStateUpdated(AppStateUpdate::OperationKeys(
+                strategy_name,
+                operation_keys,
+            ))) if self.strategy_name.as_ref() == Some(strategy_name) => {
+                self.operation_keys = operation_keys.to_vec();
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(
+                BackendEvent::AppStateUpdated(AppStateUpdate::LoadedIdentity(identity))
+                | BackendEvent::TaskCompletedStateChange {
+                    app_state_update: AppStateUpdate::LoadedIdentity(identity),
+                    ..
+                },
+            ) => {
+                self.loaded_identity = Some((*identity).clone());
+                ScreenFeedback::None
+            }
+            Event::Backend(BackendEvent::AppStateUpdated(
+                AppStateUpdate::ClearedLoadedIdentity,
+            )) => {
+                self.loaded_identity = None;
+                ScreenFeedback::None
+            }
             _ => ScreenFeedback::None,
         }
     }
@@ -257,7 +393,7 @@ impl ScreenController for OperationsScreenController {
     fn view(&mut self, frame: &mut Frame, area: Rect) {
         let display_text = if let Some(strategy) = &self.selected_strategy {
             let mut operations_lines = String::new();
-            for op in &strategy.operations {
+            for (index, op) in strategy.operations.iter().enumerate() {
                 let op_name = format_operation_name(&op.op_type);
                 let times_per_block_display = if op.frequency.times_per_block_range.end
                     > op.frequency.times_per_block_range.start
@@ -266,12 +402,18 @@ impl ScreenController for OperationsScreenController {
                 } else {
                     op.frequency.times_per_block_range.end
                 };
+                let signing_key_display = match self.operation_keys.get(index) {
+                    Some(Some(key)) => format!("; Signed with {}", key),
+                    _ => String::new(),
+                };
                 operations_lines.push_str(&format!(
-                    "{:indent$}{}; Times per block: {}, chance per block: {}\n",
+                    "{:indent$}{}. {}; Times per block: {}, chance per block: {}{}\n",
                     "",
+                    index + 1,
                     op_name,
                     times_per_block_display,
                     op.frequency.chance_per_block.unwrap_or(0.0),
+                    signing_key_display,
                     indent = 0
                 ));
             }
@@ -352,6 +494,8 @@ pub(super) struct StrategyAddOperationFormController {
     known_contracts: BTreeMap<String, DataContract>,
     supporting_contracts: BTreeMap<String, DataContract>,
     strategy_contract_names: StrategyContractNames,
+    /// Draws the random parameters of the operation
+    rng: StdRng,
 }
 
 impl StrategyAddOperationFormController {
@@ -360,6 +504,7 @@ impl StrategyAddOperationFormController {
         known_contracts: BTreeMap<String, DataContract>,
         supporting_contracts: BTreeMap<String, DataContract>,
         strategy_contract_names: StrategyContractNames,
+        rng: StdRng,
     ) -> Self {
         let operation_types = vec![
             "Document".to_string(),
@@ -379,6 +524,7 @@ impl StrategyAddOperationFormController {
             known_contracts,
             supporting_contracts,
             strategy_contract_names,
+            rng,
         }
     }
 
@@ -411,13 +557,17 @@ impl StrategyAddOperationFormController {
             OperationType::IdentityTransfer => Box::new(
                 StrategyOpIdentityTransferFormController::new(self.strategy_name.clone()),
             ),
-            OperationType::ContractCreateRandom => Box::new(
-                StrategyOpContractCreateFormController::new(self.strategy_name.clone()),
-            ),
+            OperationType::ContractCreateRandom => {
+                Box::new(StrategyOpContractCreateFormController::new(
+                    self.strategy_name.clone(),
+                    self.rng.clone(),
+                ))
+            }
             OperationType::ContractUpdateDocTypesRandom => {
                 Box::new(StrategyOpContractUpdateDocTypesFormController::new(
                     self.strategy_name.clone(),
                     self.known_contracts.clone(),
+                    self.rng.clone(),
                 ))
             } /* OperationType::ContractUpdateFieldsRandom => Box::new(
                *     StrategyOpContractUpdateNewFieldsFormController::new(self.strategy_name.
@@ -566,7 +716,6 @@ impl FormController for StrategyAutomaticDocumentsFormController {
                         fill_size,
                         fill_type,
                     )),
-                    block: false,
                 }
             }
             status => status.into(),
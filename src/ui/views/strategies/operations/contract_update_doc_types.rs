@@ -2,13 +2,8 @@
 
 use std::{cmp::min, collections::BTreeMap};
 
-use dpp::data_contract::{
-    document_type::v0::random_document_type::{
-        FieldMinMaxBounds, FieldTypeWeights, RandomDocumentTypeParameters,
-    },
-    DataContract,
-};
-use rand::Rng;
+use dpp::data_contract::DataContract;
+use rand::rngs::StdRng;
 use strategy_tests::{
     frequency::Frequency,
     operations::{
@@ -20,7 +15,7 @@ use tracing::error;
 use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 
 use crate::{
-    backend::{StrategyTask, Task},
+    backend::{strategies::random_document_type_parameters, StrategyTask, Task},
     ui::form::{ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput},
 };
 
@@ -32,12 +27,14 @@ pub(super) struct StrategyOpContractUpdateDocTypesFormController {
     )>,
     selected_strategy: String,
     known_contracts: BTreeMap<String, DataContract>,
+    rng: StdRng,
 }
 
 impl StrategyOpContractUpdateDocTypesFormController {
     pub(super) fn new(
         selected_strategy: String,
         known_contracts: BTreeMap<String, DataContract>,
+        rng: StdRng,
     ) -> Self {
         StrategyOpContractUpdateDocTypesFormController {
             input: ComposedInput::new((
@@ -56,53 +53,13 @@ impl StrategyOpContractUpdateDocTypesFormController {
             )),
             selected_strategy,
             known_contracts,
+            rng,
         }
     }
 }
 
 impl FormController for StrategyOpContractUpdateDocTypesFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
-        let random_number1 = rand::thread_rng().gen_range(3..=50);
-        let random_number2 = rand::thread_rng().gen_range(3..=50);
-        let random_number3 = rand::thread_rng().gen::<i64>() - 1000000;
-
-        let random_doc_type_parameters = RandomDocumentTypeParameters {
-            new_fields_optional_count_range: 1..random_number1,
-            new_fields_required_count_range: 1..random_number2,
-            new_indexes_count_range: 1..rand::thread_rng().gen_range(2..=10),
-            field_weights: FieldTypeWeights {
-                string_weight: rand::thread_rng().gen_range(1..=100),
-                float_weight: rand::thread_rng().gen_range(1..=100),
-                integer_weight: rand::thread_rng().gen_range(1..=100),
-                date_weight: rand::thread_rng().gen_range(1..=100),
-                boolean_weight: rand::thread_rng().gen_range(1..=100),
-                byte_array_weight: rand::thread_rng().gen_range(1..=100),
-            },
-            field_bounds: FieldMinMaxBounds {
-                string_min_len: 1..10,
-                string_has_min_len_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                string_max_len: 10..63,
-                string_has_max_len_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                integer_min: 1..10,
-                integer_has_min_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                integer_max: 10..10000,
-                integer_has_max_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                float_min: 0.1..10.0,
-                float_has_min_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                float_max: 10.0..1000.0,
-                float_has_max_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                date_min: random_number3,
-                date_max: random_number3 + 1000000,
-                byte_array_min_len: 1..10,
-                byte_array_has_min_len_chance: rand::thread_rng().gen_range(0.01..=1.0),
-                byte_array_max_len: 10..255,
-                byte_array_has_max_len_chance: rand::thread_rng().gen_range(0.01..=1.0),
-            },
-            keep_history_chance: rand::thread_rng().gen_range(0.01..=1.0),
-            documents_mutable_chance: rand::thread_rng().gen_range(0.01..=1.0),
-            documents_can_be_deleted_chance: rand::thread_rng().gen_range(0.01..=1.0),
-        };
-
         match self.input.on_event(event) {
             InputStatus::Done((contract_name, chance_per_block)) => {
                 // Retrieve the DataContract object by its name
@@ -113,7 +70,7 @@ impl FormController for StrategyOpContractUpdateDocTypesFormController {
                             operation: Operation {
                                 op_type: OperationType::ContractUpdate(DataContractUpdateOp {
                                     action: DataContractNewDocumentTypes(
-                                        random_doc_type_parameters,
+                                        random_document_type_parameters(&mut self.rng),
                                     ),
                                     contract: contract.clone(),
                                     document_type: None,
@@ -124,7 +81,6 @@ impl FormController for StrategyOpContractUpdateDocTypesFormController {
                                 },
                             },
                         }),
-                        block: false,
                     }
                 } else {
                     error!("No contract in known_contracts with that name");
@@ -247,7 +247,6 @@ impl FormController for DocumentTypeFormController {
                             },
                         },
                     }),
-                    block: false,
                 }
             }
             status => status.into(),
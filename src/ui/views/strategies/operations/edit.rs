@@ -0,0 +1,296 @@
+//! Form to edit an existing operation of a strategy in place.
+
+use dpp::data_contract::document_type::random_document::{
+    DocumentFieldFillSize, DocumentFieldFillType,
+};
+use strategy_tests::{
+    frequency::Frequency,
+    operations::{DocumentAction, IdentityUpdateOp, Operation, OperationType},
+};
+use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+
+use super::super::entries::Entry;
+use crate::{
+    backend::{StrategyTask, Task},
+    ui::form::{
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, SelectInput, TextInput,
+    },
+};
+
+type TimesInput = Field<TextInput<DefaultTextInputParser<u16>>>;
+type ChanceInput = Field<SelectInput<f64>>;
+
+/// Inputs depend on the kind of the operation, the frequency is always there.
+enum OperationEditInput {
+    Frequency(ComposedInput<(TimesInput, TimesInput, ChanceInput)>),
+    DocumentInsert(
+        ComposedInput<(
+            Field<SelectInput<String>>,
+            Field<SelectInput<String>>,
+            TimesInput,
+            TimesInput,
+            ChanceInput,
+        )>,
+    ),
+    /// Keys to add or disable, or document types of random contracts
+    Count(ComposedInput<(TimesInput, TimesInput, TimesInput, ChanceInput)>),
+}
+
+pub(in crate::ui::views::strategies) struct StrategyEditOperationFormController {
+    strategy_name: String,
+    operations: Vec<Operation>,
+    entry_input: SelectInput<Entry>,
+    /// Index of the selected operation and its inputs
+    edit: Option<(usize, OperationEditInput)>,
+}
+
+impl StrategyEditOperationFormController {
+    pub(in crate::ui::views::strategies) fn new(
+        strategy_name: String,
+        operations: Vec<Operation>,
+        entries: Vec<Entry>,
+    ) -> Self {
+        Self {
+            strategy_name,
+            operations,
+            entry_input: SelectInput::new(entries),
+            edit: None,
+        }
+    }
+}
+
+fn frequency_fields(frequency: &Frequency) -> (TimesInput, TimesInput, ChanceInput) {
+    let range = &frequency.times_per_block_range;
+    let max_times = if range.end > range.start {
+        range.end - 1
+    } else {
+        range.start
+    };
+
+    let chance = frequency.chance_per_block.unwrap_or(1.0);
+    let mut chances = vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1, 0.05, 0.01];
+    let chance_line = chances
+        .iter()
+        .position(|c| *c == chance)
+        .unwrap_or_else(|| {
+            chances.push(chance);
+            chances.len() - 1
+        });
+
+    (
+        Field::new(
+            "Times per block",
+            TextInput::new_init_value("Enter a whole number", range.start),
+        ),
+        Field::new(
+            "Max times per block",
+            TextInput::new_init_value("Enter a whole number", max_times),
+        ),
+        Field::new(
+            "Chance per block",
+            SelectInput::new_init_line(chances, chance_line),
+        ),
+    )
+}
+
+fn frequency(times_per_block: u16, max_times_per_block: u16, chance_per_block: f64) -> Frequency {
+    Frequency {
+        times_per_block_range: times_per_block
+            ..max_times_per_block.max(times_per_block).saturating_add(1),
+        chance_per_block: Some(chance_per_block),
+    }
+}
+
+fn edit_input(operation: &Operation) -> OperationEditInput {
+    let (times, max_times, chance) = frequency_fields(&operation.frequency);
+    let count = |name: &'static str, count| {
+        Field::new(
+            name,
+            TextInput::new_init_value("Enter a whole number", count),
+        )
+    };
+
+    match &operation.op_type {
+        OperationType::Document(op) => match &op.action {
+            DocumentAction::DocumentActionInsertRandom(fill_type, fill_size) => {
+                let fill_size_line = match fill_size {
+                    DocumentFieldFillSize::MinDocumentFillSize => 0,
+                    DocumentFieldFillSize::MaxDocumentFillSize => 1,
+                    DocumentFieldFillSize::AnyDocumentFillSize => 2,
+                };
+                let fill_type_line = match fill_type {
+                    DocumentFieldFillType::DoNotFillIfNotRequired => 0,
+                    DocumentFieldFillType::FillIfNotRequired => 1,
+                };
+                OperationEditInput::DocumentInsert(ComposedInput::new((
+                    Field::new(
+                        "How much data to populate the document with?",
+                        SelectInput::new_init_line(
+                            vec![
+                                "Minimum".to_string(),
+                                "Maximum".to_string(),
+                                "Random".to_string(),
+                            ],
+                            fill_size_line,
+                        ),
+                    ),
+                    Field::new(
+                        "Populate not-required fields?",
+                        SelectInput::new_init_line(
+                            vec!["No".to_string(), "Yes".to_string()],
+                            fill_type_line,
+                        ),
+                    ),
+                    times,
+                    max_times,
+                    chance,
+                )))
+            }
+            _ => OperationEditInput::Frequency(ComposedInput::new((times, max_times, chance))),
+        },
+        OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateAddKeys(keys)) => {
+            OperationEditInput::Count(ComposedInput::new((
+                count("How many keys to add", *keys),
+                times,
+                max_times,
+                chance,
+            )))
+        }
+        OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateDisableKey(keys)) => {
+            OperationEditInput::Count(ComposedInput::new((
+                count("How many keys to disable", *keys),
+                times,
+                max_times,
+                chance,
+            )))
+        }
+        OperationType::ContractCreate(_, document_types) => {
+            OperationEditInput::Count(ComposedInput::new((
+                count(
+                    "Number of document types",
+                    document_types.end.saturating_sub(1).max(1),
+                ),
+                times,
+                max_times,
+                chance,
+            )))
+        }
+        _ => OperationEditInput::Frequency(ComposedInput::new((times, max_times, chance))),
+    }
+}
+
+impl FormController for StrategyEditOperationFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        let Some((index, input)) = &mut self.edit else {
+            return match self.entry_input.on_event(event) {
+                InputStatus::Done(entry) => {
+                    let input = edit_input(&self.operations[entry.index]);
+                    self.edit = Some((entry.index, input));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            };
+        };
+
+        let operation = match input {
+            OperationEditInput::Frequency(input) => match input.on_event(event) {
+                InputStatus::Done((times, max_times, chance)) => {
+                    let mut operation = self.operations[*index].clone();
+                    operation.frequency = frequency(times, max_times, chance);
+                    operation
+                }
+                status => return status.into(),
+            },
+            OperationEditInput::DocumentInsert(input) => match input.on_event(event) {
+                InputStatus::Done((fill_size, fill_type, times, max_times, chance)) => {
+                    let mut operation = self.operations[*index].clone();
+                    let fill_size = match fill_size.as_str() {
+                        "Minimum" => DocumentFieldFillSize::MinDocumentFillSize,
+                        "Maximum" => DocumentFieldFillSize::MaxDocumentFillSize,
+                        _ => DocumentFieldFillSize::AnyDocumentFillSize,
+                    };
+                    let fill_type = match fill_type.as_str() {
+                        "Yes" => DocumentFieldFillType::FillIfNotRequired,
+                        _ => DocumentFieldFillType::DoNotFillIfNotRequired,
+                    };
+                    if let OperationType::Document(op) = &mut operation.op_type {
+                        op.action =
+                            DocumentAction::DocumentActionInsertRandom(fill_type, fill_size);
+                    }
+                    operation.frequency = frequency(times, max_times, chance);
+                    operation
+                }
+                status => return status.into(),
+            },
+            OperationEditInput::Count(input) => match input.on_event(event) {
+                InputStatus::Done((count, times, max_times, chance)) => {
+                    let mut operation = self.operations[*index].clone();
+                    match &mut operation.op_type {
+                        OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateAddKeys(
+                            keys,
+                        ))
+                        | OperationType::IdentityUpdate(
+                            IdentityUpdateOp::IdentityUpdateDisableKey(keys),
+                        ) => *keys = count,
+                        OperationType::ContractCreate(_, document_types) => {
+                            *document_types = 1..count.max(1) + 1
+                        }
+                        _ => {}
+                    }
+                    operation.frequency = frequency(times, max_times, chance);
+                    operation
+                }
+                status => return status.into(),
+            },
+        };
+
+        FormStatus::Done {
+            task: Task::Strategy(StrategyTask::EditOperation {
+                strategy_name: self.strategy_name.clone(),
+                index: *index,
+                operation,
+            }),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Edit strategy operation"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match &mut self.edit {
+            None => self.entry_input.view(frame, area),
+            Some((_, OperationEditInput::Frequency(input))) => input.view(frame, area),
+            Some((_, OperationEditInput::DocumentInsert(input))) => input.view(frame, area),
+            Some((_, OperationEditInput::Count(input))) => input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match &self.edit {
+            None => "Select operation",
+            Some((_, OperationEditInput::Frequency(input))) => input.step_name(),
+            Some((_, OperationEditInput::DocumentInsert(input))) => input.step_name(),
+            Some((_, OperationEditInput::Count(input))) => input.step_name(),
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match &self.edit {
+            None => 0,
+            Some((_, OperationEditInput::Frequency(input))) => input.step_index() + 1,
+            Some((_, OperationEditInput::DocumentInsert(input))) => input.step_index() + 1,
+            Some((_, OperationEditInput::Count(input))) => input.step_index() + 1,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        match &self.edit {
+            None => 1,
+            Some((_, OperationEditInput::Frequency(input))) => input.steps_number() + 1,
+            Some((_, OperationEditInput::DocumentInsert(input))) => input.steps_number() + 1,
+            Some((_, OperationEditInput::Count(input))) => input.steps_number() + 1,
+        }
+    }
+}
@@ -48,7 +48,6 @@ impl FormController for StrategyOpIdentityTopUpFormController {
                         },
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
@@ -39,7 +39,6 @@ impl FormController for StrategyOpIdentityTransferFormController {
                         },
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
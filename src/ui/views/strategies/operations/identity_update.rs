@@ -72,7 +72,6 @@ impl FormController for StrategyOpIdentityUpdateFormController {
                         },
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
@@ -39,7 +39,6 @@ impl FormController for StrategyOpIdentityWithdrawalFormController {
                         },
                     },
                 }),
-                block: false,
             },
             status => status.into(),
         }
@@ -0,0 +1,195 @@
+//! Form to assign the key of the loaded identity a strategy operation is signed
+//! with.
+
+use std::fmt::{self, Display};
+
+use dpp::identity::{
+    identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, IdentityPublicKey, KeyID,
+    Purpose, SecurityLevel,
+};
+use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+
+use super::super::entries::Entry;
+use crate::{
+    backend::{OperationKey, StrategyTask, Task},
+    ui::form::{ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput},
+};
+
+/// Key of the loaded identity as shown in selection inputs.
+#[derive(Clone)]
+struct IdentityKey {
+    id: KeyID,
+    purpose: Purpose,
+    security_level: SecurityLevel,
+}
+
+impl Display for IdentityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Key {}: {} {}",
+            self.id, self.security_level, self.purpose
+        )
+    }
+}
+
+const DEFAULT_KEY: &str = "Default key selection";
+const SPECIFIC_KEY: &str = "Specific key";
+const KEY_POLICY: &str = "Purpose and security level";
+
+enum Step {
+    SelectOperation(SelectInput<Entry>),
+    SelectKind(usize, SelectInput<String>),
+    SelectKey(usize, SelectInput<IdentityKey>),
+    SelectPolicy(
+        usize,
+        ComposedInput<(
+            Field<SelectInput<Purpose>>,
+            Field<SelectInput<SecurityLevel>>,
+        )>,
+    ),
+}
+
+pub(super) struct StrategyOperationKeyFormController {
+    strategy_name: String,
+    identity_keys: Vec<IdentityKey>,
+    step: Step,
+}
+
+impl StrategyOperationKeyFormController {
+    pub(super) fn new<'k>(
+        strategy_name: String,
+        operation_entries: Vec<Entry>,
+        identity_keys: impl IntoIterator<Item = &'k IdentityPublicKey>,
+    ) -> Self {
+        Self {
+            strategy_name,
+            identity_keys: identity_keys
+                .into_iter()
+                .filter(|key| key.disabled_at().is_none())
+                .map(|key| IdentityKey {
+                    id: key.id(),
+                    purpose: key.purpose(),
+                    security_level: key.security_level(),
+                })
+                .collect(),
+            step: Step::SelectOperation(SelectInput::new(operation_entries)),
+        }
+    }
+
+    fn done(&self, index: usize, key: Option<OperationKey>) -> FormStatus {
+        FormStatus::Done {
+            task: Task::Strategy(StrategyTask::SetOperationKey {
+                strategy_name: self.strategy_name.clone(),
+                index,
+                key,
+            }),
+        }
+    }
+}
+
+impl FormController for StrategyOperationKeyFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match &mut self.step {
+            Step::SelectOperation(input) => match input.on_event(event) {
+                InputStatus::Done(entry) => {
+                    let mut kinds = vec![DEFAULT_KEY.to_owned()];
+                    if !self.identity_keys.is_empty() {
+                        kinds.push(SPECIFIC_KEY.to_owned());
+                    }
+                    kinds.push(KEY_POLICY.to_owned());
+                    self.step = Step::SelectKind(entry.index, SelectInput::new(kinds));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            Step::SelectKind(index, input) => match input.on_event(event) {
+                InputStatus::Done(kind) => {
+                    let index = *index;
+                    self.step = match kind.as_str() {
+                        SPECIFIC_KEY => {
+                            Step::SelectKey(index, SelectInput::new(self.identity_keys.clone()))
+                        }
+                        KEY_POLICY => Step::SelectPolicy(
+                            index,
+                            ComposedInput::new((
+                                Field::new(
+                                    "Key purpose",
+                                    SelectInput::new(vec![
+                                        Purpose::AUTHENTICATION,
+                                        Purpose::TRANSFER,
+                                    ]),
+                                ),
+                                Field::new(
+                                    "Key security level",
+                                    SelectInput::new(SecurityLevel::full_range().into()),
+                                ),
+                            )),
+                        ),
+                        _ => return self.done(index, None),
+                    };
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            Step::SelectKey(index, input) => match input.on_event(event) {
+                InputStatus::Done(key) => {
+                    let index = *index;
+                    self.done(index, Some(OperationKey::Id(key.id)))
+                }
+                status => status.into(),
+            },
+            Step::SelectPolicy(index, input) => match input.on_event(event) {
+                InputStatus::Done((purpose, security_level)) => {
+                    let index = *index;
+                    self.done(
+                        index,
+                        Some(OperationKey::Policy {
+                            purpose,
+                            security_level,
+                        }),
+                    )
+                }
+                status => status.into(),
+            },
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Operation signing key"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match &mut self.step {
+            Step::SelectOperation(input) => input.view(frame, area),
+            Step::SelectKind(_, input) => input.view(frame, area),
+            Step::SelectKey(_, input) => input.view(frame, area),
+            Step::SelectPolicy(_, input) => input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match &self.step {
+            Step::SelectOperation(_) => "Select operation",
+            Step::SelectKind(..) => "Select how the key is chosen",
+            Step::SelectKey(..) => "Select identity key",
+            Step::SelectPolicy(_, input) => input.step_name(),
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match &self.step {
+            Step::SelectOperation(_) => 0,
+            Step::SelectKind(..) => 1,
+            Step::SelectKey(..) => 2,
+            Step::SelectPolicy(_, input) => input.step_index() + 2,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        match &self.step {
+            Step::SelectOperation(_) | Step::SelectKind(..) | Step::SelectKey(..) => 3,
+            Step::SelectPolicy(_, input) => input.steps_number() + 2,
+        }
+    }
+}
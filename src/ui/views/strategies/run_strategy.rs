@@ -2,12 +2,19 @@
 
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
-    tui::prelude::Rect,
+    props::{Alignment, BorderSides},
+    tui::{
+        prelude::{Constraint, Direction, Layout, Rect},
+        widgets::{Block, Sparkline},
+    },
     Frame,
 };
 
 use crate::{
-    backend::{AppState, BackendEvent, StrategyCompletionResult, StrategyTask, Task},
+    backend::{
+        AppState, BackendEvent, StrategyCompletionResult, StrategyProgress, StrategyRunStats,
+        StrategyTask, Task,
+    },
     ui::form::{
         parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
         InputStatus, SelectInput, TextInput,
@@ -21,15 +28,26 @@ use crate::{
 
 use super::selected_strategy::SelectedStrategyScreenController;
 
-const COMMAND_KEYS: [ScreenCommandKey; 2] = [
+const COMMAND_KEYS: [ScreenCommandKey; 3] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("r", "Rerun strategy"),
+    ScreenCommandKey::new("s", "Stop strategy"),
 ];
 
+/// Height of the throughput sparkline including borders.
+const SPARKLINE_HEIGHT: u16 = 8;
+
+/// Number of blocks (or seconds) of throughput history kept for the sparkline.
+const THROUGHPUT_HISTORY: usize = 500;
+
 pub(crate) struct RunStrategyScreenController {
     info: Info,
     strategy_running: bool,
     selected_strategy: Option<String>,
+    progress: Option<StrategyProgress>,
+    stop_requested: bool,
+    /// State transitions sent per block (or second) of the current run
+    throughput: Vec<u64>,
 }
 
 impl_builder!(RunStrategyScreenController);
@@ -53,10 +71,65 @@ impl RunStrategyScreenController {
             info,
             strategy_running,
             selected_strategy,
+            progress: None,
+            stop_requested: false,
+            throughput: Vec::new(),
         }
     }
 }
 
+fn progress_text(progress: &StrategyProgress) -> String {
+    let step = if progress.index <= 2 {
+        format!("Initialization block {} of 2", progress.index)
+    } else if progress.block_mode {
+        format!("Block {} of {}", progress.index - 2, progress.total)
+    } else {
+        format!("Second {} of {}", progress.index - 2, progress.total)
+    };
+    let credits_spent = progress
+        .credits_spent
+        .map(|credits| credits.to_string())
+        .unwrap_or_else(|| "unknown yet".to_owned());
+
+    format!(
+        "Strategy '{}' is running.\n\n{}\nBlock height: {}\nState transitions sent: {}\nState \
+         transitions succeeded: {}\nState transitions failed: {}\nCredits spent (Identity): {}",
+        progress.strategy_name,
+        step,
+        progress.block_height,
+        progress.transition_count,
+        progress.success_count,
+        progress.error_count,
+        credits_spent,
+    )
+}
+
+fn stats_text(stats: &StrategyRunStats) -> String {
+    let mode = match stats.block_mode {
+        true => String::from("block"),
+        false => String::from("time"),
+    };
+    format!(
+        "Mode: {}\nState transitions attempted: {}\nState transitions succeeded: {}\nNumber of \
+         blocks (or loops): {}\nRun time: {}s\nInitialization time: {}\nAttempted rate (approx): \
+         {} tx/s\nSuccessful rate: {} tx/s\nSuccess percentage: {}%\nDash spent (Identity): \
+         {}\nDash spent (Wallet): {}\nIdentity registrations: {}",
+        mode,
+        stats.transition_count,
+        stats.success_count,
+        // -2 so we don't count the 2 init blocks
+        (stats.final_block_height - stats.start_block_height).saturating_sub(2),
+        stats.run_time.as_secs(),
+        stats.init_time.as_secs(),
+        stats.rate,
+        stats.success_rate,
+        stats.success_percent,
+        stats.dash_spent_identity,
+        stats.dash_spent_wallet,
+        stats.identity_registrations,
+    )
+}
+
 impl ScreenController for RunStrategyScreenController {
     fn name(&self) -> &'static str {
         "Run strategy"
@@ -81,71 +154,93 @@ impl ScreenController for RunStrategyScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => {
                 self.strategy_running = true;
+                self.progress = None;
+                self.stop_requested = false;
+                self.throughput.clear();
                 ScreenFeedback::Form(Box::new(RunStrategyFormController::new(
                     self.selected_strategy
                         .clone()
                         .expect("No selected strategy available"),
                 )))
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.strategy_running && !self.stop_requested => {
+                self.stop_requested = true;
+                ScreenFeedback::Task {
+                    task: Task::Strategy(StrategyTask::StopStrategy(
+                        self.selected_strategy
+                            .clone()
+                            .expect("No selected strategy available"),
+                    )),
+                }
+            }
             Event::Backend(BackendEvent::StrategyCompleted {
                 strategy_name,
                 result,
+                report_file,
+                ..
             }) => {
                 self.strategy_running = false;
+                self.stop_requested = false;
 
                 let display_text = match result {
-                    StrategyCompletionResult::Success {
-                        block_mode,
-                        final_block_height,
-                        start_block_height,
-                        success_count,
-                        transition_count,
-                        rate,
-                        success_rate,
-                        success_percent,
-                        run_time,
-                        init_time,
-                        dash_spent_identity,
-                        dash_spent_wallet,
-                    } => {
-                        let mode = match block_mode {
-                            true => String::from("block"),
-                            false => String::from("time"),
-                        };
+                    StrategyCompletionResult::Success(stats) => {
                         format!(
-                            "Strategy '{}' completed:\n\nMode: {}\nState transitions attempted: {}\nState \
-                             transitions succeeded: {}\nNumber of blocks (or loops): {}\nRun time: \
-                             {}s\nInitialization time: {}\nAttempted rate (approx): {} tx/s\nSuccessful rate: {} tx/s\nSuccess percentage: {}%\nDash spent (Identity): {}\nDash spent (Wallet): {}",
+                            "Strategy '{}' completed:\n\n{}",
                             strategy_name,
-                            mode,
-                            transition_count,
-                            success_count,
-                            (final_block_height - start_block_height - 2), // -2 so we don't count the 2 init blocks
-                            run_time.as_secs(),
-                            init_time.as_secs(),
-                            rate,
-                            success_rate,
-                            success_percent,
-                            dash_spent_identity,
-                            dash_spent_wallet,
+                            stats_text(stats)
                         )
                     }
                     StrategyCompletionResult::PartiallyCompleted {
                         reached_block_height,
                         reason,
+                        stats,
                     } => {
                         format!(
-                            "Strategy '{}' failed to complete. Reached block height {}. Reason: {}",
-                            strategy_name, reached_block_height, reason
+                            "Strategy '{}' didn't complete. Reached block height {}. Reason: {}\n\n{}",
+                            strategy_name,
+                            reached_block_height,
+                            reason,
+                            stats_text(stats)
                         )
                     }
                 };
 
-                self.info = Info::new_fixed(&display_text);
+                let report_line = match report_file {
+                    Some(path) => format!("Report saved to {}", path.display()),
+                    None => "Unable to save the report, see the log for details".to_owned(),
+                };
+
+                self.info = Info::new_fixed(&format!("{}\n\n{}", display_text, report_line));
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::DryRunStrategy { .. }),
+                execution_result,
+            }) => {
+                self.strategy_running = false;
+                self.stop_requested = false;
+
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::StrategyProgress(progress)) => {
+                // The first block starts a new run
+                if progress.index == 1 {
+                    self.throughput.clear();
+                }
+                if self.throughput.len() == THROUGHPUT_HISTORY {
+                    self.throughput.remove(0);
+                }
+                self.throughput.push(progress.step_transitions);
+                self.progress = Some(progress.clone());
                 ScreenFeedback::Redraw
             }
             Event::Backend(BackendEvent::StrategyError { error }) => {
                 self.strategy_running = false;
+                self.stop_requested = false;
 
                 self.info = Info::new_error(&format!("Strategy error: {}", &error));
                 ScreenFeedback::Redraw
@@ -156,9 +251,43 @@ impl ScreenController for RunStrategyScreenController {
 
     fn view(&mut self, frame: &mut Frame, area: Rect) {
         if self.strategy_running {
-            self.info = Info::new_fixed("Strategy is running, please wait.");
+            let mut text = match &self.progress {
+                Some(progress) => progress_text(progress),
+                None => "Strategy is running, please wait.".to_owned(),
+            };
+            if self.stop_requested {
+                text.push_str("\n\nStopping after the current block, please wait.");
+            }
+            self.info = Info::new_fixed(&text);
+        }
+
+        if self.throughput.is_empty() {
+            self.info.view(frame, area);
+            return;
         }
-        self.info.view(frame, area)
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(SPARKLINE_HEIGHT)].as_ref())
+            .split(area);
+
+        self.info.view(frame, layout[0]);
+
+        let unit = match &self.progress {
+            Some(StrategyProgress {
+                block_mode: false, ..
+            }) => "second",
+            _ => "block",
+        };
+        let block = Block::new()
+            .borders(BorderSides::ALL)
+            .title(format!("State transitions per {}", unit))
+            .title_alignment(Alignment::Left);
+        // Only the most recent values that fit into the sparkline are shown
+        let width = layout[1].width.saturating_sub(2) as usize;
+        let data = &self.throughput[self.throughput.len().saturating_sub(width)..];
+
+        frame.render_widget(Sparkline::default().block(block).data(data), layout[1]);
     }
 }
 
@@ -212,8 +341,8 @@ impl FormController for RunStrategyFormController {
                                     num_blocks,
                                     true,
                                     true,
+                                    None,
                                 )),
-                                block: true,
                             }
                         } else {
                             // time-based with proofs
@@ -223,8 +352,8 @@ impl FormController for RunStrategyFormController {
                                     num_blocks,
                                     true,
                                     false,
+                                    None,
                                 )),
-                                block: true,
                             }
                         }
                     } else {
@@ -236,8 +365,8 @@ impl FormController for RunStrategyFormController {
                                     num_blocks,
                                     false,
                                     true,
+                                    None,
                                 )),
-                                block: true,
                             }
                         } else {
                             // time-based without proofs
@@ -247,8 +376,8 @@ impl FormController for RunStrategyFormController {
                                     num_blocks,
                                     false,
                                     false,
+                                    None,
                                 )),
-                                block: true,
                             }
                         }
                     }
@@ -282,3 +411,67 @@ impl FormController for RunStrategyFormController {
         4
     }
 }
+
+pub(super) struct DryRunStrategyFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u64>>>,
+        Field<TextInput<DefaultTextInputParser<String>>>,
+    )>,
+    selected_strategy: String,
+}
+
+impl DryRunStrategyFormController {
+    pub(super) fn new(selected_strategy: String) -> Self {
+        DryRunStrategyFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Number of blocks to generate state transitions for",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Directory to write the state transitions to",
+                    TextInput::new("Leave empty to not write them"),
+                ),
+            )),
+            selected_strategy,
+        }
+    }
+}
+
+impl FormController for DryRunStrategyFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((blocks, dump_dir)) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::DryRunStrategy {
+                    strategy_name: self.selected_strategy.clone(),
+                    blocks,
+                    dump_dir: Some(dump_dir.trim())
+                        .filter(|dump_dir| !dump_dir.is_empty())
+                        .map(Into::into),
+                    seed: None,
+                }),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Dry run strategy"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        2
+    }
+}
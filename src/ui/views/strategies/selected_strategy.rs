@@ -20,7 +20,8 @@ use tuirealm::{
 
 use super::{
     identity_inserts::IdentityInsertsScreenController, operations::OperationsScreenController,
-    run_strategy::RunStrategyFormController, run_strategy::RunStrategyScreenController,
+    run_strategy::DryRunStrategyFormController, run_strategy::RunStrategyFormController,
+    run_strategy::RunStrategyScreenController,
     start_contracts::ContractsWithUpdatesScreenController,
     start_identities::StartIdentitiesScreenController,
 };
@@ -34,20 +35,24 @@ use crate::{
 };
 
 use crate::{
-    backend::{StrategyTask, Task},
+    backend::{strategies::DEFINITIONS_DIR, StrategyTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus, TextInput,
+        parsers::{DefaultTextInputParser, OptionalTextInputParser},
+        FormController, FormStatus, Input, InputStatus, TextInput,
     },
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 7] = [
+const COMMAND_KEYS: [ScreenCommandKey; 10] = [
     ScreenCommandKey::new("q", "Back to Strategies"),
     ScreenCommandKey::new("r", "Run strategy"),
+    ScreenCommandKey::new("d", "Dry run strategy"),
     ScreenCommandKey::new("l", "Clone this strategy"),
     ScreenCommandKey::new("c", "Start contracts"),
     ScreenCommandKey::new("i", "Identity inserts"),
     ScreenCommandKey::new("o", "Operations"),
     ScreenCommandKey::new("s", "Start identities"),
+    ScreenCommandKey::new("w", "Save as definition file"),
+    ScreenCommandKey::new("e", "Set run seed"),
 ];
 
 const COMMAND_KEYS_NO_SELECTION: [ScreenCommandKey; 1] =
@@ -57,6 +62,8 @@ pub struct SelectedStrategyScreenController {
     info: Info,
     available_strategies: Vec<String>,
     selected_strategy: Option<String>,
+    /// Seeds stored for the strategies
+    seeds: BTreeMap<String, u64>,
 }
 
 impl_builder!(SelectedStrategyScreenController);
@@ -64,16 +71,18 @@ impl_builder!(SelectedStrategyScreenController);
 impl SelectedStrategyScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
         let available_strategies_lock = app_state.available_strategies.lock().await;
+        let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
         let selected_strategy_lock = app_state.selected_strategy.lock().await;
+        let seeds = app_state.available_strategies_seeds.lock().await.clone();
 
         let info = if let Some(name) = selected_strategy_lock.as_ref() {
             let strategy = available_strategies_lock
                 .get(name.as_str())
                 .expect("inconsistent data");
-            let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
 
             Info::new_fixed(&display_strategy(
                 &name,
+                seeds.get(name).copied(),
                 strategy,
                 contract_names_lock
                     .get(name.as_str())
@@ -87,6 +96,7 @@ impl SelectedStrategyScreenController {
             info,
             available_strategies: available_strategies_lock.keys().cloned().collect(),
             selected_strategy: None,
+            seeds,
         }
     }
 }
@@ -139,10 +149,41 @@ impl ScreenController for SelectedStrategyScreenController {
                 )),
                 screen: RunStrategyScreenController::builder(),
             },
+            Event::Key(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => match &self.selected_strategy {
+                Some(strategy_name) => ScreenFeedback::FormThenNextScreen {
+                    form: Box::new(DryRunStrategyFormController::new(strategy_name.clone())),
+                    screen: RunStrategyScreenController::builder(),
+                },
+                None => ScreenFeedback::None,
+            },
             Event::Key(KeyEvent {
                 code: Key::Char('l'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Form(Box::new(CloneStrategyFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('w'),
+                modifiers: KeyModifiers::NONE,
+            }) => match &self.selected_strategy {
+                Some(strategy_name) => ScreenFeedback::Form(Box::new(
+                    SaveStrategyDefinitionFormController::new(strategy_name.clone()),
+                )),
+                None => ScreenFeedback::None,
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => match &self.selected_strategy {
+                Some(strategy_name) => {
+                    ScreenFeedback::Form(Box::new(StrategySeedFormController::new(
+                        strategy_name.clone(),
+                        self.seeds.get(strategy_name).copied(),
+                    )))
+                }
+                None => ScreenFeedback::None,
+            },
             Event::Backend(
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name,
@@ -157,6 +198,7 @@ impl ScreenController for SelectedStrategyScreenController {
             ) => {
                 self.info = Info::new_fixed(&display_strategy(
                     &strategy_name,
+                    self.seeds.get(strategy_name.as_str()).copied(),
                     &strategy,
                     &contract_names,
                 ));
@@ -177,6 +219,28 @@ impl ScreenController for SelectedStrategyScreenController {
                 self.info = Info::new_error(&format!("Error: {}", &error));
                 ScreenFeedback::Redraw
             }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::SaveStrategyDefinition { .. }),
+                execution_result,
+            }) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Strategy(StrategyTask::SetStrategySeed {
+                        strategy_name,
+                        seed,
+                    }),
+                execution_result,
+            }) => {
+                match seed {
+                    Some(seed) => self.seeds.insert(strategy_name.clone(), *seed),
+                    None => self.seeds.remove(strategy_name),
+                };
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
             _ => ScreenFeedback::None,
         }
     }
@@ -188,6 +252,7 @@ impl ScreenController for SelectedStrategyScreenController {
 
 fn display_strategy(
     strategy_name: &str,
+    seed: Option<u64>,
     strategy: &Strategy,
     contract_updates: &[(String, Option<BTreeMap<u64, String>>)],
 ) -> String {
@@ -319,9 +384,13 @@ fn display_strategy(
 
     let start_contracts_len = strategy.start_contracts.len();
     let operations_len = strategy.operations.len();
+    let seed = seed
+        .map(|seed| seed.to_string())
+        .unwrap_or_else(|| "random every run".to_owned());
 
     format!(
         r#"{strategy_name}:
+    Seed: {seed}
     Start identities: {} (Keys: {}, Balance: {:.2} dash)
     
     Start contracts ({start_contracts_len}):
@@ -354,7 +423,6 @@ impl FormController for CloneStrategyFormController {
         match self.input.on_event(event) {
             InputStatus::Done(strategy_name) => FormStatus::Done {
                 task: Task::Strategy(StrategyTask::CloneStrategy(strategy_name)),
-                block: false,
             },
             status => status.into(),
         }
@@ -380,3 +448,106 @@ impl FormController for CloneStrategyFormController {
         1
     }
 }
+
+pub(crate) struct SaveStrategyDefinitionFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+    strategy_name: String,
+}
+
+impl SaveStrategyDefinitionFormController {
+    pub(crate) fn new(strategy_name: String) -> Self {
+        Self {
+            input: TextInput::new_init_value(
+                "Path ending with .toml or .json",
+                format!("{}/{}.toml", DEFINITIONS_DIR, strategy_name),
+            ),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for SaveStrategyDefinitionFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::SaveStrategyDefinition {
+                    strategy_name: self.strategy_name.clone(),
+                    path: path.into(),
+                }),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Save strategy definition"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Path"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+pub(crate) struct StrategySeedFormController {
+    input: TextInput<OptionalTextInputParser<u64>>,
+    strategy_name: String,
+}
+
+impl StrategySeedFormController {
+    pub(crate) fn new(strategy_name: String, seed: Option<u64>) -> Self {
+        Self {
+            input: TextInput::new_str_value_with_parser(
+                OptionalTextInputParser::new(),
+                "Leave empty for a random seed every run",
+                &seed.map(|seed| seed.to_string()).unwrap_or_default(),
+            ),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for StrategySeedFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(seed) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::SetStrategySeed {
+                    strategy_name: self.strategy_name.clone(),
+                    seed,
+                }),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Strategy run seed"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Seed"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
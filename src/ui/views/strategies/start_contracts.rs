@@ -1,9 +1,13 @@
 //! Start contracts screen and forms.
 
+mod updates;
+
 use std::collections::BTreeMap;
 
 use walkdir::WalkDir;
 
+use self::updates::{planned_updates, ContractUpdatesFormController};
+use super::entries::{entries, Entry, EntryAction, EntryActionFormController, EntryList};
 use crate::{
     backend::{AppState, AppStateUpdate, BackendEvent, StrategyTask, Task},
     ui::form::{
@@ -30,10 +34,15 @@ use dpp::{
 };
 use strategy_tests::Strategy;
 
-const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const COMMAND_KEYS: [ScreenCommandKey; 10] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("s", "Add specific"),
     ScreenCommandKey::new("x", "Add x random"),
+    ScreenCommandKey::new("e", "Edit"),
+    ScreenCommandKey::new("d", "Delete"),
+    ScreenCommandKey::new("u", "Duplicate"),
+    ScreenCommandKey::new("m", "Move"),
+    ScreenCommandKey::new("t", "Plan updates"),
     ScreenCommandKey::new("r", "Remove last"),
     ScreenCommandKey::new("c", "Clear all"),
 ];
@@ -56,11 +65,11 @@ impl_builder!(ContractsWithUpdatesScreenController);
 impl ContractsWithUpdatesScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
         let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
         let known_contracts_lock = app_state.known_contracts.lock().await;
         let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
         let strategy_contract_names_lock =
             app_state.available_strategies_contract_names.lock().await;
+        let selected_strategy_lock = app_state.selected_strategy.lock().await;
 
         let (info_text, current_strategy, current_start_contracts) =
             if let Some(selected_strategy_name) = &*selected_strategy_lock {
@@ -109,6 +118,38 @@ impl ContractsWithUpdatesScreenController {
         }
     }
 
+    /// Numbered start contracts of the selected strategy, `None` if there
+    /// are none to pick from.
+    fn contract_entries(&self) -> Option<(String, Vec<Entry>)> {
+        let strategy_name = self.strategy_name.clone()?;
+        let contract_names = self.strategy_contract_names.get(&strategy_name)?;
+        if contract_names.is_empty() {
+            return None;
+        }
+        let contract_entries = entries(contract_names.iter().map(|(name, updates)| {
+            match updates.as_ref().map(|updates| updates.len()) {
+                Some(updates_count) if updates_count > 0 => {
+                    format!("{} ({} updates)", name, updates_count)
+                }
+                _ => name.clone(),
+            }
+        }));
+        Some((strategy_name, contract_entries))
+    }
+
+    fn entry_action_form(&self, action: EntryAction) -> ScreenFeedback {
+        if let Some((strategy_name, contract_entries)) = self.contract_entries() {
+            ScreenFeedback::Form(Box::new(EntryActionFormController::new(
+                strategy_name,
+                EntryList::Contracts,
+                action,
+                contract_entries,
+            )))
+        } else {
+            ScreenFeedback::None
+        }
+    }
+
     fn update_supporting_contracts_sync(&mut self) {
         // Use block_in_place to wait for the async operation to complete
         tokio::task::block_in_place(|| {
@@ -174,6 +215,61 @@ impl ScreenController for ContractsWithUpdatesScreenController {
                     ScreenFeedback::None
                 }
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some((strategy_name, contract_entries)) = self.contract_entries() {
+                    self.update_supporting_contracts_sync();
+
+                    ScreenFeedback::Form(Box::new(EditContractFormController::new(
+                        strategy_name,
+                        contract_entries,
+                        self.known_contracts.clone(),
+                        self.supporting_contracts.clone(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Remove),
+            Event::Key(KeyEvent {
+                code: Key::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Duplicate),
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.entry_action_form(EntryAction::Move),
+            Event::Key(KeyEvent {
+                code: Key::Char('t'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some((strategy_name, contract_entries)) = self.contract_entries() {
+                    self.update_supporting_contracts_sync();
+
+                    let updates = self.strategy_contract_names[&strategy_name]
+                        .iter()
+                        .map(|(_, updates)| {
+                            planned_updates(updates)
+                                .into_iter()
+                                .map(|(block, name)| (block, name.clone()))
+                                .collect()
+                        })
+                        .collect();
+                    ScreenFeedback::Form(Box::new(ContractUpdatesFormController::new(
+                        strategy_name,
+                        contract_entries,
+                        updates,
+                        self.supporting_contracts.keys().cloned().collect(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
             Event::Key(KeyEvent {
                 code: Key::Char('r'),
                 modifiers: KeyModifiers::NONE,
@@ -183,7 +279,6 @@ impl ScreenController for ContractsWithUpdatesScreenController {
                         task: Task::Strategy(StrategyTask::RemoveLastContract(
                             strategy_name.clone(),
                         )),
-                        block: false,
                     }
                 } else {
                     ScreenFeedback::None
@@ -196,7 +291,6 @@ impl ScreenController for ContractsWithUpdatesScreenController {
                 if let Some(strategy_name) = &self.strategy_name {
                     ScreenFeedback::Task {
                         task: Task::Strategy(StrategyTask::ClearContracts(strategy_name.clone())),
-                        block: false,
                     }
                 } else {
                     ScreenFeedback::None
@@ -239,23 +333,29 @@ impl ScreenController for ContractsWithUpdatesScreenController {
                     let mut start_contracts_lines = String::new();
                     start_contracts_lines.push_str(&format!("Strategy: {}\n", strategy_name));
                     start_contracts_lines.push_str("Start contracts:\n");
-                    for (contract_name, updates) in start_contracts {
+                    for (index, (contract_name, updates)) in start_contracts.iter().enumerate() {
                         start_contracts_lines.push_str(&format!(
-                            "{:indent$}Contract: {}\n",
+                            "{:indent$}{}. Contract: {}\n",
                             "",
+                            index + 1,
                             contract_name,
                             indent = 0
                         ));
-                        if let Some(updates_map) = updates {
-                            for (block, update) in updates_map {
-                                start_contracts_lines.push_str(&format!(
-                                    "{:indent$}On block {} apply {}\n",
-                                    "",
-                                    block * 3,
-                                    update,
-                                    indent = 4
-                                ));
-                            }
+                        let timeline = planned_updates(updates);
+                        for (position, (block, update)) in timeline.iter().enumerate() {
+                            let branch = if position + 1 == timeline.len() {
+                                "└"
+                            } else {
+                                "├"
+                            };
+                            start_contracts_lines.push_str(&format!(
+                                "{:indent$}{} block +{}: update to {}\n",
+                                "",
+                                branch,
+                                block,
+                                update,
+                                indent = 4
+                            ));
                         }
                     }
                     start_contracts_lines
@@ -277,6 +377,8 @@ pub(super) struct ContractsWithUpdatesFormController {
     known_contracts: BTreeMap<String, DataContract>,
     supporting_contracts: BTreeMap<String, DataContract>,
     selected_contract_names: Vec<String>,
+    /// Start contract to replace instead of adding a new one
+    replace_index: Option<usize>,
     input: ComposedInput<(Field<SelectInput<String>>, Field<SelectInput<String>>)>,
 }
 
@@ -306,6 +408,7 @@ impl ContractsWithUpdatesFormController {
             known_contracts,
             supporting_contracts,
             selected_contract_names: Vec::new(),
+            replace_index: None,
             input: ComposedInput::new((
                 Field::new("Select Contract", SelectInput::new(contract_names)),
                 Field::new(
@@ -351,13 +454,20 @@ impl FormController for ContractsWithUpdatesFormController {
                         ),
                     ));
                     FormStatus::Redraw
+                } else if let Some(index) = self.replace_index {
+                    FormStatus::Done {
+                        task: Task::Strategy(StrategyTask::EditContract {
+                            strategy_name: self.selected_strategy.clone(),
+                            index,
+                            contract_names: self.selected_contract_names.clone(),
+                        }),
+                    }
                 } else {
                     FormStatus::Done {
                         task: Task::Strategy(StrategyTask::SetStartContracts(
                             self.selected_strategy.clone(),
                             self.selected_contract_names.clone(),
                         )),
-                        block: false,
                     }
                 }
             }
@@ -386,6 +496,91 @@ impl FormController for ContractsWithUpdatesFormController {
     }
 }
 
+/// Picks a start contract, then its contract and updates are selected again
+/// like for a new one.
+pub(super) struct EditContractFormController {
+    entry_input: SelectInput<Entry>,
+    contracts_form: Option<ContractsWithUpdatesFormController>,
+    selected_strategy: String,
+    known_contracts: BTreeMap<String, DataContract>,
+    supporting_contracts: BTreeMap<String, DataContract>,
+}
+
+impl EditContractFormController {
+    pub(super) fn new(
+        selected_strategy: String,
+        contract_entries: Vec<Entry>,
+        known_contracts: BTreeMap<String, DataContract>,
+        supporting_contracts: BTreeMap<String, DataContract>,
+    ) -> Self {
+        Self {
+            entry_input: SelectInput::new(contract_entries),
+            contracts_form: None,
+            selected_strategy,
+            known_contracts,
+            supporting_contracts,
+        }
+    }
+}
+
+impl FormController for EditContractFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        if let Some(form) = &mut self.contracts_form {
+            return form.on_event(event);
+        }
+
+        match self.entry_input.on_event(event) {
+            InputStatus::Done(entry) => {
+                let mut form = ContractsWithUpdatesFormController::new(
+                    self.selected_strategy.clone(),
+                    self.known_contracts.clone(),
+                    self.supporting_contracts.clone(),
+                );
+                form.replace_index = Some(entry.index);
+                self.contracts_form = Some(form);
+                FormStatus::Redraw
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Edit start contract"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(form) = &mut self.contracts_form {
+            form.step_view(frame, area)
+        } else {
+            self.entry_input.view(frame, area)
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        if let Some(form) = &self.contracts_form {
+            form.step_name()
+        } else {
+            "Select start contract"
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        if let Some(form) = &self.contracts_form {
+            form.step_index() + 1
+        } else {
+            0
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        if let Some(form) = &self.contracts_form {
+            form.steps_number() + 1
+        } else {
+            1
+        }
+    }
+}
+
 pub(super) struct RandomContractsFormController {
     selected_strategy: String,
     input: ComposedInput<(
@@ -440,7 +635,6 @@ impl FormController for RandomContractsFormController {
                     selected_contract_name,
                     variants_count,
                 )),
-                block: false,
             },
             status => status.into(),
         }
@@ -0,0 +1,234 @@
+//! Form to plan, move or remove the scheduled updates of a start contract.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+
+use super::super::entries::Entry;
+use crate::{
+    backend::{ContractUpdateEdit, StrategyTask, Task, CONTRACT_UPDATE_BLOCK_INTERVAL},
+    ui::form::{
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, SelectInput, TextInput,
+    },
+};
+
+type BlockInput = Field<TextInput<DefaultTextInputParser<u64>>>;
+
+/// Update planned at a block offset, as shown in selection inputs.
+#[derive(Clone)]
+struct PlannedUpdate {
+    block: u64,
+    contract_name: String,
+}
+
+impl Display for PlannedUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Block {}: {}", self.block, self.contract_name)
+    }
+}
+
+/// Planned updates of a start contract in block order.
+pub(super) fn planned_updates(updates: &Option<BTreeMap<u64, String>>) -> Vec<(u64, &String)> {
+    updates
+        .iter()
+        .flatten()
+        .map(|(slot, name)| (slot * CONTRACT_UPDATE_BLOCK_INTERVAL, name))
+        .collect()
+}
+
+enum Step {
+    SelectContract(SelectInput<Entry>),
+    SelectAction(usize, SelectInput<String>),
+    Add(
+        usize,
+        ComposedInput<(BlockInput, Field<SelectInput<String>>)>,
+    ),
+    Move(
+        usize,
+        ComposedInput<(Field<SelectInput<PlannedUpdate>>, BlockInput)>,
+    ),
+    Remove(usize, SelectInput<PlannedUpdate>),
+}
+
+pub(super) struct ContractUpdatesFormController {
+    strategy_name: String,
+    /// Planned updates of every start contract, by position
+    updates: Vec<Vec<PlannedUpdate>>,
+    supporting_contract_names: Vec<String>,
+    step: Step,
+}
+
+impl ContractUpdatesFormController {
+    pub(super) fn new(
+        strategy_name: String,
+        contract_entries: Vec<Entry>,
+        updates: Vec<Vec<(u64, String)>>,
+        supporting_contract_names: Vec<String>,
+    ) -> Self {
+        Self {
+            strategy_name,
+            updates: updates
+                .into_iter()
+                .map(|updates| {
+                    updates
+                        .into_iter()
+                        .map(|(block, contract_name)| PlannedUpdate {
+                            block,
+                            contract_name,
+                        })
+                        .collect()
+                })
+                .collect(),
+            supporting_contract_names,
+            step: Step::SelectContract(SelectInput::new(contract_entries)),
+        }
+    }
+
+    fn done(&self, index: usize, edit: ContractUpdateEdit) -> FormStatus {
+        FormStatus::Done {
+            task: Task::Strategy(StrategyTask::EditContractUpdates {
+                strategy_name: self.strategy_name.clone(),
+                index,
+                edit,
+            }),
+        }
+    }
+}
+
+impl FormController for ContractUpdatesFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match &mut self.step {
+            Step::SelectContract(input) => match input.on_event(event) {
+                InputStatus::Done(entry) => {
+                    let mut actions = vec!["Add update".to_owned()];
+                    if !self.updates[entry.index].is_empty() {
+                        actions.push("Move update".to_owned());
+                        actions.push("Remove update".to_owned());
+                    }
+                    self.step = Step::SelectAction(entry.index, SelectInput::new(actions));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            Step::SelectAction(index, input) => match input.on_event(event) {
+                InputStatus::Done(action) => {
+                    let index = *index;
+                    let planned = self.updates[index].clone();
+                    self.step = match action.as_str() {
+                        "Move update" => Step::Move(
+                            index,
+                            ComposedInput::new((
+                                Field::new("Update to move", SelectInput::new(planned)),
+                                Field::new(
+                                    "New block offset",
+                                    TextInput::new("Enter a multiple of 3"),
+                                ),
+                            )),
+                        ),
+                        "Remove update" => Step::Remove(index, SelectInput::new(planned)),
+                        _ => Step::Add(
+                            index,
+                            ComposedInput::new((
+                                Field::new(
+                                    "Block offset from the contract creation",
+                                    TextInput::new("Enter a multiple of 3"),
+                                ),
+                                Field::new(
+                                    "Contract variant",
+                                    SelectInput::new(self.supporting_contract_names.clone()),
+                                ),
+                            )),
+                        ),
+                    };
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            Step::Add(index, input) => match input.on_event(event) {
+                InputStatus::Done((block, contract_name)) => {
+                    let index = *index;
+                    self.done(
+                        index,
+                        ContractUpdateEdit::Set {
+                            block,
+                            contract_name,
+                        },
+                    )
+                }
+                status => status.into(),
+            },
+            Step::Move(index, input) => match input.on_event(event) {
+                InputStatus::Done((update, to_block)) => {
+                    let index = *index;
+                    self.done(
+                        index,
+                        ContractUpdateEdit::Move {
+                            from_block: update.block,
+                            to_block,
+                        },
+                    )
+                }
+                status => status.into(),
+            },
+            Step::Remove(index, input) => match input.on_event(event) {
+                InputStatus::Done(update) => {
+                    let index = *index;
+                    self.done(
+                        index,
+                        ContractUpdateEdit::Remove {
+                            block: update.block,
+                        },
+                    )
+                }
+                status => status.into(),
+            },
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Start contract updates"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match &mut self.step {
+            Step::SelectContract(input) => input.view(frame, area),
+            Step::SelectAction(_, input) => input.view(frame, area),
+            Step::Add(_, input) => input.view(frame, area),
+            Step::Move(_, input) => input.view(frame, area),
+            Step::Remove(_, input) => input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match &self.step {
+            Step::SelectContract(_) => "Select start contract",
+            Step::SelectAction(..) => "Select action",
+            Step::Add(_, input) => input.step_name(),
+            Step::Move(_, input) => input.step_name(),
+            Step::Remove(..) => "Update to remove",
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match &self.step {
+            Step::SelectContract(_) => 0,
+            Step::SelectAction(..) => 1,
+            Step::Add(_, input) => input.step_index() + 2,
+            Step::Move(_, input) => input.step_index() + 2,
+            Step::Remove(..) => 2,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        match &self.step {
+            Step::SelectContract(_) | Step::SelectAction(..) => 3,
+            Step::Add(_, input) => input.steps_number() + 2,
+            Step::Move(_, input) => input.steps_number() + 2,
+            Step::Remove(..) => 3,
+        }
+    }
+}
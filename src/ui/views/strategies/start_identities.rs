@@ -113,7 +113,6 @@ impl ScreenController for StartIdentitiesScreenController {
                 task: Task::Strategy(StrategyTask::RemoveStartIdentities(
                     self.strategy_name.clone().unwrap(),
                 )),
-                block: false,
             },
             Event::Backend(BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                 strategy_name,
@@ -223,7 +222,6 @@ impl FormController for StrategyStartIdentitiesFormController {
                             balance: 10_000_000,
                             add_transfer_key: true,
                         }),
-                        block: false,
                     }
                 } else {
                     FormStatus::Done {
@@ -234,7 +232,6 @@ impl FormController for StrategyStartIdentitiesFormController {
                             balance: 10_000_000,
                             add_transfer_key: false,
                         }),
-                        block: false,
                     }
                 }
             }
@@ -285,7 +282,6 @@ impl FormController for StrategyStartIdentitiesBalanceFormController {
                     self.selected_strategy.clone(),
                     (balance * 100000000.0) as u64,
                 )),
-                block: false,
             },
             status => status.into(),
         }
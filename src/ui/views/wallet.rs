@@ -71,6 +71,10 @@ fn join_commands(
         }
     } else {
         commands.push(ScreenCommandKey::new("a", "Add wallet by private key"));
+        commands.push(ScreenCommandKey::new(
+            "h",
+            "Add HD wallet (mnemonic or xprv)",
+        ));
     }
     commands.leak()
 }
@@ -105,7 +109,6 @@ impl FormController for RegisterIdentityFormController {
                 task: Task::Identity(IdentityTask::RegisterIdentity(
                     (amount * 100000000.0) as u64,
                 )),
-                block: true,
             },
             status => status.into(),
         }
@@ -149,7 +152,6 @@ impl FormController for TopUpIdentityFormController {
         match self.input.on_event(event) {
             InputStatus::Done(amount) => FormStatus::Done {
                 task: Task::Identity(IdentityTask::TopUpIdentity((amount * 100000000.0) as u64)),
-                block: true,
             },
             InputStatus::Redraw => FormStatus::Redraw,
             InputStatus::None => FormStatus::None,
@@ -197,7 +199,6 @@ impl FormController for WithdrawFromIdentityFormController {
                 task: Task::Identity(IdentityTask::WithdrawFromIdentity(
                     (amount * 100000000.0) as u64,
                 )),
-                block: true,
             },
             InputStatus::Redraw => FormStatus::Redraw,
             InputStatus::None => FormStatus::None,
@@ -328,12 +329,18 @@ impl ScreenController for WalletScreenController {
                 ScreenFeedback::Form(Box::new(AddWalletPrivateKeyFormController::new()))
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('h'),
+                modifiers: KeyModifiers::NONE,
+            }) if !self.wallet_loaded => {
+                ScreenFeedback::Form(Box::new(AddHDWalletFormController::new()))
+            }
+
             Event::Key(KeyEvent {
                 code: Key::Char('b'),
                 modifiers: KeyModifiers::NONE,
             }) if self.wallet_loaded => ScreenFeedback::Task {
                 task: Task::Wallet(WalletTask::Refresh),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -341,7 +348,6 @@ impl ScreenController for WalletScreenController {
                 modifiers: KeyModifiers::NONE,
             }) if self.identity_loaded => ScreenFeedback::Task {
                 task: Task::Identity(IdentityTask::Refresh),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -371,7 +377,6 @@ impl ScreenController for WalletScreenController {
                 modifiers: KeyModifiers::NONE,
             }) if self.wallet_loaded => ScreenFeedback::Task {
                 task: Task::Wallet(WalletTask::CopyAddress),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -379,7 +384,6 @@ impl ScreenController for WalletScreenController {
                 modifiers: KeyModifiers::NONE,
             }) if self.identity_loaded => ScreenFeedback::Task {
                 task: Task::Identity(IdentityTask::CopyIdentityId),
-                block: true,
             },
 
             Event::Key(KeyEvent {
@@ -396,7 +400,6 @@ impl ScreenController for WalletScreenController {
                 self.identity_loaded = false;
                 ScreenFeedback::Task {
                     task: Task::Identity(IdentityTask::ClearLoadedIdentity),
-                    block: false,
                 }
             }
 
@@ -407,7 +410,6 @@ impl ScreenController for WalletScreenController {
                 self.wallet_loaded = false;
                 ScreenFeedback::Task {
                     task: Task::Wallet(WalletTask::ClearLoadedWallet),
-                    block: false,
                 }
             }
 
@@ -512,7 +514,6 @@ impl FormController for AddWalletPrivateKeyFormController {
         match self.input.on_event(event) {
             InputStatus::Done(private_key) => FormStatus::Done {
                 task: Task::Wallet(WalletTask::AddByPrivateKey(private_key)),
-                block: false,
             },
             status => status.into(),
         }
@@ -539,6 +540,49 @@ impl FormController for AddWalletPrivateKeyFormController {
     }
 }
 
+struct AddHDWalletFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl AddHDWalletFormController {
+    fn new() -> Self {
+        AddHDWalletFormController {
+            input: TextInput::new("BIP39 mnemonic phrase or xprv"),
+        }
+    }
+}
+
+impl FormController for AddHDWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(mnemonic_or_xprv) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::AddHDWallet(mnemonic_or_xprv)),
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add HD wallet"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Mnemonic or xprv"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
 struct SplitUTXOsFormController {
     input: TextInput<DefaultTextInputParser<u32>>,
 }
@@ -556,7 +600,6 @@ impl FormController for SplitUTXOsFormController {
         match self.input.on_event(event) {
             InputStatus::Done(count) => FormStatus::Done {
                 task: Task::Wallet(WalletTask::SplitUTXOs(count)),
-                block: true,
             },
             status => status.into(),
         }
@@ -595,5 +638,17 @@ fn display_wallet(wallet: &Wallet) -> String {
             let utxo_count = single_key_wallet.utxos.len();
             format!("{}\nNumber of UTXOs: {}", description, utxo_count)
         }
+        Wallet::HDWallet(hd_wallet) => {
+            let description = format!(
+                "HD Wallet\nReceive Address: {} (#{})\nChange Address: {} (#{})\nBalance: {}",
+                hd_wallet.receive_address(),
+                hd_wallet.receive_index,
+                hd_wallet.change_address(),
+                hd_wallet.change_index,
+                hd_wallet.balance_dash_formatted()
+            );
+            let utxo_count = hd_wallet.utxos.len();
+            format!("{}\nNumber of UTXOs: {}", description, utxo_count)
+        }
     }
 }
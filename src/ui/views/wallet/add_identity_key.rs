@@ -92,7 +92,6 @@ impl FormController for AddIdentityKeyFormController {
                             .purpose_result
                             .expect("must be selected on previous steps"),
                     }),
-                    block: true,
                 },
                 input => input.into(),
             },